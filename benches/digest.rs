@@ -1,7 +1,7 @@
 use halo2::{
     arithmetic::FieldExt,
     circuit::{Layouter, SimpleFloorPlanner},
-    pasta::EqAffine,
+    pasta::{EqAffine, Fp},
     plonk::{
         create_proof, keygen_pk, keygen_vk, verify_proof, Circuit, ConstraintSystem, Error,
         VerifyingKey,
@@ -34,7 +34,8 @@ pub(crate) fn bench(name: &str, k: u32, c: &mut Criterion) {
         }
 
         fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
-            Table16Chip::configure(meta)
+            let instance = meta.instance_column();
+            Table16Chip::configure_with_instance(meta, instance)
         }
 
         fn synthesize(
@@ -47,22 +48,22 @@ pub(crate) fn bench(name: &str, k: u32, c: &mut Criterion) {
 
             // Test vector: "abc"
             let test_input = [
-                BlockWord(Some(0b01100001011000100110001110000000)),
-                BlockWord(Some(0b00000000000000000000000000000000)),
-                BlockWord(Some(0b00000000000000000000000000000000)),
-                BlockWord(Some(0b00000000000000000000000000000000)),
-                BlockWord(Some(0b00000000000000000000000000000000)),
-                BlockWord(Some(0b00000000000000000000000000000000)),
-                BlockWord(Some(0b00000000000000000000000000000000)),
-                BlockWord(Some(0b00000000000000000000000000000000)),
-                BlockWord(Some(0b00000000000000000000000000000000)),
-                BlockWord(Some(0b00000000000000000000000000000000)),
-                BlockWord(Some(0b00000000000000000000000000000000)),
-                BlockWord(Some(0b00000000000000000000000000000000)),
-                BlockWord(Some(0b00000000000000000000000000000000)),
-                BlockWord(Some(0b00000000000000000000000000000000)),
-                BlockWord(Some(0b00000000000000000000000000000000)),
-                BlockWord(Some(0b00000000000000000000000000011000)),
+                BlockWord::new(Some(0b01100001011000100110001110000000)),
+                BlockWord::new(Some(0b00000000000000000000000000000000)),
+                BlockWord::new(Some(0b00000000000000000000000000000000)),
+                BlockWord::new(Some(0b00000000000000000000000000000000)),
+                BlockWord::new(Some(0b00000000000000000000000000000000)),
+                BlockWord::new(Some(0b00000000000000000000000000000000)),
+                BlockWord::new(Some(0b00000000000000000000000000000000)),
+                BlockWord::new(Some(0b00000000000000000000000000000000)),
+                BlockWord::new(Some(0b00000000000000000000000000000000)),
+                BlockWord::new(Some(0b00000000000000000000000000000000)),
+                BlockWord::new(Some(0b00000000000000000000000000000000)),
+                BlockWord::new(Some(0b00000000000000000000000000000000)),
+                BlockWord::new(Some(0b00000000000000000000000000000000)),
+                BlockWord::new(Some(0b00000000000000000000000000000000)),
+                BlockWord::new(Some(0b00000000000000000000000000000000)),
+                BlockWord::new(Some(0b00000000000000000000000000011000)),
             ];
 
             // Create a message of length 31 blocks
@@ -71,9 +72,17 @@ pub(crate) fn bench(name: &str, k: u32, c: &mut Criterion) {
                 input.extend_from_slice(&test_input);
             }
 
-            Sha256::digest(table16_chip, layouter.namespace(|| "'abc' * 2"), &input)?;
-
-            Ok(())
+            let digest = Sha256::digest(
+                table16_chip.clone(),
+                layouter.namespace(|| "'abc' * 31"),
+                &input,
+            )?;
+            Sha256::constrain_digest_public(
+                &table16_chip,
+                layouter.namespace(|| "public digest"),
+                &digest,
+                0,
+            )
         }
     }
 
@@ -121,6 +130,16 @@ pub(crate) fn bench(name: &str, k: u32, c: &mut Criterion) {
 
     let circuit: MyCircuit = MyCircuit {};
 
+    // The digest of the message, as public inputs
+    #[allow(clippy::unreadable_literal)]
+    let digest: Vec<Fp> = [
+        0x135a7b87, 0xef248227, 0xdb973e96, 0x8974d769, 0xe17968e7, 0xc5e1e7ab, 0xec632331,
+        0x1c515fca,
+    ]
+    .iter()
+    .map(|word| Fp::from_u64(*word))
+    .collect();
+
     // let prover_name = name.to_string() + "-prover";
     let verifier_name = name.to_string() + "-verifier";
 
@@ -138,7 +157,7 @@ pub(crate) fn bench(name: &str, k: u32, c: &mut Criterion) {
     let proof_path = Path::new("./benches/sha256_assets/sha256_proof");
     if File::open(&proof_path).is_err() {
         let mut transcript = Blake2bWrite::<_, _, Challenge255<_>>::init(vec![]);
-        create_proof(&params, &pk, &[circuit], &[], &mut transcript)
+        create_proof(&params, &pk, &[circuit], &[&[&digest[..]]], &mut transcript)
             .expect("proof generation should not fail");
         let proof: Vec<u8> = transcript.finalize();
         let mut file = File::create(&proof_path).expect("Failed to create sha256_proof");
@@ -151,11 +170,28 @@ pub(crate) fn bench(name: &str, k: u32, c: &mut Criterion) {
         .read_to_end(&mut proof)
         .expect("Couldn't read proof");
 
+    eprintln!(
+        "{}: k = {}, vk {} bytes, proof {} bytes",
+        name,
+        k,
+        std::fs::metadata(&vk_path)
+            .expect("couldn't stat sha256_vk")
+            .len(),
+        proof.len()
+    );
+
     c.bench_function(&verifier_name, |b| {
         b.iter(|| {
             let msm = params.empty_msm();
             let mut transcript = Blake2bRead::<_, _, Challenge255<_>>::init(&proof[..]);
-            let guard = verify_proof(&params, pk.get_vk(), msm, &[], &mut transcript).unwrap();
+            let guard = verify_proof(
+                &params,
+                pk.get_vk(),
+                msm,
+                &[&[&digest[..]]],
+                &mut transcript,
+            )
+            .unwrap();
             let msm = guard.clone().use_challenges();
             assert!(msm.eval());
         });
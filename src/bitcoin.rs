@@ -0,0 +1,220 @@
+//! Gadget for verifying the proof of work of a [Bitcoin block header].
+//!
+//! The block hash is the SHA256d digest of the 80-byte header with its bytes reversed,
+//! and must not exceed the target encoded in compact form by the header's `nBits` field.
+//!
+//! [Bitcoin block header]: https://developer.bitcoin.org/reference/block_chain.html#block-headers
+
+use std::convert::TryInto;
+
+use halo2::{arithmetic::FieldExt, circuit::Layouter, plonk::Error};
+
+use crate::{Sha256, Sha256Digest, Sha256Instructions, DIGEST_SIZE};
+
+/// The size of a Bitcoin block header, in bytes.
+pub const HEADER_SIZE: usize = 80;
+
+// The offset of the little-endian `nBits` field in a block header
+const BITS_OFFSET: usize = 72;
+
+/// The set of circuit instructions required to use [`verify_header`], in addition to
+/// those of the [`Sha256`] gadget.
+pub trait BitcoinHeaderInstructions<F: FieldExt>: Sha256Instructions<F> + Clone {
+    /// Constrains the given digest, read as a little-endian integer, to be at most the
+    /// target encoded in compact form by `bits`, given little-endian: the low three bytes
+    /// are the mantissa, and the high byte is the exponent in bytes.
+    fn check_target(
+        &self,
+        layouter: &mut impl Layouter<F>,
+        hash: &[Self::DigestWord; DIGEST_SIZE],
+        bits: [Self::Byte; 4],
+    ) -> Result<(), Error>;
+}
+
+/// Verifies the proof of work of a block header, given as bytes. Returns the SHA256d
+/// digest of the header; the block hash is its bytes in reverse order.
+///
+/// # Panics
+///
+/// Panics if `header` is not [`HEADER_SIZE`] bytes long.
+///
+/// With [`Table16Chip`](crate::Table16Chip), the chip must be configured with
+/// [`Table16Config::with_bitcoin_target`](crate::Table16Config::with_bitcoin_target).
+pub fn verify_header<F, HeaderChip>(
+    chip: HeaderChip,
+    mut layouter: impl Layouter<F>,
+    header: &[HeaderChip::Byte],
+) -> Result<Sha256Digest<HeaderChip::DigestWord>, Error>
+where
+    F: FieldExt,
+    HeaderChip: BitcoinHeaderInstructions<F>,
+    HeaderChip::BlockWord: From<HeaderChip::DigestWord>,
+{
+    assert_eq!(header.len(), HEADER_SIZE);
+
+    let words = header
+        .chunks(4)
+        .map(|word| {
+            chip.pack_bytes(
+                &mut layouter.namespace(|| "pack header"),
+                word.try_into().expect("word.len() == 4"),
+            )
+        })
+        .collect::<Result<Vec<_>, Error>>()?;
+    let digest = Sha256::sha256d(chip.clone(), layouter.namespace(|| "sha256d"), &words)?;
+
+    let bits = header[BITS_OFFSET..BITS_OFFSET + 4]
+        .try_into()
+        .expect("bits.len() == 4");
+    chip.check_target(
+        &mut layouter.namespace(|| "check target"),
+        digest.words(),
+        bits,
+    )?;
+
+    Ok(digest)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{verify_header, HEADER_SIZE};
+    use crate::{Byte, CellValue8, Table16Chip, Table16Config};
+    use halo2::{
+        arithmetic::FieldExt,
+        circuit::{Layouter, SimpleFloorPlanner},
+        dev::MockProver,
+        pasta::Fp,
+        plonk::{Advice, Circuit, Column, ConstraintSystem, Error},
+    };
+
+    // The genesis block, whose target has exponent 0x1d
+    const GENESIS: &str = "0100000000000000000000000000000000000000000000000000000000000000000000003ba3edfd7a7b12b27ac72c3e67768f617fc81bc3888a51323a9fb8aa4b1e5e4a29ab5f49ffff001d1dac2b7c";
+    // Block 125552, whose target has exponent 0x1a
+    const BLOCK_125552: &str = "0100000081cd02ab7e569e8bcd9317e2fe99f2de44d49ab2b8851ba4a308000000000000e320b6c2fffc8d750423db8b1eb942ae710e951ed797f7affc8892b0f1fc122bc7f5d74df2b9441a42a14695";
+
+    fn from_hex(hex: &str) -> Vec<Option<u8>> {
+        (0..hex.len())
+            .step_by(2)
+            .map(|idx| Some(u8::from_str_radix(&hex[idx..idx + 2], 16).unwrap()))
+            .collect()
+    }
+
+    struct MyCircuit {
+        headers: Vec<Vec<Option<u8>>>,
+        // The expected SHA256d digests of the headers, if they are known
+        expected: Vec<[u32; 8]>,
+    }
+
+    impl<F: FieldExt> Circuit<F> for MyCircuit {
+        type Config = (Table16Config, Column<Advice>);
+        type FloorPlanner = SimpleFloorPlanner;
+
+        fn without_witnesses(&self) -> Self {
+            MyCircuit {
+                headers: vec![vec![None; HEADER_SIZE]; self.headers.len()],
+                expected: vec![],
+            }
+        }
+
+        fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+            let bytes = meta.advice_column();
+            meta.enable_equality(bytes.into());
+
+            (
+                Table16Chip::configure(meta).with_bitcoin_target(meta),
+                bytes,
+            )
+        }
+
+        fn synthesize(
+            &self,
+            config: Self::Config,
+            mut layouter: impl Layouter<F>,
+        ) -> Result<(), Error> {
+            let (config, bytes) = config;
+            Table16Chip::<F>::load(config.clone(), &mut layouter)?;
+
+            for (idx, header) in self.headers.iter().enumerate() {
+                assert_eq!(header.len(), HEADER_SIZE);
+
+                // Assign the header bytes in a column outside the chip.
+                let header = layouter.assign_region(
+                    || format!("header {}", idx),
+                    |mut region| {
+                        header
+                            .iter()
+                            .enumerate()
+                            .map(|(row, byte)| {
+                                let var = region.assign_advice(
+                                    || format!("byte {}", row),
+                                    bytes,
+                                    row,
+                                    || {
+                                        byte.map(|byte| F::from_u64(byte as u64))
+                                            .ok_or(Error::SynthesisError)
+                                    },
+                                )?;
+                                Ok(Byte::Assigned(CellValue8::new(var, *byte)))
+                            })
+                            .collect::<Result<Vec<_>, Error>>()
+                    },
+                )?;
+
+                let digest = verify_header(
+                    Table16Chip::<F>::construct(config.clone()),
+                    layouter.namespace(|| format!("verify header {}", idx)),
+                    &header,
+                )?;
+                if let Some(expected) = self.expected.get(idx) {
+                    for (word, expected) in digest.words().iter().zip(expected.iter()) {
+                        assert_eq!(word.value().unwrap(), *expected);
+                    }
+                }
+            }
+
+            Ok(())
+        }
+    }
+
+    #[test]
+    #[allow(clippy::unreadable_literal)]
+    fn header_pow() {
+        let circuit = MyCircuit {
+            headers: vec![from_hex(GENESIS), from_hex(BLOCK_125552)],
+            expected: vec![
+                [
+                    0x6fe28c0a, 0xb6f1b372, 0xc1a6a246, 0xae63f74f, 0x931e8365, 0xe15a089c,
+                    0x68d61900, 0x00000000,
+                ],
+                [
+                    0x1dbd981f, 0xe6985776, 0xb644b173, 0xa4d0385d, 0xdc1aa2a8, 0x29688d1e,
+                    0x00000000, 0x00000000,
+                ],
+            ],
+        };
+
+        let prover = match MockProver::<Fp>::run(17, &circuit, vec![]) {
+            Ok(prover) => prover,
+            Err(e) => panic!("{:?}", e),
+        };
+        assert_eq!(prover.verify(), Ok(()));
+    }
+
+    #[test]
+    fn header_pow_insufficient() {
+        // Lowering the exponent of the genesis target changes the block hash, which
+        // then exceeds the target.
+        let mut header = from_hex(GENESIS);
+        header[75] = Some(0x1c);
+        let circuit = MyCircuit {
+            headers: vec![header],
+            expected: vec![],
+        };
+
+        let prover = match MockProver::<Fp>::run(17, &circuit, vec![]) {
+            Ok(prover) => prover,
+            Err(e) => panic!("{:?}", e),
+        };
+        assert!(prover.verify().is_err());
+    }
+}
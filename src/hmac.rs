@@ -0,0 +1,326 @@
+//! Gadget for [HMAC-SHA256], built on the SHA-256 chips.
+//!
+//! HMAC(K, m) = H((K' ⊕ opad) || H((K' ⊕ ipad) || m)), where K' is the key zero-padded to
+//! a block, or the digest of the key if it is longer than a block.
+//!
+//! [HMAC-SHA256]: https://tools.ietf.org/html/rfc2104
+
+use std::convert::TryInto;
+
+use halo2::{arithmetic::FieldExt, circuit::Layouter, plonk::Error};
+
+use crate::{Sha256, Sha256Digest, Sha256Instructions, BLOCK_SIZE};
+
+/// The inner pad, the byte 0x36 repeated in each byte of a word.
+const IPAD: u32 = 0x3636_3636;
+/// The outer pad, the byte 0x5c repeated in each byte of a word.
+const OPAD: u32 = 0x5c5c_5c5c;
+
+/// The set of circuit instructions required to use the [`HmacSha256`] gadget, in addition
+/// to those of the [`Sha256`] gadget.
+pub trait HmacSha256Instructions<F: FieldExt>: Sha256Instructions<F> + Clone {
+    /// Returns the XOR of each of the given words with `constant`, constrained in the
+    /// spread form of the words.
+    fn xor_constant(
+        &self,
+        layouter: &mut impl Layouter<F>,
+        words: &[Self::BlockWord],
+        constant: u32,
+    ) -> Result<Vec<Self::BlockWord>, Error>;
+
+    /// Returns a block word equal to the given digest word, so that a digest can be hashed.
+    fn digest_word_to_block_word(word: Self::DigestWord) -> Self::BlockWord;
+}
+
+/// A gadget that constrains an HMAC-SHA256 invocation, with a key given as bytes. The
+/// message supports input at a granularity of 32 bits, or of 8 bits through
+/// [`HmacSha256::update_bytes`].
+///
+/// With [`Table16Chip`](crate::Table16Chip), the chip must be configured with
+/// [`Table16Config::with_hmac`](crate::Table16Config::with_hmac).
+#[derive(Debug)]
+pub struct HmacSha256<F: FieldExt, CS: HmacSha256Instructions<F>> {
+    chip: CS,
+    inner: Sha256<F, CS>,
+    outer_key: Vec<CS::BlockWord>,
+}
+
+impl<F: FieldExt, HmacChip: HmacSha256Instructions<F>> HmacSha256<F, HmacChip> {
+    /// Create a new MAC instance with the given key.
+    pub fn new(
+        chip: HmacChip,
+        mut layouter: impl Layouter<F>,
+        key: &[HmacChip::Byte],
+    ) -> Result<Self, Error> {
+        let mut key = if key.len() > BLOCK_SIZE * 4 {
+            // Keys longer than a block are hashed first.
+            let mut hasher = Sha256::new(chip.clone(), layouter.namespace(|| "key init"))?;
+            hasher.update_bytes(layouter.namespace(|| "key update"), key)?;
+            let digest = hasher.finalize(layouter.namespace(|| "key finalize"))?;
+            digest
+                .words()
+                .iter()
+                .map(|word| HmacChip::digest_word_to_block_word(*word))
+                .collect()
+        } else {
+            // Otherwise the key is zero-padded to whole words.
+            let mut key = key.to_vec();
+            while key.len() % 4 != 0 {
+                key.push(HmacChip::constant_byte(0));
+            }
+            key.chunks(4)
+                .map(|word| {
+                    chip.pack_bytes(
+                        &mut layouter.namespace(|| "pack key"),
+                        word.try_into().expect("word.len() == 4"),
+                    )
+                })
+                .collect::<Result<Vec<_>, Error>>()?
+        };
+        key.resize(BLOCK_SIZE, HmacChip::constant_word(0));
+
+        let inner_key = chip.xor_constant(&mut layouter.namespace(|| "ipad"), &key, IPAD)?;
+        let outer_key = chip.xor_constant(&mut layouter.namespace(|| "opad"), &key, OPAD)?;
+
+        let mut inner = Sha256::new(chip.clone(), layouter.namespace(|| "inner init"))?;
+        inner.update(layouter.namespace(|| "inner key"), &inner_key)?;
+
+        Ok(HmacSha256 {
+            chip,
+            inner,
+            outer_key,
+        })
+    }
+
+    /// Digest data, updating the internal state. See [`Sha256::update`].
+    pub fn update(
+        &mut self,
+        layouter: impl Layouter<F>,
+        data: &[HmacChip::BlockWord],
+    ) -> Result<(), Error> {
+        self.inner.update(layouter, data)
+    }
+
+    /// Digest data given as bytes, updating the internal state. See
+    /// [`Sha256::update_bytes`].
+    pub fn update_bytes(
+        &mut self,
+        layouter: impl Layouter<F>,
+        data: &[HmacChip::Byte],
+    ) -> Result<(), Error> {
+        self.inner.update_bytes(layouter, data)
+    }
+
+    /// Retrieve the MAC and consume the instance.
+    pub fn finalize(
+        self,
+        mut layouter: impl Layouter<F>,
+    ) -> Result<Sha256Digest<HmacChip::DigestWord>, Error> {
+        let inner_digest = self
+            .inner
+            .finalize(layouter.namespace(|| "inner finalize"))?;
+        let inner_digest: Vec<_> = inner_digest
+            .words()
+            .iter()
+            .map(|word| HmacChip::digest_word_to_block_word(*word))
+            .collect();
+
+        let mut outer = Sha256::new(self.chip, layouter.namespace(|| "outer init"))?;
+        outer.update(layouter.namespace(|| "outer key"), &self.outer_key)?;
+        outer.update(layouter.namespace(|| "inner digest"), &inner_digest)?;
+        outer.finalize(layouter.namespace(|| "outer finalize"))
+    }
+
+    /// Convenience function to compute the MAC of the data. It will handle instance
+    /// creation, data feeding and finalization.
+    pub fn mac(
+        chip: HmacChip,
+        mut layouter: impl Layouter<F>,
+        key: &[HmacChip::Byte],
+        data: &[HmacChip::Byte],
+    ) -> Result<Sha256Digest<HmacChip::DigestWord>, Error> {
+        let mut mac = Self::new(chip, layouter.namespace(|| "init"), key)?;
+        mac.update_bytes(layouter.namespace(|| "update"), data)?;
+        mac.finalize(layouter.namespace(|| "finalize"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::HmacSha256;
+    use crate::{Byte, CellValue8, DigestWord, Table16Chip, Table16Config, DIGEST_SIZE};
+    use halo2::{
+        arithmetic::FieldExt,
+        circuit::{Layouter, SimpleFloorPlanner},
+        dev::MockProver,
+        pasta::Fp,
+        plonk::{Advice, Circuit, Column, ConstraintSystem, Error},
+    };
+
+    // RFC 4231, section 4: key, data and HMAC-SHA256 of each test case
+    #[allow(clippy::unreadable_literal)]
+    fn test_cases() -> Vec<(Vec<u8>, Vec<u8>, [u32; DIGEST_SIZE])> {
+        vec![
+            (
+                vec![0x0b; 20],
+                b"Hi There".to_vec(),
+                [
+                    0xb0344c61, 0xd8db3853, 0x5ca8afce, 0xaf0bf12b, 0x881dc200, 0xc9833da7,
+                    0x26e9376c, 0x2e32cff7,
+                ],
+            ),
+            (
+                b"Jefe".to_vec(),
+                b"what do ya want for nothing?".to_vec(),
+                [
+                    0x5bdcc146, 0xbf60754e, 0x6a042426, 0x089575c7, 0x5a003f08, 0x9d273983,
+                    0x9dec58b9, 0x64ec3843,
+                ],
+            ),
+            (
+                vec![0xaa; 20],
+                vec![0xdd; 50],
+                [
+                    0x773ea91e, 0x36800e46, 0x854db8eb, 0xd09181a7, 0x2959098b, 0x3ef8c122,
+                    0xd9635514, 0xced565fe,
+                ],
+            ),
+            (
+                (1..=25).collect(),
+                vec![0xcd; 50],
+                [
+                    0x82558a38, 0x9a443c0e, 0xa4cc8198, 0x99f2083a, 0x85f0faa3, 0xe578f807,
+                    0x7a2e3ff4, 0x6729665b,
+                ],
+            ),
+            // Test case 5 truncates the output to 128 bits; we check all of it.
+            (
+                vec![0x0c; 20],
+                b"Test With Truncation".to_vec(),
+                [
+                    0xa3b61674, 0x73100ee0, 0x6e0c796c, 0x2955552b, 0xfa6f7c0a, 0x6a8aef8b,
+                    0x93f860aa, 0xb0cd20c5,
+                ],
+            ),
+            (
+                vec![0xaa; 131],
+                b"Test Using Larger Than Block-Size Key - Hash Key First".to_vec(),
+                [
+                    0x60e43159, 0x1ee0b67f, 0x0d8a26aa, 0xcbf5b77f, 0x8e0bc621, 0x3728c514,
+                    0x0546040f, 0x0ee37f54,
+                ],
+            ),
+            (
+                vec![0xaa; 131],
+                b"This is a test using a larger than block-size key and a larger than block-size data. The key needs to be hashed before being used by the HMAC algorithm.".to_vec(),
+                [
+                    0x9b09ffa7, 0x1b942fcb, 0x27635fbc, 0xd5b0e944, 0xbfdc6364, 0x4f071393,
+                    0x8a7f5153, 0x5c3a35e2,
+                ],
+            ),
+        ]
+    }
+
+    struct MyCircuit {
+        // The indices of the test cases to run
+        cases: Vec<usize>,
+    }
+
+    impl<F: FieldExt> Circuit<F> for MyCircuit {
+        type Config = (Table16Config, Column<Advice>);
+        type FloorPlanner = SimpleFloorPlanner;
+
+        fn without_witnesses(&self) -> Self {
+            MyCircuit {
+                cases: self.cases.clone(),
+            }
+        }
+
+        fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+            let bytes = meta.advice_column();
+            meta.enable_equality(bytes.into());
+
+            (Table16Chip::configure(meta).with_hmac(meta), bytes)
+        }
+
+        fn synthesize(
+            &self,
+            config: Self::Config,
+            mut layouter: impl Layouter<F>,
+        ) -> Result<(), Error> {
+            let (config, bytes) = config;
+            Table16Chip::<F>::load(config.clone(), &mut layouter)?;
+
+            // Assign the key and data bytes in a column outside the chip.
+            let mut assign_bytes = |name: String, value: &[u8]| {
+                layouter.assign_region(
+                    || name.clone(),
+                    |mut region| {
+                        value
+                            .iter()
+                            .enumerate()
+                            .map(|(row, byte)| {
+                                let var = region.assign_advice(
+                                    || format!("byte {}", row),
+                                    bytes,
+                                    row,
+                                    || Ok(F::from_u64(*byte as u64)),
+                                )?;
+                                Ok(Byte::Assigned(CellValue8::new(var, Some(*byte))))
+                            })
+                            .collect::<Result<Vec<_>, Error>>()
+                    },
+                )
+            };
+
+            let cases = test_cases();
+            let mut inputs = Vec::with_capacity(self.cases.len());
+            for idx in self.cases.iter() {
+                let (key, data, _) = &cases[*idx];
+                inputs.push((
+                    assign_bytes(format!("key {}", idx), key)?,
+                    assign_bytes(format!("data {}", idx), data)?,
+                ));
+            }
+
+            for (idx, (key, data)) in self.cases.iter().zip(inputs.iter()) {
+                let digest: [DigestWord; DIGEST_SIZE] = *HmacSha256::mac(
+                    Table16Chip::<F>::construct(config.clone()),
+                    layouter.namespace(|| format!("test case {}", idx + 1)),
+                    key,
+                    data,
+                )?
+                .words();
+                for (word, expected) in digest.iter().zip(cases[*idx].2.iter()) {
+                    assert_eq!(word.value().unwrap(), *expected);
+                }
+            }
+
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn rfc_4231() {
+        let circuit = MyCircuit {
+            cases: vec![0, 1, 2, 3, 4],
+        };
+
+        let prover = match MockProver::<Fp>::run(17, &circuit, vec![]) {
+            Ok(prover) => prover,
+            Err(e) => panic!("{:?}", e),
+        };
+        assert_eq!(prover.verify(), Ok(()));
+    }
+
+    #[test]
+    fn rfc_4231_long_key() {
+        let circuit = MyCircuit { cases: vec![5, 6] };
+
+        let prover = match MockProver::<Fp>::run(17, &circuit, vec![]) {
+            Ok(prover) => prover,
+            Err(e) => panic!("{:?}", e),
+        };
+        assert_eq!(prover.verify(), Ok(()));
+    }
+}
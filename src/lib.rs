@@ -1,5 +1,13 @@
+pub mod bitcoin;
+pub mod hmac;
+pub mod sha224;
 pub mod sha256;
+pub mod sha512;
 mod table16;
 
+pub use bitcoin::*;
+pub use hmac::*;
+pub use sha224::*;
 pub use sha256::*;
+pub use sha512::*;
 pub use table16::*;
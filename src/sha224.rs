@@ -0,0 +1,208 @@
+//! Gadget for the [SHA-224] hash function, built on the SHA-256 chips.
+//!
+//! SHA-224 is SHA-256 with a different initialization vector, and with the digest
+//! truncated to its first seven words.
+//!
+//! [SHA-224]: https://tools.ietf.org/html/rfc6234
+
+use halo2::{arithmetic::FieldExt, circuit::Layouter, plonk::Error};
+
+use crate::{Sha256, Sha256Instructions};
+
+/// The size of a SHA-224 digest, in 32-bit words.
+pub const SHA224_DIGEST_SIZE: usize = 7;
+
+/// The set of circuit instructions required to use the [`Sha224`] gadget, in addition to
+/// those of the [`Sha256`] gadget.
+pub trait Sha224Instructions<F: FieldExt>: Sha256Instructions<F> {
+    /// Places the SHA-224 IV in the circuit, returning the initial state variable.
+    fn initialization_vector_224(
+        &self,
+        layouter: &mut impl Layouter<F>,
+    ) -> Result<Self::State, Error>;
+}
+
+/// The output of a SHA-224 circuit invocation.
+#[derive(Debug)]
+pub struct Sha224Digest<DigestWord>([DigestWord; SHA224_DIGEST_SIZE]);
+
+impl<DigestWord> Sha224Digest<DigestWord> {
+    /// Returns the words of the digest, big-endian.
+    pub fn words(&self) -> &[DigestWord; SHA224_DIGEST_SIZE] {
+        &self.0
+    }
+}
+
+/// A gadget that constrains a SHA-224 invocation. It supports input at a granularity of
+/// 32 bits, or of 8 bits through [`Sha224::update_bytes`].
+#[derive(Debug)]
+pub struct Sha224<F: FieldExt, CS: Sha224Instructions<F>> {
+    inner: Sha256<F, CS>,
+}
+
+impl<F: FieldExt, Sha224Chip: Sha224Instructions<F>> Sha224<F, Sha224Chip> {
+    /// Create a new hasher instance.
+    pub fn new(chip: Sha224Chip, mut layouter: impl Layouter<F>) -> Result<Self, Error> {
+        let state = chip.initialization_vector_224(&mut layouter)?;
+        Ok(Sha224 {
+            inner: Sha256::with_initial_state(chip, state),
+        })
+    }
+
+    /// Digest data, updating the internal state.
+    ///
+    /// # Panics
+    ///
+    /// Panics if a previous call to [`Sha224::update_bytes`] left a partial word.
+    pub fn update(
+        &mut self,
+        layouter: impl Layouter<F>,
+        data: &[Sha224Chip::BlockWord],
+    ) -> Result<(), Error> {
+        self.inner.update(layouter, data)
+    }
+
+    /// Digest data given as bytes, updating the internal state. See
+    /// [`Sha256::update_bytes`].
+    pub fn update_bytes(
+        &mut self,
+        layouter: impl Layouter<F>,
+        data: &[Sha224Chip::Byte],
+    ) -> Result<(), Error> {
+        self.inner.update_bytes(layouter, data)
+    }
+
+    /// Retrieve result and consume hasher instance.
+    pub fn finalize(
+        self,
+        layouter: impl Layouter<F>,
+    ) -> Result<Sha224Digest<Sha224Chip::DigestWord>, Error> {
+        let digest = self.inner.finalize(layouter)?;
+        let words = digest.words();
+        Ok(Sha224Digest([
+            words[0], words[1], words[2], words[3], words[4], words[5], words[6],
+        ]))
+    }
+
+    /// Convenience function to compute hash of the data. It will handle hasher creation,
+    /// data feeding and finalization.
+    pub fn digest(
+        chip: Sha224Chip,
+        mut layouter: impl Layouter<F>,
+        data: &[Sha224Chip::BlockWord],
+    ) -> Result<Sha224Digest<Sha224Chip::DigestWord>, Error> {
+        let mut hasher = Self::new(chip, layouter.namespace(|| "init"))?;
+        hasher.update(layouter.namespace(|| "update"), data)?;
+        hasher.finalize(layouter.namespace(|| "finalize"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Sha224, SHA224_DIGEST_SIZE};
+    use crate::{native, BlockWord, Byte, DigestWord, Table16Chip, Table16Config};
+    use halo2::{
+        arithmetic::FieldExt,
+        circuit::{Layouter, SimpleFloorPlanner},
+        dev::MockProver,
+        pasta::Fp,
+        plonk::{Circuit, ConstraintSystem, Error},
+    };
+
+    #[test]
+    #[allow(clippy::unreadable_literal)]
+    fn sha224() {
+        struct MyCircuit {}
+
+        impl<F: FieldExt> Circuit<F> for MyCircuit {
+            type Config = Table16Config;
+            type FloorPlanner = SimpleFloorPlanner;
+
+            fn without_witnesses(&self) -> Self {
+                MyCircuit {}
+            }
+
+            fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+                Table16Chip::configure(meta)
+            }
+
+            fn synthesize(
+                &self,
+                config: Self::Config,
+                mut layouter: impl Layouter<F>,
+            ) -> Result<(), Error> {
+                Table16Chip::<F>::load(config.clone(), &mut layouter)?;
+
+                let check = |digest: &[DigestWord; SHA224_DIGEST_SIZE],
+                             expected: [u32; SHA224_DIGEST_SIZE]| {
+                    for (word, expected) in digest.iter().zip(expected.iter()) {
+                        assert_eq!(word.value().unwrap(), *expected);
+                    }
+                };
+
+                // Test vector: "abc"
+                let input: Vec<_> = b"abc".iter().map(|byte| Byte::Constant(*byte)).collect();
+                let mut hasher = Sha224::new(
+                    Table16Chip::<F>::construct(config.clone()),
+                    layouter.namespace(|| "'abc' init"),
+                )?;
+                hasher.update_bytes(layouter.namespace(|| "'abc' update"), &input)?;
+                let digest = hasher.finalize(layouter.namespace(|| "'abc' finalize"))?;
+                check(
+                    digest.words(),
+                    [
+                        0x23097d22, 0x3405d822, 0x8642a477, 0xbda255b3, 0x2aadbce4, 0xbda0b3f7,
+                        0xe36c9da7,
+                    ],
+                );
+
+                // Test vector: ""
+                let digest = Sha224::digest(
+                    Table16Chip::<F>::construct(config.clone()),
+                    layouter.namespace(|| "''"),
+                    &[],
+                )?;
+                check(
+                    digest.words(),
+                    [
+                        0xd14a028c, 0x2a3a2bc9, 0x476102bb, 0x288234c4, 0x15a2b01f, 0x828ea62a,
+                        0xc5b3e42f,
+                    ],
+                );
+
+                // Test vector: "abcdbcdecdefdefgefghfghighijhijkijkljklmklmnlmnomnopnopq"
+                // The padding is a block of its own.
+                let message = b"abcdbcdecdefdefgefghfghighijhijkijkljklmklmnlmnomnopnopq";
+                let input: Vec<_> = message
+                    .chunks(4)
+                    .map(|word| {
+                        BlockWord::new(Some(u32::from_be_bytes([
+                            word[0], word[1], word[2], word[3],
+                        ])))
+                    })
+                    .collect();
+                let digest = Sha224::digest(
+                    Table16Chip::<F>::construct(config),
+                    layouter.namespace(|| "'abcdbcdecdef...'"),
+                    &input,
+                )?;
+                let expected = [
+                    0x75388b16, 0x512776cc, 0x5dba5da1, 0xfd890150, 0xb0c6455c, 0xb4f58b19,
+                    0x52522525,
+                ];
+                assert_eq!(native::digest_224(message), expected);
+                check(digest.words(), expected);
+
+                Ok(())
+            }
+        }
+
+        let circuit: MyCircuit = MyCircuit {};
+
+        let prover = match MockProver::<Fp>::run(17, &circuit, vec![]) {
+            Ok(prover) => prover,
+            Err(e) => panic!("{:?}", e),
+        };
+        assert_eq!(prover.verify(), Ok(()));
+    }
+}
@@ -17,7 +17,7 @@ use halo2::{
 /// The size of a SHA-256 block, in 32-bit words.
 pub const BLOCK_SIZE: usize = 16;
 /// The size of a SHA-256 digest, in 32-bit words.
-pub(crate) const DIGEST_SIZE: usize = 8;
+pub const DIGEST_SIZE: usize = 8;
 
 /// The set of circuit instructions required to use the [`Sha256`] gadget.
 pub trait Sha256Instructions<F: FieldExt>: Chip<F> {
@@ -26,10 +26,28 @@ pub trait Sha256Instructions<F: FieldExt>: Chip<F> {
     /// Variable representing a 32-bit word of the input block to the SHA-256 compression
     /// function.
     type BlockWord: Copy + fmt::Debug + Default;
+    /// Variable representing an 8-bit byte of input.
+    type Byte: Copy + fmt::Debug;
+    /// Variable representing a 32-bit word of the SHA-256 digest.
+    type DigestWord: Copy + fmt::Debug;
 
     /// Places the SHA-256 IV in the circuit, returning the initial state variable.
     fn initialization_vector(&self, layouter: &mut impl Layouter<F>) -> Result<Self::State, Error>;
 
+    /// Returns a block word that is fixed to `value` in the circuit, as used for message
+    /// padding.
+    fn constant_word(value: u32) -> Self::BlockWord;
+
+    /// Returns a byte that is fixed to `value` in the circuit, as used for message padding.
+    fn constant_byte(value: u8) -> Self::Byte;
+
+    /// Packs four bytes into a block word, big-endian, constraining each byte to 8 bits.
+    fn pack_bytes(
+        &self,
+        layouter: &mut impl Layouter<F>,
+        bytes: [Self::Byte; 4],
+    ) -> Result<Self::BlockWord, Error>;
+
     /// Creates an initial state from the output state of a previous block
     fn initialization(
         &self,
@@ -38,7 +56,7 @@ pub trait Sha256Instructions<F: FieldExt>: Chip<F> {
     ) -> Result<Self::State, Error>;
 
     /// Starting from the given initialized state, processes a block of input and returns the
-    /// final state.
+    /// chaining value, i.e. the final state added to the initialized state.
     fn compress(
         &self,
         layouter: &mut impl Layouter<F>,
@@ -51,20 +69,87 @@ pub trait Sha256Instructions<F: FieldExt>: Chip<F> {
         &self,
         layouter: &mut impl Layouter<F>,
         state: &Self::State,
-    ) -> Result<[Self::BlockWord; DIGEST_SIZE], Error>;
+    ) -> Result<[Self::DigestWord; DIGEST_SIZE], Error>;
+}
+
+/// The set of circuit instructions required to use [`Sha256::from_midstate`] and
+/// [`Sha256::midstate`], in addition to those of the [`Sha256`] gadget.
+pub trait Sha256MidstateInstructions<F: FieldExt>: Sha256Instructions<F> {
+    /// Places the given chaining value in the circuit, such as a midstate computed
+    /// outside the circuit over a prefix of the message, returning an initialized state.
+    /// Constant words are fixed in the circuit, and assigned words are copied into it.
+    fn initialization_from_words(
+        &self,
+        layouter: &mut impl Layouter<F>,
+        words: [Self::BlockWord; DIGEST_SIZE],
+    ) -> Result<Self::State, Error>;
+}
+
+/// The set of circuit instructions required to use [`Sha256::constrain_digest_public`],
+/// in addition to those of the [`Sha256`] gadget.
+pub trait Sha256PublicInstructions<F: FieldExt>: Sha256Instructions<F> {
+    /// Constrains the words of the given digest to equal the public inputs at rows
+    /// `offset..offset + DIGEST_SIZE` of an instance column. Returns an error if the chip
+    /// was not configured with an instance column.
+    fn constrain_digest_public(
+        &self,
+        layouter: &mut impl Layouter<F>,
+        digest: &[Self::DigestWord; DIGEST_SIZE],
+        offset: usize,
+    ) -> Result<(), Error>;
+}
+
+/// The set of circuit instructions required to use [`Sha256::digest_var_len`], in
+/// addition to those of the [`Sha256`] gadget.
+pub trait Sha256VarLenInstructions<F: FieldExt>: Sha256Instructions<F> {
+    /// Variable representing a boolean.
+    type Bit: Clone + fmt::Debug;
+
+    /// Pads a message of `len` bytes, given in `data`, to `max_blocks` blocks as specified
+    /// in FIPS 180-4, section 5.1.1. Bytes of `data` beyond `len` are ignored. Returns the
+    /// padded blocks, along with a bit for each block that is set only for the final block
+    /// of the padded message, or an error if `data` is longer than `max_blocks` blocks.
+    #[allow(clippy::type_complexity)]
+    fn pad_var_len(
+        &self,
+        layouter: &mut impl Layouter<F>,
+        max_blocks: usize,
+        len: Self::BlockWord,
+        data: &[Self::Byte],
+    ) -> Result<(Vec<[Self::BlockWord; BLOCK_SIZE]>, Vec<Self::Bit>), Error>;
+
+    /// Selects the digest whose bit is set, given bits of which exactly one is set.
+    fn select_digest(
+        &self,
+        layouter: &mut impl Layouter<F>,
+        bits: &[Self::Bit],
+        digests: &[[Self::DigestWord; DIGEST_SIZE]],
+    ) -> Result<[Self::DigestWord; DIGEST_SIZE], Error>;
 }
 
 /// The output of a SHA-256 circuit invocation.
 #[derive(Debug)]
-pub struct Sha256Digest<BlockWord>([BlockWord; DIGEST_SIZE]);
+pub struct Sha256Digest<DigestWord>([DigestWord; DIGEST_SIZE]);
+
+impl<DigestWord> Sha256Digest<DigestWord> {
+    /// Returns the words of the digest, big-endian.
+    pub fn words(&self) -> &[DigestWord; DIGEST_SIZE] {
+        &self.0
+    }
+}
 
 /// A gadget that constrains a SHA-256 invocation. It supports input at a granularity of
-/// 32 bits.
+/// 32 bits, or of 8 bits through [`Sha256::update_bytes`].
 #[derive(Debug)]
 pub struct Sha256<F: FieldExt, CS: Sha256Instructions<F>> {
     chip: CS,
     state: CS::State,
+    // Whether `state` is ready to be compressed, or is the chaining value of a previous
+    // block that must first be passed through `initialization`.
+    initialized: bool,
     cur_block: Vec<CS::BlockWord>,
+    // The bytes of a partial word given to `update_bytes`, not yet packed into `cur_block`.
+    cur_word: Vec<CS::Byte>,
     length: usize,
 }
 
@@ -72,22 +157,67 @@ impl<F: FieldExt, Sha256Chip: Sha256Instructions<F>> Sha256<F, Sha256Chip> {
     /// Create a new hasher instance.
     pub fn new(chip: Sha256Chip, mut layouter: impl Layouter<F>) -> Result<Self, Error> {
         let state = chip.initialization_vector(&mut layouter)?;
-        Ok(Sha256 {
+        Ok(Self::with_initial_state(chip, state))
+    }
+
+    // Create a hasher instance starting from an initial state already placed in the
+    // circuit, such as the IV of another hash function in the SHA-2 family.
+    pub(crate) fn with_initial_state(chip: Sha256Chip, state: Sha256Chip::State) -> Self {
+        Sha256 {
             chip,
             state,
+            initialized: true,
             cur_block: Vec::with_capacity(BLOCK_SIZE),
+            cur_word: Vec::with_capacity(4),
             length: 0,
-        })
+        }
     }
 
     /// Digest data, updating the internal state.
+    ///
+    /// # Panics
+    ///
+    /// Panics if a previous call to [`Sha256::update_bytes`] left a partial word.
     pub fn update(
         &mut self,
         mut layouter: impl Layouter<F>,
-        mut data: &[Sha256Chip::BlockWord],
+        data: &[Sha256Chip::BlockWord],
     ) -> Result<(), Error> {
+        assert!(
+            self.cur_word.is_empty(),
+            "cannot update with words after a partial word of bytes"
+        );
         self.length += data.len() * 32;
+        self.process_words(&mut layouter, data)
+    }
+
+    /// Digest data given as bytes, updating the internal state. Each group of four bytes is
+    /// packed big-endian into a word; the bytes of a trailing partial word are kept until
+    /// more data is given, or are padded in [`Sha256::finalize`].
+    pub fn update_bytes(
+        &mut self,
+        mut layouter: impl Layouter<F>,
+        data: &[Sha256Chip::Byte],
+    ) -> Result<(), Error> {
+        self.length += data.len() * 8;
+
+        let mut words = Vec::with_capacity((self.cur_word.len() + data.len()) / 4);
+        for byte in data {
+            self.cur_word.push(*byte);
+            if self.cur_word.len() == 4 {
+                words.push(self.pack_cur_word(&mut layouter)?);
+            }
+        }
+
+        self.process_words(&mut layouter, &words)
+    }
 
+    // Append words to the message, processing every block they complete.
+    fn process_words(
+        &mut self,
+        layouter: &mut impl Layouter<F>,
+        mut data: &[Sha256Chip::BlockWord],
+    ) -> Result<(), Error> {
         // Fill the current block, if possible.
         let remaining = BLOCK_SIZE - self.cur_block.len();
         let (l, r) = data.split_at(min(remaining, data.len()));
@@ -100,22 +230,13 @@ impl<F: FieldExt, Sha256Chip: Sha256Instructions<F>> Sha256<F, Sha256Chip> {
         }
 
         // Process the now-full current block.
-        self.state = self.chip.compress(
-            &mut layouter,
-            &self.state,
-            self.cur_block[..]
-                .try_into()
-                .expect("cur_block.len() == BLOCK_SIZE"),
-        )?;
-        self.cur_block.clear();
+        self.process_cur_block(layouter)?;
 
         // Process any additional full blocks.
         let mut chunks_iter = data.chunks_exact(BLOCK_SIZE);
         for chunk in &mut chunks_iter {
-            self.state = self.chip.initialization(&mut layouter, &self.state)?;
-            self.state = self.chip.compress(
-                &mut layouter,
-                &self.state,
+            self.process_block(
+                layouter,
                 chunk.try_into().expect("chunk.len() == BLOCK_SIZE"),
             )?;
         }
@@ -131,34 +252,936 @@ impl<F: FieldExt, Sha256Chip: Sha256Instructions<F>> Sha256<F, Sha256Chip> {
     pub fn finalize(
         mut self,
         mut layouter: impl Layouter<F>,
-    ) -> Result<Sha256Digest<Sha256Chip::BlockWord>, Error> {
-        // Pad the remaining block
-        if !self.cur_block.is_empty() {
-            let padding = vec![Sha256Chip::BlockWord::default(); BLOCK_SIZE - self.cur_block.len()];
-            self.cur_block.extend_from_slice(&padding);
-            self.state = self.chip.initialization(&mut layouter, &self.state)?;
-            self.state = self.chip.compress(
-                &mut layouter,
-                &self.state,
-                self.cur_block[..]
-                    .try_into()
-                    .expect("cur_block.len() == BLOCK_SIZE"),
-            )?;
+    ) -> Result<Sha256Digest<Sha256Chip::DigestWord>, Error> {
+        // Pad the message as specified in FIPS 180-4, section 5.1.1. The appended `1` bit
+        // is the top bit of the byte following the message. If the 64-bit message length
+        // does not fit in the current block, an extra block of padding is processed.
+        let zero = Sha256Chip::constant_word(0);
+        if self.cur_word.is_empty() {
+            self.cur_block.push(Sha256Chip::constant_word(0x8000_0000));
+        } else {
+            self.cur_word.push(Sha256Chip::constant_byte(0x80));
+            self.cur_word.resize(4, Sha256Chip::constant_byte(0));
+            let word = self.pack_cur_word(&mut layouter)?;
+            self.cur_block.push(word);
         }
+        if self.cur_block.len() > BLOCK_SIZE - 2 {
+            self.cur_block.resize(BLOCK_SIZE, zero);
+            self.process_cur_block(&mut layouter)?;
+        }
+        self.cur_block.resize(BLOCK_SIZE - 2, zero);
+
+        // Append the message length in bits, as a big-endian 64-bit integer.
+        let length = self.length as u64;
+        self.cur_block
+            .push(Sha256Chip::constant_word((length >> 32) as u32));
+        self.cur_block
+            .push(Sha256Chip::constant_word(length as u32));
+        self.process_cur_block(&mut layouter)?;
+
         self.chip
             .digest(&mut layouter, &self.state)
             .map(Sha256Digest)
     }
 
+    // Pack the full current word, clearing it.
+    fn pack_cur_word(
+        &mut self,
+        layouter: &mut impl Layouter<F>,
+    ) -> Result<Sha256Chip::BlockWord, Error> {
+        let bytes = self.cur_word[..].try_into().expect("cur_word.len() == 4");
+        self.cur_word.clear();
+        self.chip.pack_bytes(layouter, bytes)
+    }
+
+    // Process the full current block, clearing it.
+    fn process_cur_block(&mut self, layouter: &mut impl Layouter<F>) -> Result<(), Error> {
+        let block = self.cur_block[..]
+            .try_into()
+            .expect("cur_block.len() == BLOCK_SIZE");
+        self.cur_block.clear();
+        self.process_block(layouter, block)
+    }
+
+    // Compress a block into the state, initializing it first if it is the chaining value
+    // of a previous block.
+    fn process_block(
+        &mut self,
+        layouter: &mut impl Layouter<F>,
+        block: [Sha256Chip::BlockWord; BLOCK_SIZE],
+    ) -> Result<(), Error> {
+        if !self.initialized {
+            self.state = self.chip.initialization(layouter, &self.state)?;
+        }
+        self.state = self.chip.compress(layouter, &self.state, block)?;
+        self.initialized = false;
+        Ok(())
+    }
+
     /// Convenience function to compute hash of the data. It will handle hasher creation,
     /// data feeding and finalization.
     pub fn digest(
         chip: Sha256Chip,
         mut layouter: impl Layouter<F>,
         data: &[Sha256Chip::BlockWord],
-    ) -> Result<Sha256Digest<Sha256Chip::BlockWord>, Error> {
+    ) -> Result<Sha256Digest<Sha256Chip::DigestWord>, Error> {
         let mut hasher = Self::new(chip, layouter.namespace(|| "init"))?;
         hasher.update(layouter.namespace(|| "update"), data)?;
         hasher.finalize(layouter.namespace(|| "finalize"))
     }
+
+    /// Computes SHA256d, the hash of the hash of the data, as used by Bitcoin. The first
+    /// digest is copied into the input of the second hash.
+    pub fn sha256d(
+        chip: Sha256Chip,
+        mut layouter: impl Layouter<F>,
+        data: &[Sha256Chip::BlockWord],
+    ) -> Result<Sha256Digest<Sha256Chip::DigestWord>, Error>
+    where
+        Sha256Chip: Clone,
+        Sha256Chip::BlockWord: From<Sha256Chip::DigestWord>,
+    {
+        let first = Self::digest(chip.clone(), layouter.namespace(|| "first"), data)?;
+        let first: Vec<_> = first.words().iter().map(|word| (*word).into()).collect();
+        Self::digest(chip, layouter.namespace(|| "second"), &first)
+    }
+}
+
+impl<F: FieldExt, Sha256Chip: Sha256MidstateInstructions<F>> Sha256<F, Sha256Chip> {
+    /// Create a hasher instance that resumes from `midstate`, the chaining value after
+    /// hashing the first `prefix_blocks` blocks of the message, as exported by
+    /// [`Sha256::midstate`] or computed outside the circuit. The prefix counts towards the
+    /// message length encoded in the padding.
+    pub fn from_midstate(
+        chip: Sha256Chip,
+        mut layouter: impl Layouter<F>,
+        midstate: [Sha256Chip::BlockWord; DIGEST_SIZE],
+        prefix_blocks: usize,
+    ) -> Result<Self, Error> {
+        let state = chip.initialization_from_words(&mut layouter, midstate)?;
+        let mut hasher = Self::with_initial_state(chip, state);
+        hasher.length = prefix_blocks * BLOCK_SIZE * 32;
+        Ok(hasher)
+    }
+
+    /// Returns the chaining value after the blocks given so far, from which hashing can be
+    /// resumed with [`Sha256::from_midstate`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if the data given so far does not fill a whole number of blocks.
+    pub fn midstate(
+        &self,
+        mut layouter: impl Layouter<F>,
+    ) -> Result<[Sha256Chip::DigestWord; DIGEST_SIZE], Error> {
+        assert!(
+            self.cur_block.is_empty() && self.cur_word.is_empty(),
+            "cannot export the midstate of a partial block"
+        );
+        self.chip.digest(&mut layouter, &self.state)
+    }
+}
+
+impl<F: FieldExt, Sha256Chip: Sha256PublicInstructions<F>> Sha256<F, Sha256Chip> {
+    /// Constrains the digest to equal the public inputs at rows
+    /// `offset..offset + DIGEST_SIZE`, one 32-bit word per row, so that a proof attests to
+    /// knowledge of a preimage of a public digest. Returns an error if the chip was not
+    /// configured with an instance column.
+    pub fn constrain_digest_public(
+        chip: &Sha256Chip,
+        mut layouter: impl Layouter<F>,
+        digest: &Sha256Digest<Sha256Chip::DigestWord>,
+        offset: usize,
+    ) -> Result<(), Error> {
+        chip.constrain_digest_public(&mut layouter, digest.words(), offset)
+    }
+}
+
+impl<F: FieldExt, Sha256Chip: Sha256VarLenInstructions<F>> Sha256<F, Sha256Chip> {
+    /// Computes the hash of a message of a witnessed length `len`, in bytes, given in the
+    /// first bytes of `data`. All `max_blocks` blocks are compressed, so the number of rows
+    /// does not depend on `len`; the digest is taken from the chaining value of the block
+    /// that ends the padded message. The message must leave room in the last block for the
+    /// padding, i.e. `len` is at most `64 * max_blocks - 9`. Returns an error if `data` is
+    /// longer than `max_blocks` blocks.
+    ///
+    /// With [`Table16Chip`](crate::Table16Chip), the chip must be configured with
+    /// [`Table16Config::with_var_len`](crate::Table16Config::with_var_len).
+    pub fn digest_var_len(
+        chip: Sha256Chip,
+        mut layouter: impl Layouter<F>,
+        max_blocks: usize,
+        len: Sha256Chip::BlockWord,
+        data: &[Sha256Chip::Byte],
+    ) -> Result<Sha256Digest<Sha256Chip::DigestWord>, Error> {
+        let (blocks, bits) =
+            chip.pad_var_len(&mut layouter.namespace(|| "pad"), max_blocks, len, data)?;
+
+        let mut hasher = Self::new(chip, layouter.namespace(|| "init"))?;
+        let mut digests = Vec::with_capacity(max_blocks);
+        for (idx, block) in blocks.into_iter().enumerate() {
+            let mut layouter = layouter.namespace(|| format!("block {}", idx));
+            hasher.process_block(&mut layouter, block)?;
+            digests.push(hasher.chip.digest(&mut layouter, &hasher.state)?);
+        }
+
+        hasher
+            .chip
+            .select_digest(&mut layouter.namespace(|| "select"), &bits, &digests)
+            .map(Sha256Digest)
+    }
+}
+
+#[cfg(test)]
+mod cavp;
+
+#[cfg(test)]
+mod tests {
+    use super::{Sha256, BLOCK_SIZE, DIGEST_SIZE};
+    use crate::{
+        native, BlockWord, Byte, CellValue32, CellValue8, DigestWord, Table16Chip, Table16Config,
+    };
+    use halo2::{
+        arithmetic::FieldExt,
+        circuit::{Layouter, SimpleFloorPlanner},
+        dev::MockProver,
+        pasta::Fp,
+        plonk::{Advice, Circuit, Column, ConstraintSystem, Error, Selector},
+        poly::Rotation,
+    };
+
+    #[test]
+    #[allow(clippy::unreadable_literal)]
+    fn padding() {
+        struct MyCircuit {}
+
+        impl<F: FieldExt> Circuit<F> for MyCircuit {
+            type Config = Table16Config;
+            type FloorPlanner = SimpleFloorPlanner;
+
+            fn without_witnesses(&self) -> Self {
+                MyCircuit {}
+            }
+
+            fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+                Table16Chip::configure(meta)
+            }
+
+            fn synthesize(
+                &self,
+                config: Self::Config,
+                mut layouter: impl Layouter<F>,
+            ) -> Result<(), Error> {
+                let check = |digest: &[DigestWord; DIGEST_SIZE], expected: [u32; DIGEST_SIZE]| {
+                    for (word, expected) in digest.iter().zip(expected.iter()) {
+                        assert_eq!(word.value().unwrap(), *expected);
+                    }
+                };
+                let words = |words: &[u32]| -> Vec<BlockWord> {
+                    words
+                        .iter()
+                        .map(|word| BlockWord::new(Some(*word)))
+                        .collect()
+                };
+
+                Table16Chip::<F>::load(config.clone(), &mut layouter)?;
+
+                // Test vector: ""
+                let digest = Sha256::digest(
+                    Table16Chip::<F>::construct(config.clone()),
+                    layouter.namespace(|| "''"),
+                    &[],
+                )?;
+                check(
+                    digest.words(),
+                    [
+                        0xe3b0c442, 0x98fc1c14, 0x9afbf4c8, 0x996fb924, 0x27ae41e4, 0x649b934c,
+                        0xa495991b, 0x7852b855,
+                    ],
+                );
+
+                // Test vector: "abcdbcdecdefdefgefghfghighijhijkijkljklmklmnlmnomnopnopq"
+                // The message length does not fit in the first block.
+                let input = words(&[
+                    0x61626364, 0x62636465, 0x63646566, 0x64656667, 0x65666768, 0x66676869,
+                    0x6768696a, 0x68696a6b, 0x696a6b6c, 0x6a6b6c6d, 0x6b6c6d6e, 0x6c6d6e6f,
+                    0x6d6e6f70, 0x6e6f7071,
+                ]);
+                let digest = Sha256::digest(
+                    Table16Chip::<F>::construct(config.clone()),
+                    layouter.namespace(|| "'abcdbcdecdef...'"),
+                    &input,
+                )?;
+                check(
+                    digest.words(),
+                    [
+                        0x248d6a61, 0xd20638b8, 0xe5c02693, 0x0c3e6039, 0xa33ce459, 0x64ff2167,
+                        0xf6ecedd4, 0x19db06c1,
+                    ],
+                );
+
+                // Test vector: "abcd" * 16
+                // The padding is a block of its own.
+                let input = words(&[0x61626364; BLOCK_SIZE]);
+                let digest = Sha256::digest(
+                    Table16Chip::<F>::construct(config.clone()),
+                    layouter.namespace(|| "'abcd' * 16"),
+                    &input,
+                )?;
+                check(
+                    digest.words(),
+                    [
+                        0x625b4149, 0x0b883891, 0x943c5fa5, 0x4ad45d7c, 0x900b9b6e, 0x91e15933,
+                        0x4e320b1f, 0x5215a209,
+                    ],
+                );
+
+                // Test vector: "abcd" * 20, in two updates
+                let input = words(&[0x61626364; 20]);
+                let mut hasher = Sha256::new(
+                    Table16Chip::<F>::construct(config),
+                    layouter.namespace(|| "'abcd' * 20 init"),
+                )?;
+                hasher.update(layouter.namespace(|| "'abcd' * 12"), &input[..12])?;
+                hasher.update(layouter.namespace(|| "'abcd' * 8"), &input[12..])?;
+                let digest = hasher.finalize(layouter.namespace(|| "'abcd' * 20 finalize"))?;
+                check(
+                    digest.words(),
+                    [
+                        0xf7159a55, 0x5c73f634, 0xde31aa19, 0x878b7fa6, 0x6a1cceac, 0x094aa961,
+                        0x38a17f03, 0xb1a65203,
+                    ],
+                );
+
+                Ok(())
+            }
+        }
+
+        let circuit: MyCircuit = MyCircuit {};
+
+        let prover = match MockProver::<Fp>::run(17, &circuit, vec![]) {
+            Ok(prover) => prover,
+            Err(e) => panic!("{:?}", e),
+        };
+        assert_eq!(prover.verify(), Ok(()));
+    }
+
+    #[test]
+    #[allow(clippy::unreadable_literal)]
+    fn update_bytes() {
+        struct MyCircuit {}
+
+        impl<F: FieldExt> Circuit<F> for MyCircuit {
+            type Config = (Table16Config, Column<Advice>);
+            type FloorPlanner = SimpleFloorPlanner;
+
+            fn without_witnesses(&self) -> Self {
+                MyCircuit {}
+            }
+
+            fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+                let message = meta.advice_column();
+                meta.enable_equality(message.into());
+
+                (Table16Chip::configure(meta), message)
+            }
+
+            fn synthesize(
+                &self,
+                config: Self::Config,
+                mut layouter: impl Layouter<F>,
+            ) -> Result<(), Error> {
+                let (config, message) = config;
+                let check = |digest: &[DigestWord; DIGEST_SIZE], expected: [u32; DIGEST_SIZE]| {
+                    for (word, expected) in digest.iter().zip(expected.iter()) {
+                        assert_eq!(word.value().unwrap(), *expected);
+                    }
+                };
+                // Assign the message bytes in a column outside the chip.
+                let mut assign_bytes = |name: &'static str, bytes: &[u8]| {
+                    layouter.assign_region(
+                        || name,
+                        |mut region| {
+                            bytes
+                                .iter()
+                                .enumerate()
+                                .map(|(row, byte)| {
+                                    let var = region.assign_advice(
+                                        || format!("byte {}", row),
+                                        message,
+                                        row,
+                                        || Ok(F::from_u64(*byte as u64)),
+                                    )?;
+                                    Ok(Byte::Assigned(CellValue8::new(var, Some(*byte))))
+                                })
+                                .collect::<Result<Vec<_>, Error>>()
+                        },
+                    )
+                };
+
+                let abc = assign_bytes("'abc'", b"abc")?;
+                let fox = assign_bytes(
+                    "'The quick brown fox...'",
+                    b"The quick brown fox jumps over the lazy dog. The quick brown fox.",
+                )?;
+
+                Table16Chip::<F>::load(config.clone(), &mut layouter)?;
+
+                // Test vector: "abc"
+                let mut hasher = Sha256::new(
+                    Table16Chip::<F>::construct(config.clone()),
+                    layouter.namespace(|| "'abc' init"),
+                )?;
+                hasher.update_bytes(layouter.namespace(|| "'abc' update"), &abc)?;
+                let digest = hasher.finalize(layouter.namespace(|| "'abc' finalize"))?;
+                check(
+                    digest.words(),
+                    [
+                        0xba7816bf, 0x8f01cfea, 0x414140de, 0x5dae2223, 0xb00361a3, 0x96177a9c,
+                        0xb410ff61, 0xf20015ad,
+                    ],
+                );
+
+                // Test vector: "The quick brown fox...", in two updates that split a word
+                let mut hasher = Sha256::new(
+                    Table16Chip::<F>::construct(config),
+                    layouter.namespace(|| "'The quick brown fox...' init"),
+                )?;
+                hasher.update_bytes(layouter.namespace(|| "'The quick '"), &fox[..10])?;
+                hasher.update_bytes(layouter.namespace(|| "'brown fox...'"), &fox[10..])?;
+                let digest =
+                    hasher.finalize(layouter.namespace(|| "'The quick brown fox...' finalize"))?;
+                check(
+                    digest.words(),
+                    [
+                        0x0f9d7235, 0xa0dfbdd4, 0x32d9035a, 0x97147fd3, 0x94cbc942, 0x5339b70b,
+                        0xa2d902cb, 0x2b2963e2,
+                    ],
+                );
+
+                Ok(())
+            }
+        }
+
+        let circuit: MyCircuit = MyCircuit {};
+
+        let prover = match MockProver::<Fp>::run(17, &circuit, vec![]) {
+            Ok(prover) => prover,
+            Err(e) => panic!("{:?}", e),
+        };
+        assert_eq!(prover.verify(), Ok(()));
+    }
+
+    #[test]
+    fn digest_var_len() {
+        // Message lengths in bytes, on either side of the boundaries at which the marker
+        // and the message length move to another word or block
+        const LENS: [usize; 9] = [0, 1, 3, 55, 56, 63, 64, 65, 119];
+
+        struct MyCircuit {}
+
+        impl<F: FieldExt> Circuit<F> for MyCircuit {
+            type Config = (Table16Config, Column<Advice>);
+            type FloorPlanner = SimpleFloorPlanner;
+
+            fn without_witnesses(&self) -> Self {
+                MyCircuit {}
+            }
+
+            fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+                let message = meta.advice_column();
+                meta.enable_equality(message.into());
+
+                (Table16Chip::configure(meta).with_var_len(meta), message)
+            }
+
+            fn synthesize(
+                &self,
+                config: Self::Config,
+                mut layouter: impl Layouter<F>,
+            ) -> Result<(), Error> {
+                let (config, message) = config;
+
+                // Two blocks of data, of which a prefix of each length is hashed.
+                let bytes: Vec<u8> = (0..2 * BLOCK_SIZE * 4)
+                    .map(|idx| (idx * 37 + 11) as u8)
+                    .collect();
+
+                // Assign the message bytes in a column outside the chip.
+                let data = layouter.assign_region(
+                    || "message",
+                    |mut region| {
+                        bytes
+                            .iter()
+                            .enumerate()
+                            .map(|(row, byte)| {
+                                let var = region.assign_advice(
+                                    || format!("byte {}", row),
+                                    message,
+                                    row,
+                                    || Ok(F::from_u64(*byte as u64)),
+                                )?;
+                                Ok(Byte::Assigned(CellValue8::new(var, Some(*byte))))
+                            })
+                            .collect::<Result<Vec<_>, Error>>()
+                    },
+                )?;
+
+                Table16Chip::<F>::load(config.clone(), &mut layouter)?;
+
+                for len in LENS.iter() {
+                    let digest = Sha256::digest_var_len(
+                        Table16Chip::<F>::construct(config.clone()),
+                        layouter.namespace(|| format!("len {}", len)),
+                        2,
+                        BlockWord::new(Some(*len as u32)),
+                        &data,
+                    )?;
+                    for (word, expected) in digest
+                        .words()
+                        .iter()
+                        .zip(native::digest(&bytes[..*len]).iter())
+                    {
+                        assert_eq!(word.value().unwrap(), *expected);
+                    }
+                }
+
+                // The data does not fit in a single block.
+                assert!(Sha256::digest_var_len(
+                    Table16Chip::<F>::construct(config),
+                    layouter.namespace(|| "too long"),
+                    1,
+                    BlockWord::new(Some(3)),
+                    &data,
+                )
+                .is_err());
+
+                Ok(())
+            }
+        }
+
+        let circuit: MyCircuit = MyCircuit {};
+
+        let prover = match MockProver::<Fp>::run(17, &circuit, vec![]) {
+            Ok(prover) => prover,
+            Err(e) => panic!("{:?}", e),
+        };
+        assert_eq!(prover.verify(), Ok(()));
+    }
+
+    #[test]
+    #[allow(clippy::unreadable_literal)]
+    fn assigned_words() {
+        struct MyCircuit {
+            // Added to the first message word in the external column only
+            offset: u64,
+        }
+
+        impl<F: FieldExt> Circuit<F> for MyCircuit {
+            type Config = (Table16Config, Column<Advice>);
+            type FloorPlanner = SimpleFloorPlanner;
+
+            fn without_witnesses(&self) -> Self {
+                MyCircuit { offset: 0 }
+            }
+
+            fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+                let message = meta.advice_column();
+                meta.enable_equality(message.into());
+
+                (Table16Chip::configure(meta), message)
+            }
+
+            fn synthesize(
+                &self,
+                config: Self::Config,
+                mut layouter: impl Layouter<F>,
+            ) -> Result<(), Error> {
+                let (config, message) = config;
+
+                // Assign the message words in a column outside the chip, as another
+                // chip would.
+                let input = layouter.assign_region(
+                    || "'abcd' * 16",
+                    |mut region| {
+                        (0..BLOCK_SIZE)
+                            .map(|row| {
+                                let word = 0x61626364u32;
+                                let offset = if row == 0 { self.offset } else { 0 };
+                                let var = region.assign_advice(
+                                    || format!("word {}", row),
+                                    message,
+                                    row,
+                                    || Ok(F::from_u64(word as u64 + offset)),
+                                )?;
+                                Ok(BlockWord::Assigned(CellValue32::new(var, Some(word))))
+                            })
+                            .collect::<Result<Vec<_>, Error>>()
+                    },
+                )?;
+
+                Table16Chip::<F>::load(config.clone(), &mut layouter)?;
+
+                // Test vector: "abcd" * 16
+                let digest = Sha256::digest(
+                    Table16Chip::<F>::construct(config),
+                    layouter.namespace(|| "'abcd' * 16"),
+                    &input,
+                )?;
+                for (word, expected) in digest.words().iter().zip(
+                    [
+                        0x625b4149, 0x0b883891, 0x943c5fa5, 0x4ad45d7c, 0x900b9b6e, 0x91e15933,
+                        0x4e320b1f, 0x5215a209,
+                    ]
+                    .iter(),
+                ) {
+                    assert_eq!(word.value().unwrap(), *expected);
+                }
+
+                Ok(())
+            }
+        }
+
+        let circuit: MyCircuit = MyCircuit { offset: 0 };
+        let prover = match MockProver::<Fp>::run(17, &circuit, vec![]) {
+            Ok(prover) => prover,
+            Err(e) => panic!("{:?}", e),
+        };
+        assert_eq!(prover.verify(), Ok(()));
+
+        // The hashed words are bound to the assigned cells; a cell holding a value
+        // other than the one witnessed in the message schedule is rejected. A cell of
+        // 2^32 or more witnessed as such in the message schedule is rejected by the
+        // range check on W_0, as tested in `message_schedule::assigned_word_out_of_range`.
+        let circuit: MyCircuit = MyCircuit { offset: 1 << 32 };
+        let prover = match MockProver::<Fp>::run(17, &circuit, vec![]) {
+            Ok(prover) => prover,
+            Err(e) => panic!("{:?}", e),
+        };
+        assert!(prover.verify().is_err());
+    }
+
+    #[test]
+    #[allow(clippy::unreadable_literal)]
+    fn public_digest() {
+        struct MyCircuit {}
+
+        impl<F: FieldExt> Circuit<F> for MyCircuit {
+            type Config = Table16Config;
+            type FloorPlanner = SimpleFloorPlanner;
+
+            fn without_witnesses(&self) -> Self {
+                MyCircuit {}
+            }
+
+            fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+                let instance = meta.instance_column();
+                Table16Chip::configure_with_instance(meta, instance)
+            }
+
+            fn synthesize(
+                &self,
+                config: Self::Config,
+                mut layouter: impl Layouter<F>,
+            ) -> Result<(), Error> {
+                let table16_chip = Table16Chip::<F>::construct(config.clone());
+                Table16Chip::<F>::load(config, &mut layouter)?;
+
+                // Test vector: ""
+                let digest =
+                    Sha256::digest(table16_chip.clone(), layouter.namespace(|| "''"), &[])?;
+                Sha256::constrain_digest_public(
+                    &table16_chip,
+                    layouter.namespace(|| "public digest"),
+                    &digest,
+                    0,
+                )
+            }
+        }
+
+        let circuit: MyCircuit = MyCircuit {};
+        let digest = [
+            0xe3b0c442, 0x98fc1c14, 0x9afbf4c8, 0x996fb924, 0x27ae41e4, 0x649b934c, 0xa495991b,
+            0x7852b855,
+        ];
+        let public_inputs = |digest: [u64; DIGEST_SIZE]| {
+            vec![digest.iter().map(|word| Fp::from_u64(*word)).collect()]
+        };
+
+        let prover = match MockProver::<Fp>::run(17, &circuit, public_inputs(digest)) {
+            Ok(prover) => prover,
+            Err(e) => panic!("{:?}", e),
+        };
+        assert_eq!(prover.verify(), Ok(()));
+
+        // A proof cannot attest to a different digest.
+        let mut wrong_digest = digest;
+        wrong_digest[7] ^= 1;
+        let prover = match MockProver::<Fp>::run(17, &circuit, public_inputs(wrong_digest)) {
+            Ok(prover) => prover,
+            Err(e) => panic!("{:?}", e),
+        };
+        assert!(prover.verify().is_err());
+    }
+
+    #[test]
+    #[allow(clippy::unreadable_literal)]
+    fn midstate() {
+        struct MyCircuit {}
+
+        impl<F: FieldExt> Circuit<F> for MyCircuit {
+            type Config = Table16Config;
+            type FloorPlanner = SimpleFloorPlanner;
+
+            fn without_witnesses(&self) -> Self {
+                MyCircuit {}
+            }
+
+            fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+                Table16Chip::configure(meta)
+            }
+
+            fn synthesize(
+                &self,
+                config: Self::Config,
+                mut layouter: impl Layouter<F>,
+            ) -> Result<(), Error> {
+                let check = |digest: &[DigestWord; DIGEST_SIZE], expected: [u32; DIGEST_SIZE]| {
+                    for (word, expected) in digest.iter().zip(expected.iter()) {
+                        assert_eq!(word.value().unwrap(), *expected);
+                    }
+                };
+                // The digest of "abcd" * 20
+                let expected = [
+                    0xf7159a55, 0x5c73f634, 0xde31aa19, 0x878b7fa6, 0x6a1cceac, 0x094aa961,
+                    0x38a17f03, 0xb1a65203,
+                ];
+                let input = [BlockWord::new(Some(0x61626364)); 20];
+                let native_midstate = native::compress(native::IV, [0x61626364; BLOCK_SIZE]);
+
+                Table16Chip::<F>::load(config.clone(), &mut layouter)?;
+
+                // Export the midstate after the first block.
+                let mut hasher = Sha256::new(
+                    Table16Chip::<F>::construct(config.clone()),
+                    layouter.namespace(|| "prefix init"),
+                )?;
+                hasher.update(layouter.namespace(|| "prefix"), &input[..BLOCK_SIZE])?;
+                let midstate = hasher.midstate(layouter.namespace(|| "export"))?;
+                check(&midstate, native_midstate);
+
+                // Resume from the exported midstate, and from the midstate computed
+                // outside the circuit, both as constants and as witnesses.
+                let mut exported = [BlockWord::default(); DIGEST_SIZE];
+                let mut constant = [BlockWord::default(); DIGEST_SIZE];
+                let mut witnessed = [BlockWord::default(); DIGEST_SIZE];
+                for idx in 0..DIGEST_SIZE {
+                    exported[idx] = midstate[idx].into();
+                    constant[idx] = BlockWord::Constant(native_midstate[idx]);
+                    witnessed[idx] = BlockWord::new(Some(native_midstate[idx]));
+                }
+                for (name, midstate) in [
+                    ("exported", exported),
+                    ("constant", constant),
+                    ("witnessed", witnessed),
+                ]
+                .iter()
+                {
+                    let mut hasher = Sha256::from_midstate(
+                        Table16Chip::<F>::construct(config.clone()),
+                        layouter.namespace(|| format!("{} init", name)),
+                        *midstate,
+                        1,
+                    )?;
+                    hasher.update(
+                        layouter.namespace(|| format!("{} suffix", name)),
+                        &input[BLOCK_SIZE..],
+                    )?;
+                    let digest =
+                        hasher.finalize(layouter.namespace(|| format!("{} finalize", name)))?;
+                    check(digest.words(), expected);
+                }
+
+                Ok(())
+            }
+        }
+
+        let circuit: MyCircuit = MyCircuit {};
+        let prover = match MockProver::<Fp>::run(17, &circuit, vec![]) {
+            Ok(prover) => prover,
+            Err(e) => panic!("{:?}", e),
+        };
+        assert_eq!(prover.verify(), Ok(()));
+    }
+
+    #[test]
+    #[allow(clippy::unreadable_literal)]
+    fn byte_table() {
+        struct MyCircuit {}
+
+        impl<F: FieldExt> Circuit<F> for MyCircuit {
+            type Config = Table16Config;
+            type FloorPlanner = SimpleFloorPlanner;
+
+            fn without_witnesses(&self) -> Self {
+                MyCircuit {}
+            }
+
+            fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+                let instance = meta.instance_column();
+                Table16Chip::configure_with_byte_table(meta).with_instance(meta, instance)
+            }
+
+            fn synthesize(
+                &self,
+                config: Self::Config,
+                mut layouter: impl Layouter<F>,
+            ) -> Result<(), Error> {
+                let table16_chip = Table16Chip::<F>::construct(config.clone());
+                Table16Chip::<F>::load(config, &mut layouter)?;
+
+                // Test vector: "abcd"
+                let digest = Sha256::digest(
+                    table16_chip.clone(),
+                    layouter.namespace(|| "'abcd'"),
+                    &[BlockWord::new(Some(0x61626364))],
+                )?;
+                Sha256::constrain_digest_public(
+                    &table16_chip,
+                    layouter.namespace(|| "public digest"),
+                    &digest,
+                    0,
+                )
+            }
+        }
+
+        // A single compression fits in a smaller circuit than the 16-bit table.
+        let circuit: MyCircuit = MyCircuit {};
+        let digest = [
+            0x88d4266f, 0xd4e6338d, 0x13b845fc, 0xf289579d, 0x209c8978, 0x23b9217d, 0xa3e16193,
+            0x6f031589,
+        ];
+        let public_inputs = vec![digest.iter().map(|word| Fp::from_u64(*word)).collect()];
+        let prover = match MockProver::<Fp>::run(12, &circuit, public_inputs) {
+            Ok(prover) => prover,
+            Err(e) => panic!("{:?}", e),
+        };
+        assert_eq!(prover.verify(), Ok(()));
+    }
+
+    #[test]
+    #[allow(clippy::unreadable_literal)]
+    fn shared_columns() {
+        // The digest of "abcd"
+        const DIGEST: [u32; DIGEST_SIZE] = [
+            0x88d4266f, 0xd4e6338d, 0x13b845fc, 0xf289579d, 0x209c8978, 0x23b9217d, 0xa3e16193,
+            0x6f031589,
+        ];
+
+        struct MyCircuit {
+            sum: Option<u64>,
+        }
+
+        impl<F: FieldExt> Circuit<F> for MyCircuit {
+            type Config = (Table16Config, Selector, [Column<Advice>; 3]);
+            type FloorPlanner = SimpleFloorPlanner;
+
+            fn without_witnesses(&self) -> Self {
+                MyCircuit { sum: None }
+            }
+
+            fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+                let message_schedule = meta.advice_column();
+                let extras = [
+                    meta.advice_column(),
+                    meta.advice_column(),
+                    meta.advice_column(),
+                    meta.advice_column(),
+                    meta.advice_column(),
+                    meta.advice_column(),
+                ];
+
+                // Another chip, adding two words in three of the same columns.
+                let s_add = meta.selector();
+                let add = [extras[0], extras[1], message_schedule];
+                meta.create_gate("add", |meta| {
+                    let s_add = meta.query_selector(s_add);
+                    let a = meta.query_advice(add[0], Rotation::cur());
+                    let b = meta.query_advice(add[1], Rotation::cur());
+                    let c = meta.query_advice(add[2], Rotation::cur());
+
+                    vec![("add", s_add * (a + b - c))]
+                });
+
+                (
+                    Table16Chip::configure_with_columns(meta, message_schedule, extras),
+                    s_add,
+                    add,
+                )
+            }
+
+            fn synthesize(
+                &self,
+                config: Self::Config,
+                mut layouter: impl Layouter<F>,
+            ) -> Result<(), Error> {
+                let (config, s_add, add) = config;
+                Table16Chip::<F>::load(config.clone(), &mut layouter)?;
+
+                // Test vector: "abcd"
+                let digest = Sha256::digest(
+                    Table16Chip::<F>::construct(config),
+                    layouter.namespace(|| "'abcd'"),
+                    &[BlockWord::new(Some(0x61626364))],
+                )?;
+                for (word, expected) in digest.words().iter().zip(DIGEST.iter()) {
+                    assert_eq!(word.value().unwrap(), *expected);
+                }
+
+                // Add the first two words of the digest.
+                layouter.assign_region(
+                    || "add",
+                    |mut region| {
+                        s_add.enable(&mut region, 0)?;
+                        for (idx, word) in digest.words()[..2].iter().enumerate() {
+                            let var = region.assign_advice(
+                                || format!("word {}", idx),
+                                add[idx],
+                                0,
+                                || {
+                                    word.value()
+                                        .map(|value| F::from_u64(value as u64))
+                                        .ok_or(Error::SynthesisError)
+                                },
+                            )?;
+                            region.constrain_equal(var, word.cell())?;
+                        }
+                        region.assign_advice(
+                            || "sum",
+                            add[2],
+                            0,
+                            || self.sum.map(F::from_u64).ok_or(Error::SynthesisError),
+                        )?;
+                        Ok(())
+                    },
+                )
+            }
+        }
+
+        let sum = DIGEST[0] as u64 + DIGEST[1] as u64;
+        let circuit = MyCircuit { sum: Some(sum) };
+        let prover = match MockProver::<Fp>::run(17, &circuit, vec![]) {
+            Ok(prover) => prover,
+            Err(e) => panic!("{:?}", e),
+        };
+        assert_eq!(prover.verify(), Ok(()));
+
+        // The gate of the other chip still applies in the shared columns.
+        let circuit = MyCircuit { sum: Some(sum + 1) };
+        let prover = match MockProver::<Fp>::run(17, &circuit, vec![]) {
+            Ok(prover) => prover,
+            Err(e) => panic!("{:?}", e),
+        };
+        assert!(prover.verify().is_err());
+    }
 }
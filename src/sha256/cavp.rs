@@ -0,0 +1,221 @@
+//! Tests against the byte-oriented SHA-256 test vectors of the NIST Cryptographic
+//! Algorithm Validation Program, from `shabytetestvectors.zip`.
+//!
+//! The `SHA256ShortMsg.rsp` and `SHA256LongMsg.rsp` response files are read from
+//! `src/sha256/cavp/`. Each message is hashed in its own circuit, as bytes witnessed
+//! outside the chip. Only the shortest of the long messages are hashed by default; the
+//! others are hashed by `long_msg_all`, which is ignored unless requested.
+
+use std::convert::TryInto;
+use std::fs;
+use std::path::Path;
+
+use super::{Sha256, BLOCK_SIZE};
+use crate::{table16::message_rows, Byte, CellValue8, Table16Chip, Table16Config};
+use halo2::{
+    arithmetic::FieldExt,
+    circuit::{Layouter, SimpleFloorPlanner},
+    dev::MockProver,
+    pasta::Fp,
+    plonk::{Advice, Circuit, Column, ConstraintSystem, Error},
+};
+
+/// A message and its expected digest.
+#[derive(Clone, Debug)]
+struct Vector {
+    msg: Vec<u8>,
+    md: [u8; 32],
+}
+
+/// Parses the vectors of a CAVP response file. A message of length 0 is given as the
+/// single byte `00`.
+fn parse_rsp(rsp: &str) -> Vec<Vector> {
+    let mut vectors = vec![];
+    let mut len = None;
+    let mut msg = None;
+    for line in rsp.lines() {
+        let mut parts = line.trim().splitn(2, " = ");
+        match (parts.next(), parts.next()) {
+            (Some("Len"), Some(value)) => len = Some(value.parse::<usize>().unwrap()),
+            (Some("Msg"), Some(value)) => msg = Some(decode_hex(value)),
+            (Some("MD"), Some(value)) => {
+                let len = len.take().expect("Len precedes MD");
+                let mut msg: Vec<u8> = msg.take().expect("Msg precedes MD");
+                assert_eq!(len % 8, 0, "only byte-oriented messages are supported");
+                msg.truncate(len / 8);
+                vectors.push(Vector {
+                    msg,
+                    md: decode_hex(value).try_into().unwrap(),
+                });
+            }
+            // Comments, and the [L = 32] header giving the digest length
+            _ => (),
+        }
+    }
+    vectors
+}
+
+fn decode_hex(hex: &str) -> Vec<u8> {
+    (0..hex.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&hex[i..i + 2], 16).unwrap())
+        .collect()
+}
+
+struct MyCircuit {
+    msg: Vec<Option<u8>>,
+    md: Option<[u8; 32]>,
+}
+
+impl<F: FieldExt> Circuit<F> for MyCircuit {
+    type Config = (Table16Config, Column<Advice>);
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        MyCircuit {
+            msg: vec![None; self.msg.len()],
+            md: None,
+        }
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        let message = meta.advice_column();
+        meta.enable_equality(message.into());
+
+        (Table16Chip::configure_with_byte_table(meta), message)
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl Layouter<F>,
+    ) -> Result<(), Error> {
+        let (config, message) = config;
+
+        // Assign the message bytes in a column outside the chip.
+        let msg = layouter.assign_region(
+            || "message",
+            |mut region| {
+                self.msg
+                    .iter()
+                    .enumerate()
+                    .map(|(row, byte)| {
+                        let var = region.assign_advice(
+                            || format!("byte {}", row),
+                            message,
+                            row,
+                            || {
+                                byte.map(|byte| F::from_u64(byte as u64))
+                                    .ok_or(Error::SynthesisError)
+                            },
+                        )?;
+                        Ok(Byte::Assigned(CellValue8::new(var, *byte)))
+                    })
+                    .collect::<Result<Vec<_>, Error>>()
+            },
+        )?;
+
+        Table16Chip::<F>::load(config.clone(), &mut layouter)?;
+
+        let mut hasher = Sha256::new(
+            Table16Chip::<F>::construct(config),
+            layouter.namespace(|| "init"),
+        )?;
+        hasher.update_bytes(layouter.namespace(|| "update"), &msg)?;
+        let digest = hasher.finalize(layouter.namespace(|| "finalize"))?;
+
+        if let Some(md) = self.md {
+            for (word, expected) in digest.words().iter().zip(md.chunks(4)) {
+                let expected = u32::from_be_bytes(expected.try_into().unwrap());
+                assert_eq!(word.value().unwrap(), expected);
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Hashes each vector in the circuit, checking the digest and that the circuit is
+/// satisfied.
+fn check_vectors(vectors: Vec<Vector>) {
+    assert!(!vectors.is_empty());
+    for vector in vectors {
+        // The padding takes at least 9 bytes: the 1 bit and the 64-bit length.
+        let blocks = (vector.msg.len() + 8) / (4 * BLOCK_SIZE) + 1;
+        let mut k = 1;
+        while (1 << k) < message_rows(blocks) {
+            k += 1;
+        }
+
+        let circuit = MyCircuit {
+            msg: vector.msg.into_iter().map(Some).collect(),
+            md: Some(vector.md),
+        };
+        let prover = match MockProver::<Fp>::run(k, &circuit, vec![]) {
+            Ok(prover) => prover,
+            Err(e) => panic!("{:?}", e),
+        };
+        assert_eq!(prover.verify(), Ok(()));
+    }
+}
+
+fn read_rsp(file: &str) -> Vec<Vector> {
+    let path = Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("src/sha256/cavp")
+        .join(file);
+    let rsp = fs::read_to_string(&path)
+        .unwrap_or_else(|e| panic!("cannot read {}: {}", path.display(), e));
+    parse_rsp(&rsp)
+}
+
+#[test]
+fn short_msg() {
+    check_vectors(read_rsp("SHA256ShortMsg.rsp"));
+}
+
+#[test]
+fn long_msg() {
+    // The messages grow by 99 bytes from 163 bytes; the first four take up to 8 blocks.
+    let vectors = read_rsp("SHA256LongMsg.rsp");
+    assert_eq!(vectors.len(), 64);
+    check_vectors(vectors[..4].to_vec());
+}
+
+#[test]
+#[ignore = "hashes 64 messages of up to 101 blocks; run with --ignored"]
+fn long_msg_all() {
+    check_vectors(read_rsp("SHA256LongMsg.rsp"));
+}
+
+#[test]
+fn rsp_format() {
+    // Vectors in the format of the response files, ending with the two-block message of
+    // FIPS 180-2.
+    let rsp = "\
+#  A comment
+[L = 32]
+
+Len = 0
+Msg = 00
+MD = e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855
+
+Len = 8
+Msg = d3
+MD = 28969cdfa74a12c82f3bad960b0b000aca2ac329deea5c2328ebc6f2ba9802c1
+
+Len = 16
+Msg = 11af
+MD = 5ca7133fa735326081558ac312c620eeca9970d1e70a4b95533d956f072d1f98
+
+Len = 448
+Msg = 6162636462636465636465666465666765666768666768696768696a68696a6b696a6b6c6a6b6c6d6b6c6d6e6c6d6e6f6d6e6f706e6f7071
+MD = 248d6a61d20638b8e5c026930c3e6039a33ce45964ff2167f6ecedd419db06c1
+";
+
+    let vectors = parse_rsp(rsp);
+    assert_eq!(
+        vectors.iter().map(|v| v.msg.len()).collect::<Vec<_>>(),
+        vec![0, 1, 2, 56]
+    );
+    check_vectors(vectors);
+}
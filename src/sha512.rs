@@ -0,0 +1,725 @@
+//! Gadgets and chips for the [SHA-512] family of hash functions: SHA-512, SHA-384 and
+//! SHA-512/256.
+//!
+//! [SHA-512]: https://tools.ietf.org/html/rfc6234
+
+use std::cmp::min;
+use std::convert::TryInto;
+use std::fmt;
+
+use halo2::{
+    arithmetic::FieldExt,
+    circuit::{Chip, Layouter},
+    plonk::Error,
+};
+
+/// The size of a SHA-512 block, in 64-bit words.
+pub const SHA512_BLOCK_SIZE: usize = 16;
+/// The size of a SHA-512 digest, in 64-bit words.
+pub const SHA512_DIGEST_SIZE: usize = 8;
+/// The size of a SHA-384 digest, in 64-bit words.
+pub const SHA384_DIGEST_SIZE: usize = 6;
+/// The size of a SHA-512/256 digest, in 64-bit words.
+pub const SHA512_256_DIGEST_SIZE: usize = 4;
+
+/// The set of circuit instructions required to use the [`Sha512`], [`Sha384`] and
+/// [`Sha512Trunc256`] gadgets.
+pub trait Sha512Instructions<F: FieldExt>: Chip<F> {
+    /// Variable representing the SHA-512 internal state.
+    type State: Clone + fmt::Debug;
+    /// Variable representing a 64-bit word of the input block to the SHA-512 compression
+    /// function.
+    type BlockWord: Copy + fmt::Debug + Default;
+    /// Variable representing an 8-bit byte of input.
+    type Byte: Copy + fmt::Debug;
+    /// Variable representing a 64-bit word of the SHA-512 digest.
+    type DigestWord: Copy + fmt::Debug;
+
+    /// Places the SHA-512 IV in the circuit, returning the initial state variable.
+    fn initialization_vector(&self, layouter: &mut impl Layouter<F>) -> Result<Self::State, Error>;
+
+    /// Places the SHA-384 IV in the circuit, returning the initial state variable.
+    fn initialization_vector_384(
+        &self,
+        layouter: &mut impl Layouter<F>,
+    ) -> Result<Self::State, Error>;
+
+    /// Places the SHA-512/256 IV in the circuit, returning the initial state variable.
+    fn initialization_vector_512_256(
+        &self,
+        layouter: &mut impl Layouter<F>,
+    ) -> Result<Self::State, Error>;
+
+    /// Returns a block word that is fixed to `value` in the circuit, as used for message
+    /// padding.
+    fn constant_word(value: u64) -> Self::BlockWord;
+
+    /// Returns a byte that is fixed to `value` in the circuit, as used for message padding.
+    fn constant_byte(value: u8) -> Self::Byte;
+
+    /// Packs eight bytes into a block word, big-endian, constraining each byte to 8 bits.
+    fn pack_bytes(
+        &self,
+        layouter: &mut impl Layouter<F>,
+        bytes: [Self::Byte; 8],
+    ) -> Result<Self::BlockWord, Error>;
+
+    /// Starting from the given state, processes a block of input and returns the chaining
+    /// value, i.e. the final state added to the given state.
+    fn compress(
+        &self,
+        layouter: &mut impl Layouter<F>,
+        state: &Self::State,
+        input: [Self::BlockWord; SHA512_BLOCK_SIZE],
+    ) -> Result<Self::State, Error>;
+
+    /// Converts the given state into a message digest.
+    fn digest(
+        &self,
+        layouter: &mut impl Layouter<F>,
+        state: &Self::State,
+    ) -> Result<[Self::DigestWord; SHA512_DIGEST_SIZE], Error>;
+}
+
+/// The output of a SHA-512 circuit invocation.
+#[derive(Debug)]
+pub struct Sha512Digest<DigestWord>([DigestWord; SHA512_DIGEST_SIZE]);
+
+impl<DigestWord> Sha512Digest<DigestWord> {
+    /// Returns the words of the digest, big-endian.
+    pub fn words(&self) -> &[DigestWord; SHA512_DIGEST_SIZE] {
+        &self.0
+    }
+}
+
+/// A gadget that constrains a SHA-512 invocation. It supports input at a granularity of
+/// 64 bits, or of 8 bits through [`Sha512::update_bytes`].
+#[derive(Debug)]
+pub struct Sha512<F: FieldExt, CS: Sha512Instructions<F>> {
+    chip: CS,
+    state: CS::State,
+    cur_block: Vec<CS::BlockWord>,
+    // The bytes of a partial word given to `update_bytes`, not yet packed into `cur_block`.
+    cur_word: Vec<CS::Byte>,
+    length: usize,
+}
+
+impl<F: FieldExt, Sha512Chip: Sha512Instructions<F>> Sha512<F, Sha512Chip> {
+    /// Create a new hasher instance.
+    pub fn new(chip: Sha512Chip, mut layouter: impl Layouter<F>) -> Result<Self, Error> {
+        let state = chip.initialization_vector(&mut layouter)?;
+        Ok(Self::with_initial_state(chip, state))
+    }
+
+    // Create a hasher instance starting from an initial state already placed in the
+    // circuit, such as the IV of a truncated variant.
+    fn with_initial_state(chip: Sha512Chip, state: Sha512Chip::State) -> Self {
+        Sha512 {
+            chip,
+            state,
+            cur_block: Vec::with_capacity(SHA512_BLOCK_SIZE),
+            cur_word: Vec::with_capacity(8),
+            length: 0,
+        }
+    }
+
+    /// Digest data, updating the internal state.
+    ///
+    /// # Panics
+    ///
+    /// Panics if a previous call to [`Sha512::update_bytes`] left a partial word.
+    pub fn update(
+        &mut self,
+        mut layouter: impl Layouter<F>,
+        data: &[Sha512Chip::BlockWord],
+    ) -> Result<(), Error> {
+        assert!(
+            self.cur_word.is_empty(),
+            "cannot update with words after a partial word of bytes"
+        );
+        self.length += data.len() * 64;
+        self.process_words(&mut layouter, data)
+    }
+
+    /// Digest data given as bytes, updating the internal state. Each group of eight bytes
+    /// is packed big-endian into a word; the bytes of a trailing partial word are kept
+    /// until more data is given, or are padded in [`Sha512::finalize`].
+    pub fn update_bytes(
+        &mut self,
+        mut layouter: impl Layouter<F>,
+        data: &[Sha512Chip::Byte],
+    ) -> Result<(), Error> {
+        self.length += data.len() * 8;
+
+        let mut words = Vec::with_capacity((self.cur_word.len() + data.len()) / 8);
+        for byte in data {
+            self.cur_word.push(*byte);
+            if self.cur_word.len() == 8 {
+                words.push(self.pack_cur_word(&mut layouter)?);
+            }
+        }
+
+        self.process_words(&mut layouter, &words)
+    }
+
+    // Append words to the message, processing every block they complete.
+    fn process_words(
+        &mut self,
+        layouter: &mut impl Layouter<F>,
+        mut data: &[Sha512Chip::BlockWord],
+    ) -> Result<(), Error> {
+        // Fill the current block, if possible.
+        let remaining = SHA512_BLOCK_SIZE - self.cur_block.len();
+        let (l, r) = data.split_at(min(remaining, data.len()));
+        self.cur_block.extend_from_slice(l);
+        data = r;
+
+        // If we still don't have a full block, we are done.
+        if self.cur_block.len() < SHA512_BLOCK_SIZE {
+            return Ok(());
+        }
+
+        // Process the now-full current block.
+        self.process_cur_block(layouter)?;
+
+        // Process any additional full blocks.
+        let mut chunks_iter = data.chunks_exact(SHA512_BLOCK_SIZE);
+        for chunk in &mut chunks_iter {
+            self.state = self.chip.compress(
+                layouter,
+                &self.state,
+                chunk.try_into().expect("chunk.len() == SHA512_BLOCK_SIZE"),
+            )?;
+        }
+
+        // Cache the remaining partial block, if any.
+        let rem = chunks_iter.remainder();
+        self.cur_block.extend_from_slice(rem);
+
+        Ok(())
+    }
+
+    /// Retrieve result and consume hasher instance.
+    pub fn finalize(
+        mut self,
+        mut layouter: impl Layouter<F>,
+    ) -> Result<Sha512Digest<Sha512Chip::DigestWord>, Error> {
+        // Pad the message as specified in FIPS 180-4, section 5.1.2. The appended `1` bit
+        // is the top bit of the byte following the message. If the 128-bit message length
+        // does not fit in the current block, an extra block of padding is processed.
+        let zero = Sha512Chip::constant_word(0);
+        if self.cur_word.is_empty() {
+            self.cur_block
+                .push(Sha512Chip::constant_word(0x8000_0000_0000_0000));
+        } else {
+            self.cur_word.push(Sha512Chip::constant_byte(0x80));
+            self.cur_word.resize(8, Sha512Chip::constant_byte(0));
+            let word = self.pack_cur_word(&mut layouter)?;
+            self.cur_block.push(word);
+        }
+        if self.cur_block.len() > SHA512_BLOCK_SIZE - 2 {
+            self.cur_block.resize(SHA512_BLOCK_SIZE, zero);
+            self.process_cur_block(&mut layouter)?;
+        }
+        self.cur_block.resize(SHA512_BLOCK_SIZE - 2, zero);
+
+        // Append the message length in bits, as a big-endian 128-bit integer.
+        let length = self.length as u128;
+        self.cur_block
+            .push(Sha512Chip::constant_word((length >> 64) as u64));
+        self.cur_block
+            .push(Sha512Chip::constant_word(length as u64));
+        self.process_cur_block(&mut layouter)?;
+
+        self.chip
+            .digest(&mut layouter, &self.state)
+            .map(Sha512Digest)
+    }
+
+    // Pack the full current word, clearing it.
+    fn pack_cur_word(
+        &mut self,
+        layouter: &mut impl Layouter<F>,
+    ) -> Result<Sha512Chip::BlockWord, Error> {
+        let bytes = self.cur_word[..].try_into().expect("cur_word.len() == 8");
+        self.cur_word.clear();
+        self.chip.pack_bytes(layouter, bytes)
+    }
+
+    // Process the full current block, clearing it.
+    fn process_cur_block(&mut self, layouter: &mut impl Layouter<F>) -> Result<(), Error> {
+        let block = self.cur_block[..]
+            .try_into()
+            .expect("cur_block.len() == SHA512_BLOCK_SIZE");
+        self.cur_block.clear();
+        self.state = self.chip.compress(layouter, &self.state, block)?;
+        Ok(())
+    }
+
+    /// Convenience function to compute hash of the data. It will handle hasher creation,
+    /// data feeding and finalization.
+    pub fn digest(
+        chip: Sha512Chip,
+        mut layouter: impl Layouter<F>,
+        data: &[Sha512Chip::BlockWord],
+    ) -> Result<Sha512Digest<Sha512Chip::DigestWord>, Error> {
+        let mut hasher = Self::new(chip, layouter.namespace(|| "init"))?;
+        hasher.update(layouter.namespace(|| "update"), data)?;
+        hasher.finalize(layouter.namespace(|| "finalize"))
+    }
+}
+
+/// The output of a SHA-384 circuit invocation.
+#[derive(Debug)]
+pub struct Sha384Digest<DigestWord>([DigestWord; SHA384_DIGEST_SIZE]);
+
+impl<DigestWord> Sha384Digest<DigestWord> {
+    /// Returns the words of the digest, big-endian.
+    pub fn words(&self) -> &[DigestWord; SHA384_DIGEST_SIZE] {
+        &self.0
+    }
+}
+
+/// A gadget that constrains a SHA-384 invocation: SHA-512 with a different IV, and with
+/// the digest truncated to its first six words.
+#[derive(Debug)]
+pub struct Sha384<F: FieldExt, CS: Sha512Instructions<F>> {
+    inner: Sha512<F, CS>,
+}
+
+impl<F: FieldExt, Sha512Chip: Sha512Instructions<F>> Sha384<F, Sha512Chip> {
+    /// Create a new hasher instance.
+    pub fn new(chip: Sha512Chip, mut layouter: impl Layouter<F>) -> Result<Self, Error> {
+        let state = chip.initialization_vector_384(&mut layouter)?;
+        Ok(Sha384 {
+            inner: Sha512::with_initial_state(chip, state),
+        })
+    }
+
+    /// Digest data, updating the internal state.
+    ///
+    /// # Panics
+    ///
+    /// Panics if a previous call to `update_bytes` left a partial word.
+    pub fn update(
+        &mut self,
+        layouter: impl Layouter<F>,
+        data: &[Sha512Chip::BlockWord],
+    ) -> Result<(), Error> {
+        self.inner.update(layouter, data)
+    }
+
+    /// Digest data given as bytes, updating the internal state, as in
+    /// [`Sha512::update_bytes`].
+    pub fn update_bytes(
+        &mut self,
+        layouter: impl Layouter<F>,
+        data: &[Sha512Chip::Byte],
+    ) -> Result<(), Error> {
+        self.inner.update_bytes(layouter, data)
+    }
+
+    /// Retrieve result and consume hasher instance.
+    pub fn finalize(
+        self,
+        layouter: impl Layouter<F>,
+    ) -> Result<Sha384Digest<Sha512Chip::DigestWord>, Error> {
+        let digest = self.inner.finalize(layouter)?;
+        let words = digest.words();
+        Ok(Sha384Digest([
+            words[0], words[1], words[2], words[3], words[4], words[5],
+        ]))
+    }
+
+    /// Convenience function to compute hash of the data. It will handle hasher creation,
+    /// data feeding and finalization.
+    pub fn digest(
+        chip: Sha512Chip,
+        mut layouter: impl Layouter<F>,
+        data: &[Sha512Chip::BlockWord],
+    ) -> Result<Sha384Digest<Sha512Chip::DigestWord>, Error> {
+        let mut hasher = Self::new(chip, layouter.namespace(|| "init"))?;
+        hasher.update(layouter.namespace(|| "update"), data)?;
+        hasher.finalize(layouter.namespace(|| "finalize"))
+    }
+}
+
+/// The output of a SHA-512/256 circuit invocation.
+#[derive(Debug)]
+pub struct Sha512Trunc256Digest<DigestWord>([DigestWord; SHA512_256_DIGEST_SIZE]);
+
+impl<DigestWord> Sha512Trunc256Digest<DigestWord> {
+    /// Returns the words of the digest, big-endian.
+    pub fn words(&self) -> &[DigestWord; SHA512_256_DIGEST_SIZE] {
+        &self.0
+    }
+}
+
+/// A gadget that constrains a SHA-512/256 invocation: SHA-512 with a different IV, and
+/// with the digest truncated to its first four words.
+#[derive(Debug)]
+pub struct Sha512Trunc256<F: FieldExt, CS: Sha512Instructions<F>> {
+    inner: Sha512<F, CS>,
+}
+
+impl<F: FieldExt, Sha512Chip: Sha512Instructions<F>> Sha512Trunc256<F, Sha512Chip> {
+    /// Create a new hasher instance.
+    pub fn new(chip: Sha512Chip, mut layouter: impl Layouter<F>) -> Result<Self, Error> {
+        let state = chip.initialization_vector_512_256(&mut layouter)?;
+        Ok(Sha512Trunc256 {
+            inner: Sha512::with_initial_state(chip, state),
+        })
+    }
+
+    /// Digest data, updating the internal state.
+    ///
+    /// # Panics
+    ///
+    /// Panics if a previous call to `update_bytes` left a partial word.
+    pub fn update(
+        &mut self,
+        layouter: impl Layouter<F>,
+        data: &[Sha512Chip::BlockWord],
+    ) -> Result<(), Error> {
+        self.inner.update(layouter, data)
+    }
+
+    /// Digest data given as bytes, updating the internal state, as in
+    /// [`Sha512::update_bytes`].
+    pub fn update_bytes(
+        &mut self,
+        layouter: impl Layouter<F>,
+        data: &[Sha512Chip::Byte],
+    ) -> Result<(), Error> {
+        self.inner.update_bytes(layouter, data)
+    }
+
+    /// Retrieve result and consume hasher instance.
+    pub fn finalize(
+        self,
+        layouter: impl Layouter<F>,
+    ) -> Result<Sha512Trunc256Digest<Sha512Chip::DigestWord>, Error> {
+        let digest = self.inner.finalize(layouter)?;
+        let words = digest.words();
+        Ok(Sha512Trunc256Digest([
+            words[0], words[1], words[2], words[3],
+        ]))
+    }
+
+    /// Convenience function to compute hash of the data. It will handle hasher creation,
+    /// data feeding and finalization.
+    pub fn digest(
+        chip: Sha512Chip,
+        mut layouter: impl Layouter<F>,
+        data: &[Sha512Chip::BlockWord],
+    ) -> Result<Sha512Trunc256Digest<Sha512Chip::DigestWord>, Error> {
+        let mut hasher = Self::new(chip, layouter.namespace(|| "init"))?;
+        hasher.update(layouter.namespace(|| "update"), data)?;
+        hasher.finalize(layouter.namespace(|| "finalize"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Sha384, Sha512, Sha512Trunc256};
+    use crate::{
+        BlockWord64, Byte, CellValue64, CellValue8, Table16Sha512Chip, Table16Sha512Config, Word64,
+    };
+    use halo2::{
+        arithmetic::FieldExt,
+        circuit::{Layouter, SimpleFloorPlanner},
+        dev::MockProver,
+        pasta::Fp,
+        plonk::{Advice, Circuit, Column, ConstraintSystem, Error},
+    };
+
+    // Packs a message into big-endian 64-bit words; its length must be a multiple of 8.
+    fn words(message: &[u8]) -> Vec<BlockWord64> {
+        message
+            .chunks(8)
+            .map(|word| {
+                let mut bytes = [0; 8];
+                bytes.copy_from_slice(word);
+                BlockWord64::Witness(Some(u64::from_be_bytes(bytes)))
+            })
+            .collect()
+    }
+
+    fn check(digest: &[Word64], expected: &[u64]) {
+        assert_eq!(digest.len(), expected.len());
+        for (word, expected) in digest.iter().zip(expected.iter()) {
+            assert_eq!(word.value().unwrap(), *expected);
+        }
+    }
+
+    #[test]
+    #[allow(clippy::unreadable_literal)]
+    fn sha512() {
+        struct MyCircuit {}
+
+        impl<F: FieldExt> Circuit<F> for MyCircuit {
+            type Config = Table16Sha512Config;
+            type FloorPlanner = SimpleFloorPlanner;
+
+            fn without_witnesses(&self) -> Self {
+                MyCircuit {}
+            }
+
+            fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+                Table16Sha512Chip::configure(meta)
+            }
+
+            fn synthesize(
+                &self,
+                config: Self::Config,
+                mut layouter: impl Layouter<F>,
+            ) -> Result<(), Error> {
+                Table16Sha512Chip::<F>::load(config.clone(), &mut layouter)?;
+
+                // Test vector: "abcdefgh" (64 bits, so the padding fits in the block)
+                let digest = Sha512::digest(
+                    Table16Sha512Chip::<F>::construct(config.clone()),
+                    layouter.namespace(|| "'abcdefgh'"),
+                    &words(b"abcdefgh"),
+                )?;
+                check(
+                    digest.words(),
+                    &[
+                        0xa3a8c81bc97c2560,
+                        0x010d7389bc88aac9,
+                        0x74a104e0e2381220,
+                        0xc6e084c4dccd1d2d,
+                        0x17d4f86db31c2a85,
+                        0x1dc80e6681d74733,
+                        0xc55dcd03dd96f606,
+                        0x2cdda12a291ae6ce,
+                    ],
+                );
+
+                // Test vector: "abcdefghbcdefghi...nopqrstu", in two updates
+                // The padding is a block of its own.
+                let input = words(
+                    b"abcdefghbcdefghicdefghijdefghijkefghijklfghijklmghijklmnhijklmnoijklmnopjklmnopqklmnopqrlmnopqrsmnopqrstnopqrstu",
+                );
+                let mut hasher = Sha512::new(
+                    Table16Sha512Chip::<F>::construct(config),
+                    layouter.namespace(|| "'abcdefghbcdefghi...' init"),
+                )?;
+                hasher.update(layouter.namespace(|| "'abcdefgh...'"), &input[..5])?;
+                hasher.update(layouter.namespace(|| "'...nopqrstu'"), &input[5..])?;
+                let digest =
+                    hasher.finalize(layouter.namespace(|| "'abcdefghbcdefghi...' finalize"))?;
+                check(
+                    digest.words(),
+                    &[
+                        0x8e959b75dae313da,
+                        0x8cf4f72814fc143f,
+                        0x8f7779c6eb9f7fa1,
+                        0x7299aeadb6889018,
+                        0x501d289e4900f7e4,
+                        0x331b99dec4b5433a,
+                        0xc7d329eeb6dd2654,
+                        0x5e96e55b874be909,
+                    ],
+                );
+
+                Ok(())
+            }
+        }
+
+        let circuit: MyCircuit = MyCircuit {};
+
+        let prover = match MockProver::<Fp>::run(17, &circuit, vec![]) {
+            Ok(prover) => prover,
+            Err(e) => panic!("{:?}", e),
+        };
+        assert_eq!(prover.verify(), Ok(()));
+    }
+
+    #[test]
+    #[allow(clippy::unreadable_literal)]
+    fn assigned_input() {
+        struct MyCircuit {}
+
+        impl<F: FieldExt> Circuit<F> for MyCircuit {
+            type Config = (Table16Sha512Config, Column<Advice>);
+            type FloorPlanner = SimpleFloorPlanner;
+
+            fn without_witnesses(&self) -> Self {
+                MyCircuit {}
+            }
+
+            fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+                let message = meta.advice_column();
+                meta.enable_equality(message.into());
+
+                (Table16Sha512Chip::configure(meta), message)
+            }
+
+            fn synthesize(
+                &self,
+                config: Self::Config,
+                mut layouter: impl Layouter<F>,
+            ) -> Result<(), Error> {
+                let (config, message) = config;
+
+                // Assign the bytes of "abc", and the word "abcdefgh", in a column outside
+                // the chip.
+                let (bytes, word) = layouter.assign_region(
+                    || "message",
+                    |mut region| {
+                        let bytes = b"abc"
+                            .iter()
+                            .enumerate()
+                            .map(|(row, byte)| {
+                                let var = region.assign_advice(
+                                    || format!("byte {}", row),
+                                    message,
+                                    row,
+                                    || Ok(F::from_u64(*byte as u64)),
+                                )?;
+                                Ok(Byte::Assigned(CellValue8::new(var, Some(*byte))))
+                            })
+                            .collect::<Result<Vec<_>, Error>>()?;
+
+                        let value = u64::from_be_bytes(*b"abcdefgh");
+                        let var = region.assign_advice(
+                            || "word",
+                            message,
+                            3,
+                            || Ok(F::from_u64(value)),
+                        )?;
+                        let word = BlockWord64::Assigned(CellValue64::new(var, Some(value)));
+
+                        Ok((bytes, word))
+                    },
+                )?;
+
+                Table16Sha512Chip::<F>::load(config.clone(), &mut layouter)?;
+
+                // Test vector: "abc", as bytes
+                let mut hasher = Sha512::new(
+                    Table16Sha512Chip::<F>::construct(config.clone()),
+                    layouter.namespace(|| "'abc' init"),
+                )?;
+                hasher.update_bytes(layouter.namespace(|| "'ab'"), &bytes[..2])?;
+                hasher.update_bytes(layouter.namespace(|| "'c'"), &bytes[2..])?;
+                let digest = hasher.finalize(layouter.namespace(|| "'abc' finalize"))?;
+                check(
+                    digest.words(),
+                    &[
+                        0xddaf35a193617aba,
+                        0xcc417349ae204131,
+                        0x12e6fa4e89a97ea2,
+                        0x0a9eeee64b55d39a,
+                        0x2192992a274fc1a8,
+                        0x36ba3c23a3feebbd,
+                        0x454d4423643ce80e,
+                        0x2a9ac94fa54ca49f,
+                    ],
+                );
+
+                // Test vector: "abcdefgh", as an assigned word
+                let digest = Sha512::digest(
+                    Table16Sha512Chip::<F>::construct(config),
+                    layouter.namespace(|| "'abcdefgh'"),
+                    &[word],
+                )?;
+                check(
+                    digest.words(),
+                    &[
+                        0xa3a8c81bc97c2560,
+                        0x010d7389bc88aac9,
+                        0x74a104e0e2381220,
+                        0xc6e084c4dccd1d2d,
+                        0x17d4f86db31c2a85,
+                        0x1dc80e6681d74733,
+                        0xc55dcd03dd96f606,
+                        0x2cdda12a291ae6ce,
+                    ],
+                );
+
+                Ok(())
+            }
+        }
+
+        let circuit: MyCircuit = MyCircuit {};
+
+        let prover = match MockProver::<Fp>::run(17, &circuit, vec![]) {
+            Ok(prover) => prover,
+            Err(e) => panic!("{:?}", e),
+        };
+        assert_eq!(prover.verify(), Ok(()));
+    }
+
+    #[test]
+    #[allow(clippy::unreadable_literal)]
+    fn truncated_variants() {
+        struct MyCircuit {}
+
+        impl<F: FieldExt> Circuit<F> for MyCircuit {
+            type Config = Table16Sha512Config;
+            type FloorPlanner = SimpleFloorPlanner;
+
+            fn without_witnesses(&self) -> Self {
+                MyCircuit {}
+            }
+
+            fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+                Table16Sha512Chip::configure(meta)
+            }
+
+            fn synthesize(
+                &self,
+                config: Self::Config,
+                mut layouter: impl Layouter<F>,
+            ) -> Result<(), Error> {
+                Table16Sha512Chip::<F>::load(config.clone(), &mut layouter)?;
+
+                // Test vector: "", SHA-384
+                let digest = Sha384::digest(
+                    Table16Sha512Chip::<F>::construct(config.clone()),
+                    layouter.namespace(|| "SHA-384 ''"),
+                    &[],
+                )?;
+                check(
+                    digest.words(),
+                    &[
+                        0x38b060a751ac9638,
+                        0x4cd9327eb1b1e36a,
+                        0x21fdb71114be0743,
+                        0x4c0cc7bf63f6e1da,
+                        0x274edebfe76f65fb,
+                        0xd51ad2f14898b95b,
+                    ],
+                );
+
+                // Test vector: "", SHA-512/256
+                let digest = Sha512Trunc256::digest(
+                    Table16Sha512Chip::<F>::construct(config),
+                    layouter.namespace(|| "SHA-512/256 ''"),
+                    &[],
+                )?;
+                check(
+                    digest.words(),
+                    &[
+                        0xc672b8d1ef56ed28,
+                        0xab87c3622c511406,
+                        0x9bdd3ad7b8f97374,
+                        0x98d0c01ecef0967a,
+                    ],
+                );
+
+                Ok(())
+            }
+        }
+
+        let circuit: MyCircuit = MyCircuit {};
+
+        let prover = match MockProver::<Fp>::run(17, &circuit, vec![]) {
+            Ok(prover) => prover,
+            Err(e) => panic!("{:?}", e),
+        };
+        assert_eq!(prover.verify(), Ok(()));
+    }
+}
@@ -1,22 +1,42 @@
+use std::convert::TryInto;
 use std::marker::PhantomData;
 
-use crate::Sha256Instructions;
+use crate::{
+    BitcoinHeaderInstructions, HmacSha256Instructions, Sha224Instructions, Sha256Instructions,
+    Sha256MidstateInstructions, Sha256PublicInstructions, Sha256VarLenInstructions,
+};
 use halo2::{
     arithmetic::FieldExt,
     circuit::{Cell, Chip, Layouter, Region},
-    plonk::{Advice, Column, ConstraintSystem, Error},
+    plonk::{Advice, Column, ConstraintSystem, Error, Instance},
 };
 
 mod compression;
 mod gates;
+mod merkle;
 mod message_schedule;
+pub mod native;
+mod select;
+mod sha512;
 mod spread_table;
+#[cfg(test)]
+mod tamper;
+mod target;
 mod util;
+mod var_len;
+mod xor;
 
 use compression::*;
 use gates::*;
+pub use merkle::{NodeHash, Sha256MerkleChip};
 use message_schedule::*;
+use select::SelectConfig;
+pub use sha512::{BlockWord64, State64, Table16Sha512Chip, Table16Sha512Config, Word64};
 use spread_table::*;
+pub use spread_table::{SpreadTableChip, SpreadTableConfig, SpreadWord};
+use target::*;
+use var_len::*;
+use xor::*;
 
 const ROUNDS: usize = 64;
 const STATE: usize = 8;
@@ -44,15 +64,174 @@ const IV: [u32; STATE] = [
     0x5be0_cd19,
 ];
 
-#[derive(Clone, Copy, Debug, Default)]
+const IV_224: [u32; STATE] = [
+    0xc105_9ed8,
+    0x367c_d507,
+    0x3070_dd17,
+    0xf70e_5939,
+    0xffc0_0b31,
+    0x6858_1511,
+    0x64f9_8fa7,
+    0xbefa_4fa4,
+];
+
+/// Returns an upper bound on the rows taken by hashing a message of `blocks` blocks given
+/// as bytes, from the IV to the digest: every word is counted as packed from bytes, and
+/// each region as placed below all the previous ones.
+#[cfg(test)]
+pub(crate) fn message_rows(blocks: usize) -> usize {
+    let block_rows = super::BLOCK_SIZE * PACK_ROWS
+        + schedule_rows()
+        + initialize_with_state_rows()
+        + compress_rows();
+    initialize_with_iv_rows() + blocks * block_rows + DIGEST_ROWS
+}
+
+#[derive(Clone, Copy, Debug)]
 /// A word in a `Table16` message block.
-pub struct BlockWord(pub Option<u32>);
+pub enum BlockWord {
+    /// A word witnessed by the prover.
+    Witness(Option<u32>),
+    /// A word fixed in the circuit, such as message padding.
+    Constant(u32),
+    /// A word assigned elsewhere in the circuit, such as one packed from bytes or output
+    /// by another chip, which is copied into the message schedule. The cell must be in a
+    /// column with equality enabled; its value is range-checked to 32 bits.
+    Assigned(CellValue32),
+}
+
+impl Default for BlockWord {
+    fn default() -> Self {
+        BlockWord::Witness(None)
+    }
+}
+
+impl From<Option<u32>> for BlockWord {
+    fn from(value: Option<u32>) -> Self {
+        BlockWord::Witness(value)
+    }
+}
+
+impl BlockWord {
+    /// Constructs a word witnessed by the prover.
+    pub fn new(value: Option<u32>) -> Self {
+        BlockWord::Witness(value)
+    }
+
+    /// Returns the value of this word, if known.
+    pub fn value(&self) -> Option<u32> {
+        match self {
+            BlockWord::Witness(value) => *value,
+            BlockWord::Constant(value) => Some(*value),
+            BlockWord::Assigned(word) => word.value,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+/// A byte of a `Table16` message.
+pub enum Byte {
+    /// A byte assigned elsewhere in the circuit, which is copied into the message.
+    Assigned(CellValue8),
+    /// A byte fixed in the circuit, such as message padding.
+    Constant(u8),
+}
+
+impl Byte {
+    /// Returns the value of this byte, if known.
+    pub fn value(&self) -> Option<u8> {
+        match self {
+            Byte::Assigned(byte) => byte.value,
+            Byte::Constant(value) => Some(*value),
+        }
+    }
+}
 
 pub trait CellValue<T> {
     fn var(&self) -> Cell;
     fn value(&self) -> Option<T>;
 }
 
+#[derive(Clone, Copy, Debug)]
+/// A word of a `Table16` digest, assigned in the circuit.
+pub struct DigestWord {
+    cell: Cell,
+    value: Option<u32>,
+}
+
+impl DigestWord {
+    pub fn new(cell: Cell, value: Option<u32>) -> Self {
+        DigestWord { cell, value }
+    }
+
+    /// Returns the cell this word is assigned to, for copying it elsewhere in the circuit.
+    pub fn cell(&self) -> Cell {
+        self.cell
+    }
+
+    /// Returns the value of this word, if known.
+    pub fn value(&self) -> Option<u32> {
+        self.value
+    }
+}
+
+impl<F: FieldExt> CellValue<F> for DigestWord {
+    fn var(&self) -> Cell {
+        self.cell
+    }
+    fn value(&self) -> Option<F> {
+        self.value.map(|value| F::from_u64(value as u64))
+    }
+}
+
+impl From<DigestWord> for BlockWord {
+    fn from(word: DigestWord) -> Self {
+        BlockWord::Assigned(CellValue32::new(word.cell, word.value))
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct Bit {
+    var: Cell,
+    value: Option<bool>,
+}
+
+impl<F: FieldExt> CellValue<F> for Bit {
+    fn var(&self) -> Cell {
+        self.var
+    }
+    fn value(&self) -> Option<F> {
+        self.value.map(|value| F::from_u64(value as u64))
+    }
+}
+
+impl Bit {
+    pub fn new(var: Cell, value: Option<bool>) -> Self {
+        Bit { var, value }
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct CellValue8 {
+    var: Cell,
+    value: Option<u8>,
+}
+
+impl<F: FieldExt> CellValue<F> for CellValue8 {
+    fn var(&self) -> Cell {
+        self.var
+    }
+    fn value(&self) -> Option<F> {
+        self.value.map(|value| F::from_u64(value as u64))
+    }
+}
+
+impl CellValue8 {
+    pub fn new(var: Cell, value: Option<u8>) -> Self {
+        CellValue8 { var, value }
+    }
+}
+
 #[derive(Clone, Copy, Debug)]
 pub struct CellValue16 {
     var: Cell,
@@ -95,6 +274,27 @@ impl<F: FieldExt> CellValue<F> for CellValue32 {
     }
 }
 
+#[derive(Clone, Copy, Debug)]
+pub struct CellValue64 {
+    var: Cell,
+    value: Option<u64>,
+}
+
+impl CellValue64 {
+    pub fn new(var: Cell, value: Option<u64>) -> Self {
+        CellValue64 { var, value }
+    }
+}
+
+impl<F: FieldExt> CellValue<F> for CellValue64 {
+    fn var(&self) -> Cell {
+        self.var
+    }
+    fn value(&self) -> Option<F> {
+        self.value.map(F::from_u64)
+    }
+}
+
 #[allow(clippy::from_over_into)]
 impl Into<CellValue32> for CellValue16 {
     fn into(self) -> CellValue32 {
@@ -105,9 +305,77 @@ impl Into<CellValue32> for CellValue16 {
 /// Configuration for a [`Table16Chip`].
 #[derive(Clone, Debug)]
 pub struct Table16Config {
+    message_schedule_column: Column<Advice>,
+    extras: [Column<Advice>; 6],
     lookup: SpreadTableConfig,
     message_schedule: MessageScheduleConfig,
     compression: CompressionConfig,
+    var_len: Option<VarLenConfig>,
+    xor: Option<XorConfig>,
+    target: Option<TargetConfig>,
+    select: SelectConfig,
+    instance: Option<Column<Instance>>,
+}
+
+impl Table16Config {
+    /// Returns the configuration of the spread table, which other chips can issue lookups
+    /// against with [`SpreadTableConfig::lookup`]. The table is loaded by
+    /// [`Table16Chip::load`].
+    pub fn spread_table(&self) -> &SpreadTableConfig {
+        &self.lookup
+    }
+
+    /// Adds an instance column, to which digests can be constrained with
+    /// [`Sha256::constrain_digest_public`](crate::Sha256::constrain_digest_public).
+    pub fn with_instance<F: FieldExt>(
+        mut self,
+        meta: &mut ConstraintSystem<F>,
+        instance: Column<Instance>,
+    ) -> Self {
+        meta.enable_equality(instance.into());
+        self.instance = Some(instance);
+        self
+    }
+
+    /// Adds the gates for padding messages of a witnessed length, which
+    /// [`Sha256::digest_var_len`](crate::Sha256::digest_var_len) requires. They take six
+    /// selectors, so are only configured on request; without them, the chip returns an
+    /// error when asked to pad such a message.
+    pub fn with_var_len<F: FieldExt>(mut self, meta: &mut ConstraintSystem<F>) -> Self {
+        self.var_len = Some(VarLenConfig::configure(
+            meta,
+            self.message_schedule_column,
+            self.extras,
+        ));
+        self
+    }
+
+    /// Adds the gate for XORing words with a constant, which
+    /// [`HmacSha256`](crate::HmacSha256) requires to pad its key. The gate takes a fixed
+    /// column and a selector, so is only configured on request; without it, the chip
+    /// returns an error when asked to XOR words.
+    pub fn with_hmac<F: FieldExt>(mut self, meta: &mut ConstraintSystem<F>) -> Self {
+        self.xor = Some(XorConfig::configure(
+            meta,
+            self.lookup.input.clone(),
+            self.extras[0],
+        ));
+        self
+    }
+
+    /// Adds the gates for comparing a hash against a compact target, which
+    /// [`verify_header`](crate::verify_header) requires to check the proof of work. The
+    /// gates take three selectors, so are only configured on request; without them, the
+    /// chip returns an error when asked to check a target.
+    pub fn with_bitcoin_target<F: FieldExt>(mut self, meta: &mut ConstraintSystem<F>) -> Self {
+        self.target = Some(TargetConfig::configure(
+            meta,
+            self.lookup.input.clone(),
+            self.message_schedule_column,
+            self.extras,
+        ));
+        self
+    }
 }
 
 /// A chip that implements SHA-256 with a maximum lookup table size of $2^16$.
@@ -139,6 +407,47 @@ impl<F: FieldExt> Table16Chip<F> {
     }
 
     pub fn configure(meta: &mut ConstraintSystem<F>) -> <Self as Chip<F>>::Config {
+        let (message_schedule, extras) = Self::advice_columns(meta);
+        Self::configure_with_columns(meta, message_schedule, extras)
+    }
+
+    /// Configures the chip with advice columns that can be shared with other chips, which
+    /// may use them in regions of their own. Equality is enabled on all but the last of
+    /// these columns. The three advice columns holding the inputs to the spread table are
+    /// still allocated by the chip, as every row of them is looked up in the table.
+    pub fn configure_with_columns(
+        meta: &mut ConstraintSystem<F>,
+        message_schedule: Column<Advice>,
+        extras: [Column<Advice>; 6],
+    ) -> <Self as Chip<F>>::Config {
+        Self::configure_with_lookup(meta, message_schedule, extras, SpreadTableChip::configure)
+    }
+
+    /// Configures the chip to look up the spread forms of 16-bit words byte by byte, in a
+    /// table of fewer than $2^{11}$ rows instead of $2^{16}$. This takes four more advice
+    /// columns, but circuits with few compressions can then be built with $k = 12$.
+    pub fn configure_with_byte_table(meta: &mut ConstraintSystem<F>) -> <Self as Chip<F>>::Config {
+        let (message_schedule, extras) = Self::advice_columns(meta);
+
+        // - Four advice columns for the bytes of each looked-up word.
+        let bytes = [
+            meta.advice_column(),
+            meta.advice_column(),
+            meta.advice_column(),
+            meta.advice_column(),
+        ];
+
+        Self::configure_with_lookup(
+            meta,
+            message_schedule,
+            extras,
+            |meta, input_tag, input_dense, input_spread| {
+                SpreadTableChip::configure_bytes(meta, input_tag, input_dense, input_spread, bytes)
+            },
+        )
+    }
+
+    fn advice_columns(meta: &mut ConstraintSystem<F>) -> (Column<Advice>, [Column<Advice>; 6]) {
         // Columns required by this chip:
         let message_schedule = meta.advice_column();
         let extras = [
@@ -150,12 +459,26 @@ impl<F: FieldExt> Table16Chip<F> {
             meta.advice_column(),
         ];
 
+        (message_schedule, extras)
+    }
+
+    fn configure_with_lookup(
+        meta: &mut ConstraintSystem<F>,
+        message_schedule: Column<Advice>,
+        extras: [Column<Advice>; 6],
+        configure_lookup: impl FnOnce(
+            &mut ConstraintSystem<F>,
+            Column<Advice>,
+            Column<Advice>,
+            Column<Advice>,
+        ) -> SpreadTableConfig,
+    ) -> <Self as Chip<F>>::Config {
         // - Three advice columns to interact with the lookup table.
         let input_tag = meta.advice_column();
         let input_dense = meta.advice_column();
         let input_spread = meta.advice_column();
 
-        let lookup = SpreadTableChip::configure(meta, input_tag, input_dense, input_spread);
+        let lookup = configure_lookup(meta, input_tag, input_dense, input_spread);
         let lookup_inputs = lookup.input.clone();
 
         // Rename these here for ease of matching the gates to the specification.
@@ -175,32 +498,124 @@ impl<F: FieldExt> Table16Chip<F> {
             meta.enable_equality((*column).into());
         }
 
+        // - One fixed column to load constants (e.g. the IV) into the advice columns.
+        let constants = meta.fixed_column();
+        meta.enable_constant(constants);
+
         let compression =
             CompressionConfig::configure(meta, lookup_inputs.clone(), message_schedule, extras);
 
+        let select = SelectConfig::configure(meta, message_schedule, extras);
+
+        let message_schedule_column = message_schedule;
         let message_schedule =
             MessageScheduleConfig::configure(meta, lookup_inputs, message_schedule, extras);
 
         Table16Config {
+            message_schedule_column,
+            extras,
             lookup,
             message_schedule,
             compression,
+            var_len: None,
+            xor: None,
+            target: None,
+            select,
+            instance: None,
         }
     }
 
+    /// Configures the chip with an instance column, to which digests can be constrained
+    /// with [`Sha256::constrain_digest_public`](crate::Sha256::constrain_digest_public).
+    /// An instance column can be added to any other configuration of the chip with
+    /// [`Table16Config::with_instance`].
+    pub fn configure_with_instance(
+        meta: &mut ConstraintSystem<F>,
+        instance: Column<Instance>,
+    ) -> <Self as Chip<F>>::Config {
+        Self::configure(meta).with_instance(meta, instance)
+    }
+
     pub fn load(config: Table16Config, layouter: &mut impl Layouter<F>) -> Result<(), Error> {
         SpreadTableChip::load(config.lookup, layouter)
     }
+
+    /// Witnesses a bit with which to select between states or words.
+    pub fn witness_bit(
+        &self,
+        layouter: &mut impl Layouter<F>,
+        value: Option<bool>,
+    ) -> Result<Bit, Error> {
+        self.config().select.witness_bit(layouter, value)
+    }
+
+    /// Returns state `a` if the bit is unset, and state `b` if it is set. The bit is
+    /// boolean-constrained.
+    ///
+    /// The selected state is decomposed into an initialized state, so that it can be
+    /// compressed directly, as well as digested or used as a chaining value.
+    pub fn select_state(
+        &self,
+        layouter: &mut impl Layouter<F>,
+        bit: Bit,
+        a: &State,
+        b: &State,
+    ) -> Result<State, Error> {
+        let config = self.config();
+        let halves =
+            config
+                .select
+                .select_halves(layouter, bit, a.dense_halves(), b.dense_halves())?;
+        config.compression.initialize_with_halves(layouter, halves)
+    }
+
+    /// Returns the words of `a` if the bit is unset, and those of `b` if it is set. The
+    /// bit is boolean-constrained.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `a` and `b` are of different lengths.
+    pub fn select_words(
+        &self,
+        layouter: &mut impl Layouter<F>,
+        bit: Bit,
+        a: &[BlockWord],
+        b: &[BlockWord],
+    ) -> Result<Vec<BlockWord>, Error> {
+        self.config().select.select_words(layouter, bit, a, b)
+    }
+
+    fn var_len(&self) -> Result<&VarLenConfig, Error> {
+        self.config.var_len.as_ref().ok_or(Error::SynthesisError)
+    }
 }
 
 impl<F: FieldExt> Sha256Instructions<F> for Table16Chip<F> {
     type State = State;
     type BlockWord = BlockWord;
+    type Byte = Byte;
+    type DigestWord = DigestWord;
 
     fn initialization_vector(&self, layouter: &mut impl Layouter<F>) -> Result<State, Error> {
         self.config().compression.initialize_with_iv(layouter, IV)
     }
 
+    fn constant_word(value: u32) -> Self::BlockWord {
+        BlockWord::Constant(value)
+    }
+
+    fn constant_byte(value: u8) -> Self::Byte {
+        Byte::Constant(value)
+    }
+
+    fn pack_bytes(
+        &self,
+        layouter: &mut impl Layouter<F>,
+        bytes: [Self::Byte; 4],
+    ) -> Result<Self::BlockWord, Error> {
+        self.config().message_schedule.pack_bytes(layouter, bytes)
+    }
+
     fn initialization(
         &self,
         layouter: &mut impl Layouter<F>,
@@ -230,13 +645,117 @@ impl<F: FieldExt> Sha256Instructions<F> for Table16Chip<F> {
         &self,
         layouter: &mut impl Layouter<F>,
         state: &Self::State,
-    ) -> Result<[Self::BlockWord; super::DIGEST_SIZE], Error> {
+    ) -> Result<[Self::DigestWord; super::DIGEST_SIZE], Error> {
         // Copy the dense forms of the state variable chunks down to this gate.
         // Reconstruct the 32-bit dense words.
         self.config().compression.digest(layouter, state.clone())
     }
 }
 
+impl<F: FieldExt> Sha256MidstateInstructions<F> for Table16Chip<F> {
+    fn initialization_from_words(
+        &self,
+        layouter: &mut impl Layouter<F>,
+        words: [Self::BlockWord; super::DIGEST_SIZE],
+    ) -> Result<Self::State, Error> {
+        self.config()
+            .compression
+            .initialize_with_words(layouter, words)
+    }
+}
+
+impl<F: FieldExt> Sha256PublicInstructions<F> for Table16Chip<F> {
+    fn constrain_digest_public(
+        &self,
+        layouter: &mut impl Layouter<F>,
+        digest: &[Self::DigestWord; super::DIGEST_SIZE],
+        offset: usize,
+    ) -> Result<(), Error> {
+        let instance = self.config().instance.ok_or(Error::SynthesisError)?;
+        for (idx, word) in digest.iter().enumerate() {
+            layouter.constrain_instance(word.cell(), instance, offset + idx)?;
+        }
+        Ok(())
+    }
+}
+
+impl<F: FieldExt> Sha256VarLenInstructions<F> for Table16Chip<F> {
+    type Bit = Bit;
+
+    fn pad_var_len(
+        &self,
+        layouter: &mut impl Layouter<F>,
+        max_blocks: usize,
+        len: Self::BlockWord,
+        data: &[Self::Byte],
+    ) -> Result<(Vec<[Self::BlockWord; super::BLOCK_SIZE]>, Vec<Self::Bit>), Error> {
+        let (bytes, bits) = self.var_len()?.pad(layouter, max_blocks, len, data)?;
+
+        // Pack the padded bytes into words, constraining each byte to 8 bits.
+        let words = bytes
+            .chunks(4)
+            .map(|word| self.pack_bytes(layouter, word.try_into().expect("word.len() == 4")))
+            .collect::<Result<Vec<_>, Error>>()?;
+        let blocks = words
+            .chunks(super::BLOCK_SIZE)
+            .map(|block| block.try_into().expect("block.len() == BLOCK_SIZE"))
+            .collect();
+
+        Ok((blocks, bits))
+    }
+
+    fn select_digest(
+        &self,
+        layouter: &mut impl Layouter<F>,
+        bits: &[Self::Bit],
+        digests: &[[Self::DigestWord; super::DIGEST_SIZE]],
+    ) -> Result<[Self::DigestWord; super::DIGEST_SIZE], Error> {
+        self.var_len()?.select_digest(layouter, bits, digests)
+    }
+}
+
+impl<F: FieldExt> Sha224Instructions<F> for Table16Chip<F> {
+    fn initialization_vector_224(&self, layouter: &mut impl Layouter<F>) -> Result<State, Error> {
+        self.config()
+            .compression
+            .initialize_with_iv(layouter, IV_224)
+    }
+}
+
+impl<F: FieldExt> HmacSha256Instructions<F> for Table16Chip<F> {
+    fn xor_constant(
+        &self,
+        layouter: &mut impl Layouter<F>,
+        words: &[Self::BlockWord],
+        constant: u32,
+    ) -> Result<Vec<Self::BlockWord>, Error> {
+        self.config()
+            .xor
+            .as_ref()
+            .ok_or(Error::SynthesisError)?
+            .xor_constant(layouter, words, constant)
+    }
+
+    fn digest_word_to_block_word(word: Self::DigestWord) -> Self::BlockWord {
+        word.into()
+    }
+}
+
+impl<F: FieldExt> BitcoinHeaderInstructions<F> for Table16Chip<F> {
+    fn check_target(
+        &self,
+        layouter: &mut impl Layouter<F>,
+        hash: &[Self::DigestWord; super::DIGEST_SIZE],
+        bits: [Self::Byte; 4],
+    ) -> Result<(), Error> {
+        self.config()
+            .target
+            .as_ref()
+            .ok_or(Error::SynthesisError)?
+            .check_target(layouter, hash, bits)
+    }
+}
+
 /// Common assignment patterns used by Table16 regions.
 trait Table16Assignment<F: FieldExt> {
     // Assign cells for general spread computation used in sigma, ch, ch_neg, maj gates
@@ -328,10 +847,75 @@ trait Table16Assignment<F: FieldExt> {
         region.constrain_equal(cell, copy.var())?;
         Ok(cell)
     }
+
+    // Assign a block word, fixing it if it is constant and copying it if it is assigned.
+    fn assign_block_word<A, AR>(
+        &self,
+        region: &mut Region<'_, F>,
+        annotation: A,
+        column: Column<Advice>,
+        row: usize,
+        word: BlockWord,
+    ) -> Result<Cell, Error>
+    where
+        A: Fn() -> AR,
+        AR: Into<String>,
+    {
+        match word {
+            BlockWord::Witness(value) => region.assign_advice(annotation, column, row, || {
+                value
+                    .map(|value| F::from_u64(value as u64))
+                    .ok_or(Error::SynthesisError)
+            }),
+            BlockWord::Constant(value) => region.assign_advice_from_constant(
+                annotation,
+                column,
+                row,
+                F::from_u64(value as u64),
+            ),
+            BlockWord::Assigned(word) => {
+                self.assign_and_constrain(region, annotation, column, row, word)
+            }
+        }
+    }
 }
 
 #[cfg(test)]
 mod tests {
+    use super::Table16Chip;
+    use halo2::{pasta::Fp, plonk::ConstraintSystem};
+
+    /// Reads a `usize` field from the `Debug` output of a `ConstraintSystem`,
+    /// which does not expose its column counts.
+    fn debug_count(meta: &ConstraintSystem<Fp>, field: &str) -> usize {
+        let debug = format!("{:?}", meta);
+        let start = debug
+            .find(&format!("{}: ", field))
+            .unwrap_or_else(|| panic!("ConstraintSystem has no {}", field))
+            + field.len()
+            + 2;
+        debug[start..]
+            .split(|c: char| !c.is_ascii_digit())
+            .next()
+            .unwrap()
+            .parse()
+            .unwrap()
+    }
+
+    #[test]
+    fn column_counts() {
+        let mut meta = ConstraintSystem::<Fp>::default();
+        Table16Chip::<Fp>::configure(&mut meta);
+
+        // The constants column and the three spread table columns. The 25
+        // gate selectors were fixed columns of their own before they were
+        // Selectors, which made 29 fixed columns; keygen now combines the
+        // selectors into a few fixed columns.
+        assert_eq!(debug_count(&meta, "num_fixed_columns"), 4);
+        assert_eq!(debug_count(&meta, "num_selectors"), 25);
+        assert_eq!(debug_count(&meta, "num_advice_columns"), 10);
+    }
+
     #[cfg(feature = "dev-graph")]
     #[test]
     fn print_sha256_circuit() {
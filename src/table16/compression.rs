@@ -1,17 +1,18 @@
 use super::{
-    super::DIGEST_SIZE, BlockWord, CellValue16, CellValue32, SpreadInputs, SpreadVar,
+    super::DIGEST_SIZE, BlockWord, CellValue16, CellValue32, DigestWord, SpreadInputs, SpreadVar,
     Table16Assignment, ROUNDS, STATE,
 };
 use halo2::{
     arithmetic::FieldExt,
-    circuit::Layouter,
-    plonk::{Advice, Column, ConstraintSystem, Error, Fixed},
+    circuit::{Layouter, Region},
+    plonk::{Advice, Column, ConstraintSystem, Error, Selector},
     poly::Rotation,
 };
 
 mod compression_gates;
 mod compression_util;
 mod subregion_digest;
+mod subregion_feed_forward;
 mod subregion_initial;
 mod subregion_main;
 
@@ -219,6 +220,22 @@ impl State {
             h: None,
         }
     }
+
+    /// Returns the dense halves of the words `[A, B, C, D, E, F, G, H]` of this state.
+    #[allow(clippy::many_single_char_names)]
+    pub(super) fn dense_halves(&self) -> [(CellValue16, CellValue16); STATE] {
+        let (a, b, c, d, e, f, g, h) = compression_util::match_state(self.clone());
+        [
+            a.dense_halves,
+            b.dense_halves,
+            c.dense_halves,
+            d.dense_halves,
+            e.dense_halves,
+            f.dense_halves,
+            g.dense_halves,
+            h.dense_halves,
+        ]
+    }
 }
 
 #[derive(Clone, Debug)]
@@ -239,22 +256,23 @@ pub(super) struct CompressionConfig {
     message_schedule: Column<Advice>,
     extras: [Column<Advice>; 6],
 
-    s_ch: Column<Fixed>,
-    s_ch_neg: Column<Fixed>,
-    s_maj: Column<Fixed>,
-    s_h_prime: Column<Fixed>,
-    s_a_new: Column<Fixed>,
-    s_e_new: Column<Fixed>,
+    s_ch: Selector,
+    s_ch_neg: Selector,
+    s_maj: Selector,
+    s_h_prime: Selector,
+    s_a_new: Selector,
+    s_e_new: Selector,
 
-    s_upper_sigma_0: Column<Fixed>,
-    s_upper_sigma_1: Column<Fixed>,
+    s_upper_sigma_0: Selector,
+    s_upper_sigma_1: Selector,
 
     // Decomposition gate for AbcdVar
-    s_decompose_abcd: Column<Fixed>,
+    s_decompose_abcd: Selector,
     // Decomposition gate for EfghVar
-    s_decompose_efgh: Column<Fixed>,
+    s_decompose_efgh: Selector,
 
-    s_digest: Column<Fixed>,
+    s_feed_forward: Selector,
+    s_digest: Selector,
 }
 
 impl<F: FieldExt> Table16Assignment<F> for CompressionConfig {}
@@ -266,22 +284,23 @@ impl CompressionConfig {
         message_schedule: Column<Advice>,
         extras: [Column<Advice>; 6],
     ) -> Self {
-        let s_ch = meta.fixed_column();
-        let s_ch_neg = meta.fixed_column();
-        let s_maj = meta.fixed_column();
-        let s_h_prime = meta.fixed_column();
-        let s_a_new = meta.fixed_column();
-        let s_e_new = meta.fixed_column();
+        let s_ch = meta.selector();
+        let s_ch_neg = meta.selector();
+        let s_maj = meta.selector();
+        let s_h_prime = meta.selector();
+        let s_a_new = meta.selector();
+        let s_e_new = meta.selector();
 
-        let s_upper_sigma_0 = meta.fixed_column();
-        let s_upper_sigma_1 = meta.fixed_column();
+        let s_upper_sigma_0 = meta.selector();
+        let s_upper_sigma_1 = meta.selector();
 
         // Decomposition gate for AbcdVar
-        let s_decompose_abcd = meta.fixed_column();
+        let s_decompose_abcd = meta.selector();
         // Decomposition gate for EfghVar
-        let s_decompose_efgh = meta.fixed_column();
+        let s_decompose_efgh = meta.selector();
 
-        let s_digest = meta.fixed_column();
+        let s_feed_forward = meta.selector();
+        let s_digest = meta.selector();
 
         // Rename these here for ease of matching the gates to the specification.
         let a_0 = lookup.tag;
@@ -298,7 +317,7 @@ impl CompressionConfig {
         // Decompose `A,B,C,D` words into (2, 11, 9, 10)-bit chunks.
         // `c` is split into (3, 3, 3)-bit c_lo, c_mid, c_hi.
         meta.create_gate("decompose ABCD", |meta| {
-            let s_decompose_abcd = meta.query_fixed(s_decompose_abcd, Rotation::cur());
+            let s_decompose_abcd = meta.query_selector(s_decompose_abcd);
             let a = meta.query_advice(a_3, Rotation::next()); // 2-bit chunk
             let spread_a = meta.query_advice(a_4, Rotation::next());
             let b = meta.query_advice(a_1, Rotation::cur()); // 11-bit chunk
@@ -345,7 +364,7 @@ impl CompressionConfig {
         // `a` is split into (3, 3)-bit a_lo, a_hi
         // `b` is split into (2, 3)-bit b_lo, b_hi
         meta.create_gate("Decompose EFGH", |meta| {
-            let s_decompose_efgh = meta.query_fixed(s_decompose_efgh, Rotation::cur());
+            let s_decompose_efgh = meta.query_selector(s_decompose_efgh);
             let a_lo = meta.query_advice(a_3, Rotation::next()); // 3-bit chunk
             let spread_a_lo = meta.query_advice(a_4, Rotation::next());
             let a_hi = meta.query_advice(a_5, Rotation::next()); // 3-bit chunk
@@ -391,7 +410,7 @@ impl CompressionConfig {
         // s_upper_sigma_0 on abcd words
         // (2, 11, 9, 10)-bit chunks
         meta.create_gate("s_upper_sigma_0", |meta| {
-            let s_upper_sigma_0 = meta.query_fixed(s_upper_sigma_0, Rotation::cur());
+            let s_upper_sigma_0 = meta.query_selector(s_upper_sigma_0);
             let spread_r0_even = meta.query_advice(a_2, Rotation::prev());
             let spread_r0_odd = meta.query_advice(a_2, Rotation::cur());
             let spread_r1_even = meta.query_advice(a_2, Rotation::next());
@@ -422,7 +441,7 @@ impl CompressionConfig {
         // s_upper_sigma_1 on efgh words
         // (6, 5, 14, 7)-bit chunks
         meta.create_gate("s_upper_sigma_1", |meta| {
-            let s_upper_sigma_1 = meta.query_fixed(s_upper_sigma_1, Rotation::cur());
+            let s_upper_sigma_1 = meta.query_selector(s_upper_sigma_1);
             let spread_r0_even = meta.query_advice(a_2, Rotation::prev());
             let spread_r0_odd = meta.query_advice(a_2, Rotation::cur());
             let spread_r1_even = meta.query_advice(a_2, Rotation::next());
@@ -452,7 +471,7 @@ impl CompressionConfig {
         // s_ch on efgh words
         // First part of choice gate on (E, F, G), E ∧ F
         meta.create_gate("s_ch", |meta| {
-            let s_ch = meta.query_fixed(s_ch, Rotation::cur());
+            let s_ch = meta.query_selector(s_ch);
             let spread_p0_even = meta.query_advice(a_2, Rotation::prev());
             let spread_p0_odd = meta.query_advice(a_2, Rotation::cur());
             let spread_p1_even = meta.query_advice(a_2, Rotation::next());
@@ -478,7 +497,7 @@ impl CompressionConfig {
         // s_ch_neg on efgh words
         // Second part of Choice gate on (E, F, G), ¬E ∧ G
         meta.create_gate("s_ch_neg", |meta| {
-            let s_ch_neg = meta.query_fixed(s_ch_neg, Rotation::cur());
+            let s_ch_neg = meta.query_selector(s_ch_neg);
             let spread_q0_even = meta.query_advice(a_2, Rotation::prev());
             let spread_q0_odd = meta.query_advice(a_2, Rotation::cur());
             let spread_q1_even = meta.query_advice(a_2, Rotation::next());
@@ -507,7 +526,7 @@ impl CompressionConfig {
 
         // s_maj on abcd words
         meta.create_gate("s_maj", |meta| {
-            let s_maj = meta.query_fixed(s_maj, Rotation::cur());
+            let s_maj = meta.query_selector(s_maj);
             let spread_m0_even = meta.query_advice(a_2, Rotation::prev());
             let spread_m0_odd = meta.query_advice(a_2, Rotation::cur());
             let spread_m1_even = meta.query_advice(a_2, Rotation::next());
@@ -536,7 +555,7 @@ impl CompressionConfig {
 
         // s_h_prime to compute H' = H + Ch(E, F, G) + s_upper_sigma_1(E) + K + W
         meta.create_gate("s_h_prime", |meta| {
-            let s_h_prime = meta.query_fixed(s_h_prime, Rotation::cur());
+            let s_h_prime = meta.query_selector(s_h_prime);
             let h_prime_lo = meta.query_advice(a_7, Rotation::next());
             let h_prime_hi = meta.query_advice(a_8, Rotation::next());
             let h_prime_carry = meta.query_advice(a_9, Rotation::next());
@@ -575,7 +594,7 @@ impl CompressionConfig {
 
         // s_a_new
         meta.create_gate("s_a_new", |meta| {
-            let s_a_new = meta.query_fixed(s_a_new, Rotation::cur());
+            let s_a_new = meta.query_selector(s_a_new);
             let a_new_lo = meta.query_advice(a_8, Rotation::cur());
             let a_new_hi = meta.query_advice(a_8, Rotation::next());
             let a_new_carry = meta.query_advice(a_9, Rotation::cur());
@@ -602,7 +621,7 @@ impl CompressionConfig {
 
         // s_e_new
         meta.create_gate("s_e_new", |meta| {
-            let s_e_new = meta.query_fixed(s_e_new, Rotation::cur());
+            let s_e_new = meta.query_selector(s_e_new);
             let e_new_lo = meta.query_advice(a_8, Rotation::cur());
             let e_new_hi = meta.query_advice(a_8, Rotation::next());
             let e_new_carry = meta.query_advice(a_9, Rotation::next());
@@ -623,9 +642,32 @@ impl CompressionConfig {
             )
         });
 
+        // s_feed_forward to add the initial state of a block to its final state
+        meta.create_gate("s_feed_forward", |meta| {
+            let s_feed_forward = meta.query_selector(s_feed_forward);
+            let out_lo = meta.query_advice(a_1, Rotation::cur());
+            let out_hi = meta.query_advice(a_1, Rotation::next());
+            let carry = meta.query_advice(a_5, Rotation::cur());
+            let initial_lo = meta.query_advice(a_3, Rotation::cur());
+            let initial_hi = meta.query_advice(a_3, Rotation::next());
+            let final_lo = meta.query_advice(a_4, Rotation::cur());
+            let final_hi = meta.query_advice(a_4, Rotation::next());
+
+            CompressionGate::s_feed_forward(
+                s_feed_forward,
+                out_lo,
+                out_hi,
+                carry,
+                initial_lo,
+                initial_hi,
+                final_lo,
+                final_hi,
+            )
+        });
+
         // s_digest for final round
         meta.create_gate("s_digest", |meta| {
-            let s_digest = meta.query_fixed(s_digest, Rotation::cur());
+            let s_digest = meta.query_selector(s_digest);
             let lo_0 = meta.query_advice(a_3, Rotation::cur());
             let hi_0 = meta.query_advice(a_4, Rotation::cur());
             let word_0 = meta.query_advice(a_5, Rotation::cur());
@@ -659,6 +701,7 @@ impl CompressionConfig {
             s_upper_sigma_1,
             s_decompose_abcd,
             s_decompose_efgh,
+            s_feed_forward,
             s_digest,
         }
     }
@@ -699,52 +742,130 @@ impl CompressionConfig {
         Ok(new_state)
     }
 
-    /// Given an initialized state and a message schedule, perform 64 compression rounds.
-    pub(super) fn compress<F: FieldExt>(
+    /// Initialize compression with a chaining value given as words. The words are
+    /// decomposed into an initialized state, whose dense halves are recomposed as in the
+    /// digest; constant words are then fixed to, and assigned words copied to, the
+    /// recomposed words.
+    pub(super) fn initialize_with_words<F: FieldExt>(
         &self,
         layouter: &mut impl Layouter<F>,
-        initialized_state: State,
-        w_halves: [(CellValue16, CellValue16); ROUNDS],
+        words: [BlockWord; STATE],
     ) -> Result<State, Error> {
-        let mut state = State::empty_state();
+        let mut values = [None; STATE];
+        for (value, word) in values.iter_mut().zip(words.iter()) {
+            *value = word.value();
+        }
+
+        let state = layouter.assign_region(
+            || "initialize_with_words",
+            |mut region| self.assign_initial_state(&mut region, values),
+        )?;
         layouter.assign_region(
-            || "compress",
+            || "recompose words",
             |mut region| {
-                state = initialized_state.clone();
-                for idx in 0..64 {
-                    state =
-                        self.assign_round(&mut region, idx, state.clone(), w_halves[idx as usize])?;
+                let recomposed = self.assign_digest(&mut region, state.clone())?;
+                for (word, recomposed) in words.iter().zip(recomposed.iter()) {
+                    match word {
+                        BlockWord::Witness(_) => (),
+                        BlockWord::Constant(value) => region
+                            .constrain_constant(recomposed.cell(), F::from_u64(*value as u64))?,
+                        BlockWord::Assigned(word) => {
+                            region.constrain_equal(word.var, recomposed.cell())?
+                        }
+                    }
                 }
                 Ok(())
             },
         )?;
+
         Ok(state)
     }
 
+    /// Initialize compression with the words given by their dense halves, such as those
+    /// selected from two states. The halves are copied into the decomposed words.
+    pub(super) fn initialize_with_halves<F: FieldExt>(
+        &self,
+        layouter: &mut impl Layouter<F>,
+        halves: [(CellValue16, CellValue16); STATE],
+    ) -> Result<State, Error> {
+        layouter.assign_region(
+            || "initialize_with_halves",
+            |mut region| self.initialize_halves(&mut region, halves),
+        )
+    }
+
+    /// Given an initialized state and a message schedule, perform 64 compression rounds
+    /// and add the initialized state to the result. Returns the chaining value.
+    pub(super) fn compress<F: FieldExt>(
+        &self,
+        layouter: &mut impl Layouter<F>,
+        initialized_state: State,
+        w_halves: [(CellValue16, CellValue16); ROUNDS],
+    ) -> Result<State, Error> {
+        layouter.assign_region(
+            || "compress",
+            |mut region| self.assign_compress(&mut region, initialized_state.clone(), w_halves),
+        )
+    }
+
+    /// Assigns the 64 compression rounds and the feed-forward of the initialized state
+    /// within a single region. Returns the chaining value.
+    pub(super) fn assign_compress<F: FieldExt>(
+        &self,
+        region: &mut Region<'_, F>,
+        initialized_state: State,
+        w_halves: [(CellValue16, CellValue16); ROUNDS],
+    ) -> Result<State, Error> {
+        let mut state = initialized_state.clone();
+        for idx in 0..64 {
+            state = self.assign_round(region, idx, state, w_halves[idx as usize])?;
+        }
+        self.assign_feed_forward(region, initialized_state, state)
+    }
+
     /// After the final round, convert the state into the final digest.
     pub(super) fn digest<F: FieldExt>(
         &self,
         layouter: &mut impl Layouter<F>,
         state: State,
-    ) -> Result<[BlockWord; DIGEST_SIZE], Error> {
-        let mut digest = [BlockWord(Some(0)); DIGEST_SIZE];
+    ) -> Result<[DigestWord; DIGEST_SIZE], Error> {
         layouter.assign_region(
             || "digest",
-            |mut region| {
-                digest = self.assign_digest(&mut region, state.clone())?;
-
-                Ok(())
-            },
-        )?;
-        Ok(digest)
+            |mut region| self.assign_digest(&mut region, state.clone()),
+        )
     }
 }
 
+// The rows taken by the regions of the compression, for sizing circuits in tests
+
+#[cfg(test)]
+pub(super) fn initialize_with_iv_rows() -> usize {
+    compression_util::get_d_row(-1) + compression_util::DECOMPOSE_ABCD
+}
+
+#[cfg(test)]
+pub(super) fn initialize_with_state_rows() -> usize {
+    compression_util::DECOMPOSE_EFGH + compression_util::DECOMPOSE_ABCD
+}
+
+#[cfg(test)]
+pub(super) fn compress_rows() -> usize {
+    compression_util::get_feed_forward_row(STATE - 1) + compression_util::FEED_FORWARD_ROWS
+}
+
+// A, B, C, D and E, F, G, H are recomposed on two rows each.
+#[cfg(test)]
+pub(super) const DIGEST_ROWS: usize = 4;
+
 #[cfg(test)]
 mod tests {
     use super::super::{
-        super::BLOCK_SIZE, msg_schedule_test_input, BlockWord, Table16Chip, Table16Config, IV,
+        super::BLOCK_SIZE,
+        msg_schedule_test_input, native,
+        tamper::{advice_columns, assert_copy_fails, assert_gate_fails, tamper},
+        BlockWord, Table16Chip, Table16Config, IV, STATE,
     };
+    use super::compression_util::*;
     use halo2::{
         arithmetic::FieldExt,
         circuit::{Layouter, SimpleFloorPlanner},
@@ -789,11 +910,14 @@ mod tests {
                     .compress(&mut layouter, initial_state, w_halves)?;
 
                 let digest = config.compression.digest(&mut layouter, state)?;
+                let mut block = [0; BLOCK_SIZE];
+                for (word, input) in block.iter_mut().zip(input.iter()) {
+                    *word = input.value().unwrap();
+                }
+                let native_digest = native::compress(IV, block);
                 for (idx, digest_word) in digest.iter().enumerate() {
-                    assert_eq!(
-                        (digest_word.0.unwrap() as u64 + IV[idx] as u64) as u32,
-                        super::compression_util::COMPRESSION_OUTPUT[idx]
-                    );
+                    assert_eq!(digest_word.value().unwrap(), COMPRESSION_OUTPUT[idx]);
+                    assert_eq!(digest_word.value().unwrap(), native_digest[idx]);
                 }
 
                 Ok(())
@@ -808,4 +932,349 @@ mod tests {
         };
         assert_eq!(prover.verify(), Ok(()));
     }
+
+    #[test]
+    fn wrong_iv() {
+        struct MyCircuit {
+            iv: [Option<u32>; STATE],
+        }
+
+        impl<F: FieldExt> Circuit<F> for MyCircuit {
+            type Config = Table16Config;
+            type FloorPlanner = SimpleFloorPlanner;
+
+            fn without_witnesses(&self) -> Self {
+                MyCircuit { iv: [None; STATE] }
+            }
+
+            fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+                Table16Chip::configure(meta)
+            }
+
+            fn synthesize(
+                &self,
+                config: Self::Config,
+                mut layouter: impl Layouter<F>,
+            ) -> Result<(), Error> {
+                Table16Chip::<F>::load(config.clone(), &mut layouter)?;
+
+                // Witness `self.iv`, but constrain it to the real IV as a prover would have
+                // to with the keys generated for the honest circuit.
+                layouter.assign_region(
+                    || "initialize_with_iv",
+                    |mut region| {
+                        let state = config
+                            .compression
+                            .assign_initial_state(&mut region, self.iv)?;
+                        config.compression.constrain_iv(&mut region, &state, IV)
+                    },
+                )
+            }
+        }
+
+        let mut iv = [None; STATE];
+        for (word, iv_word) in iv.iter_mut().zip(IV.iter()) {
+            *word = Some(*iv_word);
+        }
+
+        let circuit = MyCircuit { iv };
+        let prover = match MockProver::<Fp>::run(17, &circuit, vec![]) {
+            Ok(prover) => prover,
+            Err(e) => panic!("{:?}", e),
+        };
+        assert_eq!(prover.verify(), Ok(()));
+
+        // Start from a state that differs from the IV in a single bit of H.
+        iv[7] = iv[7].map(|h| h ^ 1);
+
+        let circuit = MyCircuit { iv };
+        let prover = match MockProver::<Fp>::run(17, &circuit, vec![]) {
+            Ok(prover) => prover,
+            Err(e) => panic!("{:?}", e),
+        };
+        assert!(prover.verify().is_err());
+    }
+
+    #[test]
+    fn tampered_chaining_value() {
+        struct MyCircuit {
+            // The region, column index and row of the cell to tamper with
+            tamper: Option<(&'static str, usize, usize)>,
+        }
+
+        impl<F: FieldExt> Circuit<F> for MyCircuit {
+            type Config = Table16Config;
+            type FloorPlanner = SimpleFloorPlanner;
+
+            fn without_witnesses(&self) -> Self {
+                MyCircuit {
+                    tamper: self.tamper,
+                }
+            }
+
+            fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+                Table16Chip::configure_with_byte_table(meta)
+            }
+
+            fn synthesize(
+                &self,
+                config: Self::Config,
+                mut layouter: impl Layouter<F>,
+            ) -> Result<(), Error> {
+                Table16Chip::<F>::load(config.clone(), &mut layouter)?;
+
+                let compression = &config.compression;
+                let columns = advice_columns(
+                    &compression.lookup,
+                    compression.message_schedule,
+                    compression.extras,
+                );
+
+                // Chain from the IV state, as from the output of a previous compression.
+                let state = compression.initialize_with_iv(&mut layouter, IV)?;
+                let state = layouter.assign_region(
+                    || "initialize_with_state",
+                    |mut region| {
+                        let state = compression.initialize_state(&mut region, state.clone())?;
+                        if let Some(("initialize_with_state", column, row)) = self.tamper {
+                            tamper(&mut region, columns[column], row)?;
+                        }
+                        Ok(state)
+                    },
+                )?;
+
+                let input: [BlockWord; BLOCK_SIZE] = msg_schedule_test_input();
+                let (_, w_halves) = config.message_schedule.process(&mut layouter, input)?;
+                layouter.assign_region(
+                    || "compress",
+                    |mut region| {
+                        compression.assign_compress(&mut region, state.clone(), w_halves)?;
+                        if let Some(("compress", column, row)) = self.tamper {
+                            tamper(&mut region, columns[column], row)?;
+                        }
+                        Ok(())
+                    },
+                )
+            }
+        }
+
+        let circuit = MyCircuit { tamper: None };
+        let prover = match MockProver::<Fp>::run(12, &circuit, vec![]) {
+            Ok(prover) => prover,
+            Err(e) => panic!("{:?}", e),
+        };
+        assert_eq!(prover.verify(), Ok(()));
+
+        // A and E are decomposed again from the chaining value, and their dense halves
+        // copied from it. The other words are copied from the chaining value into the
+        // first round. For each word, a cell holding it, given as (region, column, row)
+        let cells = [
+            // a_lo
+            ("initialize_with_state", 7, DECOMPOSE_EFGH),
+            // spread_b_lo
+            ("compress", 4, get_maj_row(0)),
+            // spread_c_lo
+            ("compress", 4, get_maj_row(0) + 1),
+            // d_lo
+            ("compress", 7, get_e_new_row(0)),
+            // e_hi
+            ("initialize_with_state", 7, 1),
+            // spread_f_lo
+            ("compress", 3, get_ch_row(0) + 1),
+            // spread_g_lo
+            ("compress", 3, get_ch_neg_row(0) + 1),
+            // h_lo
+            ("compress", 7, get_h_prime_row(0) - 1),
+        ];
+
+        for (region, column, row) in cells.iter() {
+            let circuit = MyCircuit {
+                tamper: Some((region, *column, *row)),
+            };
+            let prover = match MockProver::<Fp>::run(12, &circuit, vec![]) {
+                Ok(prover) => prover,
+                Err(e) => panic!("{:?}", e),
+            };
+            assert_copy_fails(&prover);
+        }
+    }
+
+    #[test]
+    fn chain_rows() {
+        // Initializations from a chaining value, which take 4 rows each
+        const CHAINS: usize = 450;
+
+        struct MyCircuit {}
+
+        impl<F: FieldExt> Circuit<F> for MyCircuit {
+            type Config = Table16Config;
+            type FloorPlanner = SimpleFloorPlanner;
+
+            fn without_witnesses(&self) -> Self {
+                MyCircuit {}
+            }
+
+            fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+                Table16Chip::configure_with_byte_table(meta)
+            }
+
+            fn synthesize(
+                &self,
+                config: Self::Config,
+                mut layouter: impl Layouter<F>,
+            ) -> Result<(), Error> {
+                Table16Chip::<F>::load(config.clone(), &mut layouter)?;
+
+                let mut state = config.compression.initialize_with_iv(&mut layouter, IV)?;
+                for _ in 0..CHAINS {
+                    state = config
+                        .compression
+                        .initialize_with_state(&mut layouter, state)?;
+                }
+
+                for ((lo, hi), iv_word) in state.dense_halves().iter().zip(IV.iter()) {
+                    let word = lo.value.unwrap() as u32 + ((hi.value.unwrap() as u32) << 16);
+                    assert_eq!(word, *iv_word);
+                }
+
+                Ok(())
+            }
+        }
+
+        // The chained initializations fit in 2^11 rows along with the initialization from
+        // the IV, but would not with a single extra row each.
+        let circuit: MyCircuit = MyCircuit {};
+        let prover = match MockProver::<Fp>::run(11, &circuit, vec![]) {
+            Ok(prover) => prover,
+            Err(e) => panic!("{:?}", e),
+        };
+        assert_eq!(prover.verify(), Ok(()));
+    }
+
+    #[test]
+    fn tampered_cells() {
+        // The round whose cells are tampered with
+        const ROUND: i32 = 1;
+
+        struct MyCircuit {
+            // The region, column index and row of the cell to tamper with
+            tamper: Option<(&'static str, usize, usize)>,
+        }
+
+        impl<F: FieldExt> Circuit<F> for MyCircuit {
+            type Config = Table16Config;
+            type FloorPlanner = SimpleFloorPlanner;
+
+            fn without_witnesses(&self) -> Self {
+                MyCircuit {
+                    tamper: self.tamper,
+                }
+            }
+
+            fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+                Table16Chip::configure_with_byte_table(meta)
+            }
+
+            fn synthesize(
+                &self,
+                config: Self::Config,
+                mut layouter: impl Layouter<F>,
+            ) -> Result<(), Error> {
+                Table16Chip::<F>::load(config.clone(), &mut layouter)?;
+
+                let compression = &config.compression;
+                let columns = advice_columns(
+                    &compression.lookup,
+                    compression.message_schedule,
+                    compression.extras,
+                );
+
+                let input: [BlockWord; BLOCK_SIZE] = msg_schedule_test_input();
+                let (_, w_halves) = config.message_schedule.process(&mut layouter, input)?;
+                let initial_state = compression.initialize_with_iv(&mut layouter, IV)?;
+
+                let state = layouter.assign_region(
+                    || "compress",
+                    |mut region| {
+                        let state = compression.assign_compress(
+                            &mut region,
+                            initial_state.clone(),
+                            w_halves,
+                        )?;
+                        if let Some(("compress", column, row)) = self.tamper {
+                            tamper(&mut region, columns[column], row)?;
+                        }
+                        Ok(state)
+                    },
+                )?;
+
+                layouter.assign_region(
+                    || "digest",
+                    |mut region| {
+                        compression.assign_digest(&mut region, state.clone())?;
+                        if let Some(("digest", column, row)) = self.tamper {
+                            tamper(&mut region, columns[column], row)?;
+                        }
+                        Ok(())
+                    },
+                )
+            }
+        }
+
+        let circuit = MyCircuit { tamper: None };
+        let prover = match MockProver::<Fp>::run(12, &circuit, vec![]) {
+            Ok(prover) => prover,
+            Err(e) => panic!("{:?}", e),
+        };
+        assert_eq!(prover.verify(), Ok(()));
+
+        // For each gate, a cell it queries, given as (gate, region, column, row)
+        let cells = [
+            // tag_b
+            ("decompose ABCD", "compress", 0, get_decompose_a_row(ROUND)),
+            // tag_d
+            ("Decompose EFGH", "compress", 0, get_decompose_e_row(ROUND)),
+            // spread_r0_even
+            (
+                "s_upper_sigma_0",
+                "compress",
+                2,
+                get_upper_sigma_0_row(ROUND) - 1,
+            ),
+            // spread_r1_odd
+            (
+                "s_upper_sigma_1",
+                "compress",
+                3,
+                get_upper_sigma_1_row(ROUND),
+            ),
+            // spread_p1_odd
+            ("s_ch", "compress", 3, get_ch_row(ROUND)),
+            // spread_q0_odd
+            ("s_ch_neg", "compress", 2, get_ch_neg_row(ROUND)),
+            // spread_m0_odd
+            ("s_maj", "compress", 2, get_maj_row(ROUND)),
+            // h_prime_carry
+            ("s_h_prime", "compress", 9, get_h_prime_row(ROUND) + 1),
+            // a_new_carry
+            ("s_a_new", "compress", 9, get_a_new_row(ROUND)),
+            // e_new_carry
+            ("s_e_new", "compress", 9, get_e_new_row(ROUND) + 1),
+            // carry of A
+            ("s_feed_forward", "compress", 5, get_feed_forward_row(0)),
+            // word_0, in the first row of the digest region
+            ("s_digest", "digest", 5, 0),
+        ];
+
+        for (gate, region, column, row) in cells.iter() {
+            let circuit = MyCircuit {
+                tamper: Some((region, *column, *row)),
+            };
+            let prover = match MockProver::<Fp>::run(12, &circuit, vec![]) {
+                Ok(prover) => prover,
+                Err(e) => panic!("{:?}", e),
+            };
+            assert_gate_fails(&prover, gate);
+        }
+    }
 }
@@ -393,6 +393,30 @@ impl<F: FieldExt> CompressionGate<F> {
         std::iter::empty().chain(Some(("s_e_new", s_e_new * check)))
     }
 
+    // s_feed_forward to get H_i = H_{i-1} + (final state of the block)
+    #[allow(clippy::too_many_arguments)]
+    pub fn s_feed_forward(
+        s_feed_forward: Expression<F>,
+        out_lo: Expression<F>,
+        out_hi: Expression<F>,
+        carry: Expression<F>,
+        initial_lo: Expression<F>,
+        initial_hi: Expression<F>,
+        final_lo: Expression<F>,
+        final_hi: Expression<F>,
+    ) -> impl Iterator<Item = (&'static str, Expression<F>)> {
+        let lo = initial_lo + final_lo;
+        let hi = initial_hi + final_hi;
+        let sum = lo + hi * F::from_u64(1 << 16);
+        let out = out_lo + out_hi * F::from_u64(1 << 16);
+
+        let check = sum - (carry.clone() * F::from_u64(1 << 32)) - out;
+        let carry_check = Gate::range_check(carry, 0, 1);
+
+        array::IntoIter::new([("s_feed_forward", check), ("carry_check", carry_check)])
+            .map(move |(name, poly)| (name, s_feed_forward.clone() * poly))
+    }
+
     // s_digest on final round
     #[allow(clippy::too_many_arguments)]
     pub fn s_digest(
@@ -3,7 +3,7 @@ use super::{
     State,
 };
 use crate::table16::{
-    util::*, CellValue16, CellValue32, SpreadVar, SpreadWord, StateWord, Table16Assignment,
+    util::*, CellValue16, CellValue32, SpreadVar, SpreadWord, StateWord, Table16Assignment, STATE,
 };
 use halo2::{
     arithmetic::FieldExt,
@@ -31,6 +31,7 @@ pub const CH_ROWS: usize = 8;
 pub const MAJ_ROWS: usize = 4;
 pub const DECOMPOSE_ABCD: usize = 2;
 pub const DECOMPOSE_EFGH: usize = 2;
+pub const FEED_FORWARD_ROWS: usize = 2;
 
 // Rows needed for main subregion
 pub const SUBREGION_MAIN_LEN: usize = 64;
@@ -136,12 +137,11 @@ pub fn get_a_new_row(round_idx: i32) -> usize {
     get_maj_row(round_idx)
 }
 
-pub fn get_digest_abcd_row() -> usize {
-    SUBREGION_MAIN_ROWS
-}
-
-pub fn get_digest_efgh_row() -> usize {
-    get_digest_abcd_row() + 2
+/// Returns starting row number of the feed-forward addition for the word at `word_idx`
+/// of the state, counting from A. The feed-forward follows the rows of the last round.
+pub fn get_feed_forward_row(word_idx: usize) -> usize {
+    assert!(word_idx < STATE);
+    SUBREGION_MAIN_ROWS + FEED_FORWARD_ROWS * word_idx
 }
 
 impl CompressionConfig {
@@ -161,12 +161,7 @@ impl CompressionConfig {
         ),
         Error,
     > {
-        region.assign_fixed(
-            || "s_decompose_abcd",
-            self.s_decompose_abcd,
-            row,
-            || Ok(F::one()),
-        )?;
+        self.s_decompose_abcd.enable(region, row)?;
 
         let a_3 = self.extras[0];
         let a_4 = self.extras[1];
@@ -208,12 +203,7 @@ impl CompressionConfig {
         ),
         Error,
     > {
-        region.assign_fixed(
-            || "s_decompose_efgh",
-            self.s_decompose_efgh,
-            row,
-            || Ok(F::one()),
-        )?;
+        self.s_decompose_efgh.enable(region, row)?;
 
         let a_3 = self.extras[0];
         let a_4 = self.extras[1];
@@ -244,8 +234,17 @@ impl CompressionConfig {
         idx: i32,
         a_val: Option<u32>,
     ) -> Result<RoundWordA, Error> {
-        let row = get_decompose_a_row(idx);
+        self.decompose_a_at(region, idx, get_decompose_a_row(idx), a_val)
+    }
 
+    /// Decomposes A for round `idx` at the given row.
+    pub(super) fn decompose_a_at<F: FieldExt>(
+        &self,
+        region: &mut Region<'_, F>,
+        idx: i32,
+        row: usize,
+        a_val: Option<u32>,
+    ) -> Result<RoundWordA, Error> {
         let (dense_halves, spread_halves) = self.assign_word_halves(region, row, a_val)?;
         let (a, b, c_lo, c_mid, c_hi, d) = self.decompose_abcd(region, row, a_val)?;
         let a_pieces = AbcdVar {
@@ -267,8 +266,17 @@ impl CompressionConfig {
         idx: i32,
         e_val: Option<u32>,
     ) -> Result<RoundWordE, Error> {
-        let row = get_decompose_e_row(idx);
+        self.decompose_e_at(region, idx, get_decompose_e_row(idx), e_val)
+    }
 
+    /// Decomposes E for round `idx` at the given row.
+    pub(super) fn decompose_e_at<F: FieldExt>(
+        &self,
+        region: &mut Region<'_, F>,
+        idx: i32,
+        row: usize,
+        e_val: Option<u32>,
+    ) -> Result<RoundWordE, Error> {
         let (dense_halves, spread_halves) = self.assign_word_halves(region, row, e_val)?;
         let (a_lo, a_hi, b_lo, b_hi, c, d) = self.decompose_efgh(region, row, e_val)?;
         let e_pieces = EfghVar {
@@ -297,12 +305,7 @@ impl CompressionConfig {
 
         let row = get_upper_sigma_0_row(idx);
 
-        region.assign_fixed(
-            || "s_upper_sigma_0",
-            self.s_upper_sigma_0,
-            row,
-            || Ok(F::one()),
-        )?;
+        self.s_upper_sigma_0.enable(region, row)?;
 
         // Assign `spread_a` and copy constraint
         self.assign_and_constrain(region, || "spread_a", a_3, row + 1, word.a.spread)?;
@@ -379,12 +382,7 @@ impl CompressionConfig {
 
         let row = get_upper_sigma_1_row(idx);
 
-        region.assign_fixed(
-            || "s_upper_sigma_1",
-            self.s_upper_sigma_1,
-            row,
-            || Ok(F::one()),
-        )?;
+        self.s_upper_sigma_1.enable(region, row)?;
 
         // Assign `spread_a_lo` and copy constraint
         self.assign_and_constrain(region, || "spread_a_lo", a_3, row + 1, word.a_lo.spread)?;
@@ -485,7 +483,7 @@ impl CompressionConfig {
 
         let row = get_ch_row(idx);
 
-        region.assign_fixed(|| "s_ch", self.s_ch, row, || Ok(F::one()))?;
+        self.s_ch.enable(region, row)?;
 
         // Assign and copy spread_e_lo, spread_e_hi
         self.assign_and_constrain(region, || "spread_e_lo", a_3, row - 1, spread_halves_e.0)?;
@@ -522,7 +520,7 @@ impl CompressionConfig {
     ) -> Result<(CellValue16, CellValue16), Error> {
         let row = get_ch_neg_row(idx);
 
-        region.assign_fixed(|| "s_ch_neg", self.s_ch_neg, row, || Ok(F::one()))?;
+        self.s_ch_neg.enable(region, row)?;
 
         let a_3 = self.extras[0];
         let a_4 = self.extras[1];
@@ -623,7 +621,7 @@ impl CompressionConfig {
 
         let row = get_maj_row(idx);
 
-        region.assign_fixed(|| "s_maj", self.s_maj, row, || Ok(F::one()))?;
+        self.s_maj.enable(region, row)?;
 
         // Assign and copy spread_a_lo, spread_a_hi
         self.assign_and_constrain(region, || "spread_a_lo", a_4, row - 1, spread_halves_a.0)?;
@@ -671,7 +669,7 @@ impl CompressionConfig {
         w: (CellValue16, CellValue16),
     ) -> Result<(CellValue16, CellValue16), Error> {
         let row = get_h_prime_row(idx);
-        region.assign_fixed(|| "s_h_prime", self.s_h_prime, row, || Ok(F::one()))?;
+        self.s_h_prime.enable(region, row)?;
 
         let a_4 = self.extras[1];
         let a_5 = self.message_schedule;
@@ -775,7 +773,7 @@ impl CompressionConfig {
     ) -> Result<(CellValue16, CellValue16), Error> {
         let row = get_e_new_row(idx);
 
-        region.assign_fixed(|| "s_e_new", self.s_e_new, row, || Ok(F::one()))?;
+        self.s_e_new.enable(region, row)?;
 
         let a_7 = self.extras[3];
         let a_8 = self.extras[4];
@@ -813,7 +811,7 @@ impl CompressionConfig {
     ) -> Result<(CellValue16, CellValue16), Error> {
         let row = get_a_new_row(idx);
 
-        region.assign_fixed(|| "s_a_new", self.s_a_new, row, || Ok(F::one()))?;
+        self.s_a_new.enable(region, row)?;
 
         let a_3 = self.extras[0];
         let a_6 = self.extras[2];
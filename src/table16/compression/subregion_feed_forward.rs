@@ -0,0 +1,91 @@
+use super::super::{
+    util::*, CellValue16, RoundWordA, RoundWordE, RoundWordSpread, SpreadVar, SpreadWord,
+    StateWord, Table16Assignment,
+};
+use super::{compression_util::*, CompressionConfig, State};
+use halo2::{arithmetic::FieldExt, circuit::Region, plonk::Error};
+
+impl CompressionConfig {
+    /// Adds the state the block was initialized with to the state after the final round,
+    /// returning the chaining value `H_i = H_{i-1} + compress(H_{i-1}, M_i)`.
+    #[allow(clippy::many_single_char_names)]
+    pub fn assign_feed_forward<F: FieldExt>(
+        &self,
+        region: &mut Region<'_, F>,
+        initial_state: State,
+        final_state: State,
+    ) -> Result<State, Error> {
+        let (a_i, b_i, c_i, d_i, e_i, f_i, g_i, h_i) = match_state(initial_state);
+        let (a_f, b_f, c_f, d_f, e_f, f_f, g_f, h_f) = match_state(final_state);
+
+        let a = self.assign_feed_forward_word(region, 0, a_i.dense_halves, a_f.dense_halves)?;
+        let b = self.assign_feed_forward_word(region, 1, b_i.dense_halves, b_f.dense_halves)?;
+        let c = self.assign_feed_forward_word(region, 2, c_i.dense_halves, c_f.dense_halves)?;
+        let d = self.assign_feed_forward_word(region, 3, d_i.dense_halves, d_f.dense_halves)?;
+        let e = self.assign_feed_forward_word(region, 4, e_i.dense_halves, e_f.dense_halves)?;
+        let f = self.assign_feed_forward_word(region, 5, f_i.dense_halves, f_f.dense_halves)?;
+        let g = self.assign_feed_forward_word(region, 6, g_i.dense_halves, g_f.dense_halves)?;
+        let h = self.assign_feed_forward_word(region, 7, h_i.dense_halves, h_f.dense_halves)?;
+
+        Ok(State::new(
+            StateWord::A(RoundWordA::new_dense(a.dense_halves)),
+            StateWord::B(b),
+            StateWord::C(c),
+            StateWord::D(d.into()),
+            StateWord::E(RoundWordE::new_dense(e.dense_halves)),
+            StateWord::F(f),
+            StateWord::G(g),
+            StateWord::H(h.into()),
+        ))
+    }
+
+    // Assign out = initial + final (mod 2^32). The output halves are looked up in the
+    // spread table, which range-checks them to 16 bits.
+    fn assign_feed_forward_word<F: FieldExt>(
+        &self,
+        region: &mut Region<'_, F>,
+        word_idx: usize,
+        initial: (CellValue16, CellValue16),
+        fin: (CellValue16, CellValue16),
+    ) -> Result<RoundWordSpread, Error> {
+        let a_3 = self.extras[0];
+        let a_4 = self.extras[1];
+        let a_5 = self.message_schedule;
+
+        let row = get_feed_forward_row(word_idx);
+
+        self.s_feed_forward.enable(region, row)?;
+
+        // Assign and copy the initial word
+        self.assign_and_constrain(region, || "initial_lo", a_3, row, initial.0)?;
+        self.assign_and_constrain(region, || "initial_hi", a_3, row + 1, initial.1)?;
+
+        // Assign and copy the final word
+        self.assign_and_constrain(region, || "final_lo", a_4, row, fin.0)?;
+        self.assign_and_constrain(region, || "final_hi", a_4, row + 1, fin.1)?;
+
+        // Assign out_lo, out_hi, carry
+        let (out, carry) = sum_with_carry(vec![
+            (initial.0.value, initial.1.value),
+            (fin.0.value, fin.1.value),
+        ]);
+        let halves = transpose_option_vec(out.map(|out| chop_u32(out, &[16, 16])), 2);
+        let out_lo = SpreadWord::opt_new(halves[0].map(|value| value as u16));
+        let out_hi = SpreadWord::opt_new(halves[1].map(|value| value as u16));
+
+        let out_lo = SpreadVar::with_lookup(region, &self.lookup, row, out_lo)?;
+        let out_hi = SpreadVar::with_lookup(region, &self.lookup, row + 1, out_hi)?;
+
+        region.assign_advice(
+            || "carry",
+            a_5,
+            row,
+            || carry.map(F::from_u64).ok_or(Error::SynthesisError),
+        )?;
+
+        Ok(RoundWordSpread::new(
+            (out_lo.dense, out_hi.dense),
+            (out_lo.spread, out_hi.spread),
+        ))
+    }
+}
@@ -1,97 +1,130 @@
-use super::super::{RoundWordDense, RoundWordSpread, StateWord, STATE};
+use super::super::{util::*, CellValue16, RoundWordDense, RoundWordSpread, StateWord, STATE};
 use super::{compression_util::*, CompressionConfig, State};
 use halo2::{arithmetic::FieldExt, circuit::Region, plonk::Error};
 
 impl CompressionConfig {
-    #[allow(clippy::many_single_char_names)]
+    /// Assigns the initial state from the IV. The dense halves of each word are fixed to
+    /// the IV as circuit constants, so the prover cannot start from any other state.
     pub fn initialize_iv<F: FieldExt>(
         &self,
         region: &mut Region<'_, F>,
         iv: [u32; STATE],
     ) -> Result<State, Error> {
-        let a_7 = self.extras[3];
-
-        let idx = -1;
-
-        // Decompose E into (6, 5, 14, 7)-bit chunks
-        let e = self.decompose_e(region, idx, Some(iv[4]))?;
+        let mut values = [None; STATE];
+        for (value, iv_word) in values.iter_mut().zip(iv.iter()) {
+            *value = Some(*iv_word);
+        }
 
-        // Decompose F, G
-        let f = self.decompose_f(region, idx, Some(iv[5]))?;
-        let g = self.decompose_g(region, idx, Some(iv[6]))?;
-
-        // Assign H
-        let h_row = get_h_row(idx);
-        let h_dense =
-            self.assign_word_halves_dense(region, h_row, a_7, h_row + 1, a_7, Some(iv[7]))?;
-        let h = RoundWordDense::new(h_dense);
+        let state = self.assign_initial_state(region, values)?;
+        self.constrain_iv(region, &state, iv)?;
 
-        // Decompose A into (2, 11, 9, 10)-bit chunks
-        let a = self.decompose_a(region, idx, Some(iv[0]))?;
+        Ok(state)
+    }
 
-        // Decompose B, C
-        let b = self.decompose_b(region, idx, Some(iv[1]))?;
-        let c = self.decompose_c(region, idx, Some(iv[2]))?;
+    /// Initializes the state from the chaining value output by a previous compression.
+    /// B, C, D, F, G and H are only used through their dense and spread halves, which
+    /// the feed-forward has already assigned and looked up, so they are reused as they
+    /// are. A and E are decomposed again into the pieces used by the upper sigma gates,
+    /// in the rows below, and their dense halves are copied from the chaining value.
+    ///
+    /// | Row | Word |
+    /// |-----|------|
+    /// | 0   | E    |
+    /// | 2   | A    |
+    #[allow(clippy::many_single_char_names)]
+    pub fn initialize_state<F: FieldExt>(
+        &self,
+        region: &mut Region<'_, F>,
+        state: State,
+    ) -> Result<State, Error> {
+        let (a, b, c, d, e, f, g, h) = match_state(state);
 
-        // Assign D
-        let d_row = get_d_row(idx);
-        let d_dense =
-            self.assign_word_halves_dense(region, d_row, a_7, d_row + 1, a_7, Some(iv[3]))?;
-        let d = RoundWordDense::new(d_dense);
+        let e_new = self.decompose_e_at(region, -1, 0, val_from_dense_halves(e.dense_halves))?;
+        let a_new = self.decompose_a_at(
+            region,
+            -1,
+            DECOMPOSE_EFGH,
+            val_from_dense_halves(a.dense_halves),
+        )?;
+
+        for (halves, new_halves) in [
+            (a.dense_halves, a_new.dense_halves),
+            (e.dense_halves, e_new.dense_halves),
+        ]
+        .iter()
+        {
+            region.constrain_equal(halves.0.var, new_halves.0.var)?;
+            region.constrain_equal(halves.1.var, new_halves.1.var)?;
+        }
 
         Ok(State::new(
-            StateWord::A(a),
+            StateWord::A(a_new),
             StateWord::B(b),
             StateWord::C(c),
             StateWord::D(d),
-            StateWord::E(e),
+            StateWord::E(e_new),
             StateWord::F(f),
             StateWord::G(g),
             StateWord::H(h),
         ))
     }
 
+    /// Decomposes the words given by their dense halves into an initialized state, and
+    /// constrains the dense halves of the decomposed words to equal the given cells.
+    pub fn initialize_halves<F: FieldExt>(
+        &self,
+        region: &mut Region<'_, F>,
+        halves: [(CellValue16, CellValue16); STATE],
+    ) -> Result<State, Error> {
+        let mut values = [None; STATE];
+        for (value, halves) in values.iter_mut().zip(halves.iter()) {
+            *value = val_from_dense_halves(*halves);
+        }
+
+        let state = self.assign_initial_state(region, values)?;
+        for ((lo, hi), (new_lo, new_hi)) in halves.iter().zip(state.dense_halves().iter()) {
+            region.constrain_equal(lo.var, new_lo.var)?;
+            region.constrain_equal(hi.var, new_hi.var)?;
+        }
+
+        Ok(state)
+    }
+
+    /// Decomposes the words `[A, B, C, D, E, F, G, H]` into an initialized state.
     #[allow(clippy::many_single_char_names)]
-    pub fn initialize_state<F: FieldExt>(
+    pub fn assign_initial_state<F: FieldExt>(
         &self,
         region: &mut Region<'_, F>,
-        state: State,
+        values: [Option<u32>; STATE],
     ) -> Result<State, Error> {
         let a_7 = self.extras[3];
-        let (a, b, c, d, e, f, g, h) = match_state(state);
 
         let idx = -1;
 
         // Decompose E into (6, 5, 14, 7)-bit chunks
-        let e = val_from_dense_halves(e.dense_halves);
-        let e = self.decompose_e(region, idx, e)?;
+        let e = self.decompose_e(region, idx, values[4])?;
 
         // Decompose F, G
-        let f = val_from_dense_halves(f.dense_halves);
-        let f = self.decompose_f(region, idx, f)?;
-        let g = val_from_dense_halves(g.dense_halves);
-        let g = self.decompose_g(region, idx, g)?;
+        let f = self.decompose_f(region, idx, values[5])?;
+        let g = self.decompose_g(region, idx, values[6])?;
 
         // Assign H
-        let h = val_from_dense_halves(h.dense_halves);
         let h_row = get_h_row(idx);
-        let h_dense = self.assign_word_halves_dense(region, h_row, a_7, h_row + 1, a_7, h)?;
+        let h_dense =
+            self.assign_word_halves_dense(region, h_row, a_7, h_row + 1, a_7, values[7])?;
         let h = RoundWordDense::new(h_dense);
 
         // Decompose A into (2, 11, 9, 10)-bit chunks
-        let a = val_from_dense_halves(a.dense_halves);
-        let a = self.decompose_a(region, idx, a)?;
+        let a = self.decompose_a(region, idx, values[0])?;
 
         // Decompose B, C
-        let b = val_from_dense_halves(b.dense_halves);
-        let b = self.decompose_b(region, idx, b)?;
-        let c = val_from_dense_halves(c.dense_halves);
-        let c = self.decompose_c(region, idx, c)?;
+        let b = self.decompose_b(region, idx, values[1])?;
+        let c = self.decompose_c(region, idx, values[2])?;
 
         // Assign D
-        let d = val_from_dense_halves(d.dense_halves);
         let d_row = get_d_row(idx);
-        let d_dense = self.assign_word_halves_dense(region, d_row, a_7, d_row + 1, a_7, d)?;
+        let d_dense =
+            self.assign_word_halves_dense(region, d_row, a_7, d_row + 1, a_7, values[3])?;
         let d = RoundWordDense::new(d_dense);
 
         Ok(State::new(
@@ -106,6 +139,35 @@ impl CompressionConfig {
         ))
     }
 
+    /// Constrains the dense halves of each word of `state` to equal the given IV.
+    #[allow(clippy::many_single_char_names)]
+    pub fn constrain_iv<F: FieldExt>(
+        &self,
+        region: &mut Region<'_, F>,
+        state: &State,
+        iv: [u32; STATE],
+    ) -> Result<(), Error> {
+        let (a, b, c, d, e, f, g, h) = match_state(state.clone());
+        let dense_halves = [
+            a.dense_halves,
+            b.dense_halves,
+            c.dense_halves,
+            d.dense_halves,
+            e.dense_halves,
+            f.dense_halves,
+            g.dense_halves,
+            h.dense_halves,
+        ];
+
+        for ((lo, hi), iv_word) in dense_halves.iter().zip(iv.iter()) {
+            let halves = chop_u32(*iv_word, &[16, 16]);
+            region.constrain_constant(lo.var, F::from_u64(halves[0] as u64))?;
+            region.constrain_constant(hi.var, F::from_u64(halves[1] as u64))?;
+        }
+
+        Ok(())
+    }
+
     fn decompose_b<F: FieldExt>(
         &self,
         region: &mut Region<'_, F>,
@@ -13,8 +13,6 @@ impl CompressionConfig {
         state: State,
         schedule_word: (CellValue16, CellValue16),
     ) -> Result<State, Error> {
-        let a_3 = self.extras[0];
-        let a_4 = self.extras[1];
         let a_7 = self.extras[3];
 
         let (a, b, c, d, e, f, g, h) = match_state(state);
@@ -86,21 +84,13 @@ impl CompressionConfig {
                 StateWord::H(g.into()),
             ))
         } else {
-            let abcd_row = get_digest_abcd_row();
-            let efgh_row = get_digest_efgh_row();
-
-            let a_final =
-                self.assign_word_halves_dense(region, abcd_row, a_3, abcd_row, a_4, a_new_val)?;
-
-            let e_final =
-                self.assign_word_halves_dense(region, efgh_row, a_3, efgh_row, a_4, e_new_val)?;
-
+            // The feed-forward copies the final A and E from the cells of the last round.
             Ok(State::new(
-                StateWord::A(RoundWordA::new_dense(a_final)),
+                StateWord::A(RoundWordA::new_dense(a_new_dense)),
                 StateWord::B(a.into()),
                 StateWord::C(b),
                 StateWord::D(c.into()),
-                StateWord::E(RoundWordE::new_dense(e_final)),
+                StateWord::E(RoundWordE::new_dense(e_new_dense)),
                 StateWord::F(e.into()),
                 StateWord::G(f),
                 StateWord::H(g.into()),
@@ -122,3 +122,215 @@ impl<F: FieldExt> Gate<F> {
             )))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::Gate;
+    use crate::table16::util::interleave_u16_with_zeros;
+    use halo2::{
+        arithmetic::FieldExt,
+        circuit::{Layouter, SimpleFloorPlanner},
+        dev::MockProver,
+        pasta::Fp,
+        plonk::{Advice, Circuit, Column, ConstraintSystem, Error, Selector},
+        poly::Rotation,
+    };
+    use rand::{rngs::StdRng, Rng, SeedableRng};
+
+    // The values interpolated in the test of `Gate::lagrange_interpolate`
+    const EVALS: [u32; 8] = [3, 1, 4, 1, 5, 9, 2, 6];
+
+    #[derive(Clone, Copy, Debug)]
+    enum TestGate {
+        // `Gate::range_check` of x on [2, 5]
+        RangeCheck,
+        // `Gate::two_bit_spread_and_range` of x and its spread y
+        TwoBit,
+        // `Gate::three_bit_spread_and_range` of x and its spread y
+        ThreeBit,
+        // `Gate::lagrange_interpolate` of EVALS at x, scaled, minus y scaled
+        Lagrange,
+    }
+
+    #[derive(Clone, Debug)]
+    struct MyConfig {
+        x: Column<Advice>,
+        y: Column<Advice>,
+        s_range_check: Selector,
+        s_two_bit: Selector,
+        s_three_bit: Selector,
+        s_lagrange: Selector,
+    }
+
+    struct MyCircuit {
+        gate: TestGate,
+        x: u64,
+        y: u64,
+    }
+
+    impl<F: FieldExt> Circuit<F> for MyCircuit {
+        type Config = MyConfig;
+        type FloorPlanner = SimpleFloorPlanner;
+
+        fn without_witnesses(&self) -> Self {
+            MyCircuit {
+                gate: self.gate,
+                x: 0,
+                y: 0,
+            }
+        }
+
+        fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+            let x = meta.advice_column();
+            let y = meta.advice_column();
+            let s_range_check = meta.selector();
+            let s_two_bit = meta.selector();
+            let s_three_bit = meta.selector();
+            let s_lagrange = meta.selector();
+
+            meta.create_gate("range_check", |meta| {
+                let s = meta.query_selector(s_range_check);
+                let x = meta.query_advice(x, Rotation::cur());
+
+                vec![("range_check", s * Gate::range_check(x, 2, 5))]
+            });
+
+            meta.create_gate("two_bit_spread_and_range", |meta| {
+                let s = meta.query_selector(s_two_bit);
+                let x = meta.query_advice(x, Rotation::cur());
+                let y = meta.query_advice(y, Rotation::cur());
+
+                Gate::two_bit_spread_and_range(x, y)
+                    .map(move |(name, poly)| (name, s.clone() * poly))
+                    .collect::<Vec<_>>()
+            });
+
+            meta.create_gate("three_bit_spread_and_range", |meta| {
+                let s = meta.query_selector(s_three_bit);
+                let x = meta.query_advice(x, Rotation::cur());
+                let y = meta.query_advice(y, Rotation::cur());
+
+                Gate::three_bit_spread_and_range(x, y)
+                    .map(move |(name, poly)| (name, s.clone() * poly))
+                    .collect::<Vec<_>>()
+            });
+
+            meta.create_gate("lagrange_interpolate", |meta| {
+                let s = meta.query_selector(s_lagrange);
+                let x = meta.query_advice(x, Rotation::cur());
+                let y = meta.query_advice(y, Rotation::cur());
+
+                let (factor, poly) =
+                    Gate::lagrange_interpolate(x, (0..8).collect(), EVALS.to_vec());
+                vec![("lagrange_interpolate", s * (poly - y * factor))]
+            });
+
+            MyConfig {
+                x,
+                y,
+                s_range_check,
+                s_two_bit,
+                s_three_bit,
+                s_lagrange,
+            }
+        }
+
+        fn synthesize(
+            &self,
+            config: MyConfig,
+            mut layouter: impl Layouter<F>,
+        ) -> Result<(), Error> {
+            layouter.assign_region(
+                || "gate",
+                |mut region| {
+                    let selector = match self.gate {
+                        TestGate::RangeCheck => config.s_range_check,
+                        TestGate::TwoBit => config.s_two_bit,
+                        TestGate::ThreeBit => config.s_three_bit,
+                        TestGate::Lagrange => config.s_lagrange,
+                    };
+                    selector.enable(&mut region, 0)?;
+
+                    region.assign_advice(|| "x", config.x, 0, || Ok(F::from_u64(self.x)))?;
+                    region.assign_advice(|| "y", config.y, 0, || Ok(F::from_u64(self.y)))?;
+                    Ok(())
+                },
+            )
+        }
+    }
+
+    fn satisfied(gate: TestGate, x: u64, y: u64) -> bool {
+        let circuit = MyCircuit { gate, x, y };
+        let prover = match MockProver::<Fp>::run(5, &circuit, vec![]) {
+            Ok(prover) => prover,
+            Err(e) => panic!("{:?}", e),
+        };
+        prover.verify().is_ok()
+    }
+
+    // Values of x beyond the small ranges checked exhaustively, drawn from a fixed seed so
+    // that a failure can be reproduced
+    fn large_values() -> Vec<u64> {
+        let mut rng = StdRng::seed_from_u64(0x5a256);
+        let mut values = vec![1 << 16, u32::MAX as u64, u64::MAX];
+        values.extend((0..10).map(|_| rng.gen::<u64>()));
+        values
+    }
+
+    #[test]
+    fn range_check() {
+        for x in (0..16).chain(large_values()) {
+            assert_eq!(
+                satisfied(TestGate::RangeCheck, x, 0),
+                (2..=5).contains(&x),
+                "x = {}",
+                x
+            );
+        }
+    }
+
+    #[test]
+    fn spread_and_range() {
+        for (gate, bits) in [(TestGate::TwoBit, 2), (TestGate::ThreeBit, 3)].iter() {
+            // Only the spread of a dense value in range satisfies the gate.
+            for x in 0..(1 << (bits + 1)) {
+                for y in 0..(1 << (2 * bits + 1)) {
+                    let valid = x < (1 << bits) && y == interleave_u16_with_zeros(x as u16) as u64;
+                    assert_eq!(
+                        satisfied(*gate, x, y),
+                        valid,
+                        "{:?}, x = {}, y = {}",
+                        gate,
+                        x,
+                        y
+                    );
+                }
+            }
+
+            for x in large_values() {
+                assert!(!satisfied(*gate, x, 0));
+                assert!(!satisfied(
+                    *gate,
+                    x,
+                    interleave_u16_with_zeros(x as u16) as u64
+                ));
+            }
+        }
+    }
+
+    #[test]
+    fn lagrange_interpolate() {
+        // The polynomial takes the value EVALS[x] at each x in 0..8.
+        for (x, eval) in EVALS.iter().enumerate() {
+            for y in 0..16 {
+                assert_eq!(
+                    satisfied(TestGate::Lagrange, x as u64, y),
+                    y == *eval as u64,
+                    "x = {}, y = {}",
+                    x,
+                    y
+                );
+            }
+        }
+    }
+}
@@ -0,0 +1,359 @@
+use super::{
+    super::{Sha256, Sha256Instructions, BLOCK_SIZE, DIGEST_SIZE},
+    Bit, BlockWord, DigestWord, Table16Chip, Table16Config,
+};
+
+use halo2::{
+    arithmetic::FieldExt,
+    circuit::{Chip, Layouter},
+    plonk::{ConstraintSystem, Error},
+};
+
+/// The hash of the 64-byte block formed by the left and right child digests, giving
+/// their parent in a [`Sha256MerkleChip`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum NodeHash {
+    /// The SHA-256 compression function, starting from the SHA-256 IV, without padding.
+    /// This takes a single compression per node, but is not SHA-256.
+    Compression,
+    /// SHA-256, as in the Merkle trees of SSZ.
+    Sha256,
+    /// SHA256d, the hash of the hash, as in the transaction Merkle trees of Bitcoin.
+    Sha256d,
+}
+
+/// A chip that verifies paths in binary Merkle trees whose nodes are hashed with SHA-256
+/// or its compression function, as given by a [`NodeHash`].
+///
+/// The chip shares the configuration and lookup table of [`Table16Chip`], so that paths
+/// can be verified in the same circuit as other SHA-256 invocations.
+#[derive(Clone, Debug)]
+pub struct Sha256MerkleChip<F: FieldExt> {
+    chip: Table16Chip<F>,
+    node_hash: NodeHash,
+}
+
+impl<F: FieldExt> Chip<F> for Sha256MerkleChip<F> {
+    type Config = Table16Config;
+    type Loaded = ();
+
+    fn config(&self) -> &Self::Config {
+        self.chip.config()
+    }
+
+    fn loaded(&self) -> &Self::Loaded {
+        &()
+    }
+}
+
+impl<F: FieldExt> Sha256MerkleChip<F> {
+    pub fn construct(config: <Self as Chip<F>>::Config, node_hash: NodeHash) -> Self {
+        Sha256MerkleChip {
+            chip: Table16Chip::construct(config),
+            node_hash,
+        }
+    }
+
+    pub fn configure(meta: &mut ConstraintSystem<F>) -> <Self as Chip<F>>::Config {
+        Table16Chip::configure(meta)
+    }
+
+    pub fn load(config: Table16Config, layouter: &mut impl Layouter<F>) -> Result<(), Error> {
+        Table16Chip::load(config, layouter)
+    }
+
+    /// Hashes the given block of two child digests into their parent.
+    pub fn hash_node(
+        &self,
+        layouter: &mut impl Layouter<F>,
+        block: [BlockWord; BLOCK_SIZE],
+    ) -> Result<[DigestWord; DIGEST_SIZE], Error> {
+        let chip = self.chip.clone();
+        match self.node_hash {
+            NodeHash::Compression => {
+                let iv = chip.initialization_vector(layouter)?;
+                let state = chip.compress(layouter, &iv, block)?;
+                chip.digest(layouter, &state)
+            }
+            NodeHash::Sha256 => Sha256::digest(chip, layouter.namespace(|| "SHA-256"), &block)
+                .map(|digest| *digest.words()),
+            NodeHash::Sha256d => Sha256::sha256d(chip, layouter.namespace(|| "SHA256d"), &block)
+                .map(|digest| *digest.words()),
+        }
+    }
+
+    /// Computes the root of a Merkle path, starting from the leaf digest and hashing it
+    /// with the sibling digest at each level, from the bottom. The direction bit of a
+    /// level is set if the current node is the right child, and is witnessed by the
+    /// prover. Returns the root, whose cells can be constrained to public inputs with
+    /// [`Sha256::constrain_digest_public`](crate::Sha256::constrain_digest_public), along
+    /// with the boolean-constrained direction bits.
+    pub fn root(
+        &self,
+        mut layouter: impl Layouter<F>,
+        leaf: [DigestWord; DIGEST_SIZE],
+        path: &[([DigestWord; DIGEST_SIZE], Option<bool>)],
+    ) -> Result<([DigestWord; DIGEST_SIZE], Vec<Bit>), Error> {
+        let mut node = leaf;
+        let mut bits = Vec::with_capacity(path.len());
+        for (level, (sibling, bit)) in path.iter().enumerate() {
+            let mut layouter = layouter.namespace(|| format!("level {}", level));
+            let bit = self.chip.witness_bit(&mut layouter, *bit)?;
+            let block = self
+                .config()
+                .select
+                .swap(&mut layouter, bit, &node, sibling)?;
+            node = self.hash_node(&mut layouter, block)?;
+            bits.push(bit);
+        }
+
+        Ok((node, bits))
+    }
+
+    /// Returns the inner [`Table16Chip`], to hash leaves or constrain the root.
+    pub fn table16_chip(&self) -> &Table16Chip<F> {
+        &self.chip
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        super::{native, DigestWord, Table16Chip, Table16Config, IV},
+        NodeHash, Sha256MerkleChip,
+    };
+    use crate::{Sha256PublicInstructions, DIGEST_SIZE};
+    use halo2::{
+        arithmetic::FieldExt,
+        circuit::{Layouter, SimpleFloorPlanner},
+        dev::MockProver,
+        pasta::Fp,
+        plonk::{Advice, Circuit, Column, ConstraintSystem, Error},
+    };
+    use std::convert::TryInto;
+
+    // The leaf, and the sibling at each level
+    fn path() -> ([u32; DIGEST_SIZE], Vec<[u32; DIGEST_SIZE]>) {
+        let leaf = native::digest(b"leaf");
+        let siblings = (0..3)
+            .map(|level| native::digest(format!("sibling {}", level).as_bytes()))
+            .collect();
+        (leaf, siblings)
+    }
+
+    // Computes the root of the path with the given direction bits, hashing nodes with the
+    // compression function
+    fn native_root(bits: &[bool]) -> [u32; DIGEST_SIZE] {
+        let (leaf, siblings) = path();
+        siblings
+            .iter()
+            .zip(bits.iter())
+            .fold(leaf, |node, (sibling, bit)| {
+                let (left, right) = if *bit {
+                    (sibling, &node)
+                } else {
+                    (&node, sibling)
+                };
+                let block: Vec<u32> = left.iter().chain(right.iter()).copied().collect();
+                native::compress(IV, block.try_into().unwrap())
+            })
+    }
+
+    struct MyCircuit {
+        node_hash: NodeHash,
+        leaf: Option<[u32; DIGEST_SIZE]>,
+        siblings: Vec<Option<[u32; DIGEST_SIZE]>>,
+        bits: Vec<Option<bool>>,
+    }
+
+    impl MyCircuit {
+        fn new(
+            node_hash: NodeHash,
+            leaf: [u32; DIGEST_SIZE],
+            siblings: Vec<[u32; DIGEST_SIZE]>,
+            bits: Vec<bool>,
+        ) -> Self {
+            MyCircuit {
+                node_hash,
+                leaf: Some(leaf),
+                siblings: siblings.into_iter().map(Some).collect(),
+                bits: bits.into_iter().map(Some).collect(),
+            }
+        }
+    }
+
+    impl<F: FieldExt> Circuit<F> for MyCircuit {
+        type Config = (Table16Config, Column<Advice>);
+        type FloorPlanner = SimpleFloorPlanner;
+
+        fn without_witnesses(&self) -> Self {
+            MyCircuit {
+                node_hash: self.node_hash,
+                leaf: None,
+                siblings: vec![None; self.siblings.len()],
+                bits: vec![None; self.bits.len()],
+            }
+        }
+
+        fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+            let instance = meta.instance_column();
+            let digests = meta.advice_column();
+            meta.enable_equality(digests.into());
+
+            (
+                Table16Chip::configure_with_instance(meta, instance),
+                digests,
+            )
+        }
+
+        fn synthesize(
+            &self,
+            config: Self::Config,
+            mut layouter: impl Layouter<F>,
+        ) -> Result<(), Error> {
+            let (config, digests) = config;
+            Sha256MerkleChip::<F>::load(config.clone(), &mut layouter)?;
+            let chip = Sha256MerkleChip::<F>::construct(config, self.node_hash);
+
+            // Assign the leaf and sibling digests in a column outside the chip.
+            let mut assign_digest = |name: String, digest: Option<[u32; DIGEST_SIZE]>| {
+                layouter
+                    .assign_region(
+                        || name.clone(),
+                        |mut region| {
+                            (0..DIGEST_SIZE)
+                                .map(|row| {
+                                    let word = digest.map(|digest| digest[row]);
+                                    let var = region.assign_advice(
+                                        || format!("word {}", row),
+                                        digests,
+                                        row,
+                                        || {
+                                            word.map(|word| F::from_u64(word as u64))
+                                                .ok_or(Error::SynthesisError)
+                                        },
+                                    )?;
+                                    Ok(DigestWord::new(var, word))
+                                })
+                                .collect::<Result<Vec<_>, Error>>()
+                        },
+                    )
+                    .map(|words| words.try_into().unwrap())
+            };
+            let leaf = assign_digest("leaf".to_string(), self.leaf)?;
+            let mut path = Vec::with_capacity(self.siblings.len());
+            for (level, (sibling, bit)) in self.siblings.iter().zip(self.bits.iter()).enumerate() {
+                let sibling = assign_digest(format!("sibling {}", level), *sibling)?;
+                path.push((sibling, *bit));
+            }
+
+            let (root, _) = chip.root(layouter.namespace(|| "root"), leaf, &path)?;
+
+            chip.table16_chip().constrain_digest_public(
+                &mut layouter.namespace(|| "public root"),
+                &root,
+                0,
+            )
+        }
+    }
+
+    fn public_inputs(root: [u32; DIGEST_SIZE]) -> Vec<Vec<Fp>> {
+        vec![root.iter().map(|word| Fp::from_u64(*word as u64)).collect()]
+    }
+
+    fn verify(circuit: &MyCircuit, root: [u32; DIGEST_SIZE]) -> bool {
+        let prover = match MockProver::<Fp>::run(17, circuit, public_inputs(root)) {
+            Ok(prover) => prover,
+            Err(e) => panic!("{:?}", e),
+        };
+        prover.verify().is_ok()
+    }
+
+    #[test]
+    fn merkle_path() {
+        let bits = vec![false, true, true];
+        let root = native_root(&bits);
+        let (leaf, siblings) = path();
+        let circuit = MyCircuit::new(NodeHash::Compression, leaf, siblings.clone(), bits);
+        assert!(verify(&circuit, root));
+
+        // The path does not lead to the root with other directions.
+        let circuit = MyCircuit::new(
+            NodeHash::Compression,
+            leaf,
+            siblings,
+            vec![true, true, true],
+        );
+        assert!(!verify(&circuit, root));
+    }
+
+    #[test]
+    #[allow(clippy::unreadable_literal)]
+    fn bitcoin_merkle_path() {
+        // The last transaction of Bitcoin block 100000, with the hashes in its Merkle
+        // branch. The hashes are in internal byte order, the reverse of the displayed txids.
+        let circuit = MyCircuit::new(
+            NodeHash::Sha256d,
+            // e9a66845e05d5abc0ad04ec80f774a7e585c6e8db975962d069a522137b80c1d
+            [
+                0x1d0cb837, 0x21529a06, 0x2d9675b9, 0x8d6e5c58, 0x7e4a770f, 0xc84ed00a, 0xbc5a5de0,
+                0x4568a6e9,
+            ],
+            vec![
+                // 6359f0868171b1d194cbee1af2f16ea598ae8fad666d9b012c8ed2b79a236ec4
+                [
+                    0xc46e239a, 0xb7d28e2c, 0x019b6d66, 0xad8fae98, 0xa56ef1f2, 0x1aeecb94,
+                    0xd1b17181, 0x86f05963,
+                ],
+                // The parent of the first two transactions
+                [
+                    0x15b88c51, 0x07195bf0, 0x9eb9da89, 0xb83d95b3, 0xd070079a, 0x3c5c5d3d,
+                    0x17d0dcd8, 0x73fbdacc,
+                ],
+            ],
+            vec![true, true],
+        );
+        // The Merkle root of the block header,
+        // f3e94742aca4b5ef85488dc37c06c3282295ffec960994b2c0d5ac2a25a95766
+        let root = [
+            0x6657a925, 0x2aacd5c0, 0xb2940996, 0xecff9522, 0x28c3067c, 0xc38d4885, 0xefb5a4ac,
+            0x4247e9f3,
+        ];
+        assert!(verify(&circuit, root));
+    }
+
+    #[test]
+    #[allow(clippy::unreadable_literal)]
+    fn ssz_merkle_path() {
+        // The SSZ root of eight zero chunks, whose siblings are the roots of zero subtrees.
+        let zero_hashes = [
+            [0; DIGEST_SIZE],
+            [
+                0xf5a5fd42, 0xd16a2030, 0x2798ef6e, 0xd309979b, 0x43003d23, 0x20d9f0e8, 0xea9831a9,
+                0x2759fb4b,
+            ],
+            [
+                0xdb56114e, 0x00fdd4c1, 0xf85c892b, 0xf35ac9a8, 0x9289aaec, 0xb1ebd0a9, 0x6cde606a,
+                0x748b5d71,
+            ],
+            [
+                0xc78009fd, 0xf07fc56a, 0x11f12237, 0x0658a353, 0xaaa542ed, 0x63e44c4b, 0xc15ff4cd,
+                0x105ab33c,
+            ],
+        ];
+        let circuit = MyCircuit::new(
+            NodeHash::Sha256,
+            zero_hashes[0],
+            zero_hashes[..3].to_vec(),
+            vec![false, true, false],
+        );
+        assert!(verify(&circuit, zero_hashes[3]));
+
+        // The compression function gives a different root.
+        let circuit = MyCircuit {
+            node_hash: NodeHash::Compression,
+            ..circuit
+        };
+        assert!(!verify(&circuit, zero_hashes[3]));
+    }
+}
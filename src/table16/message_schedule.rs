@@ -1,10 +1,12 @@
 use std::convert::TryInto;
 
-use super::{super::BLOCK_SIZE, BlockWord, CellValue16, SpreadInputs, Table16Assignment, ROUNDS};
+use super::{
+    super::BLOCK_SIZE, BlockWord, Byte, CellValue16, SpreadInputs, Table16Assignment, ROUNDS,
+};
 use halo2::{
     arithmetic::FieldExt,
-    circuit::{Cell, Layouter},
-    plonk::{Advice, Column, ConstraintSystem, Error, Fixed},
+    circuit::{Cell, Layouter, Region},
+    plonk::{Advice, Column, ConstraintSystem, Error, Selector},
     poly::Rotation,
 };
 
@@ -13,6 +15,7 @@ mod schedule_util;
 mod subregion1;
 mod subregion2;
 mod subregion3;
+mod subregion_pack;
 
 use schedule_gates::ScheduleGate;
 use schedule_util::*;
@@ -33,23 +36,25 @@ pub(super) struct MessageScheduleConfig {
     extras: [Column<Advice>; 6],
 
     /// Construct a word using reduce_4.
-    s_word: Column<Fixed>,
+    s_word: Selector,
     /// Decomposition gate for W_0, W_62, W_63.
-    s_decompose_0: Column<Fixed>,
+    s_decompose_0: Selector,
     /// Decomposition gate for W_[1..14]
-    s_decompose_1: Column<Fixed>,
+    s_decompose_1: Selector,
     /// Decomposition gate for W_[14..49]
-    s_decompose_2: Column<Fixed>,
+    s_decompose_2: Selector,
     /// Decomposition gate for W_[49..62]
-    s_decompose_3: Column<Fixed>,
+    s_decompose_3: Selector,
     /// sigma_0 gate for W_[1..14]
-    s_lower_sigma_0: Column<Fixed>,
+    s_lower_sigma_0: Selector,
     /// sigma_1 gate for W_[49..62]
-    s_lower_sigma_1: Column<Fixed>,
+    s_lower_sigma_1: Selector,
     /// sigma_0_v2 gate for W_[14..49]
-    s_lower_sigma_0_v2: Column<Fixed>,
+    s_lower_sigma_0_v2: Selector,
     /// sigma_1_v2 gate for W_[14..49]
-    s_lower_sigma_1_v2: Column<Fixed>,
+    s_lower_sigma_1_v2: Selector,
+    /// Packing gate for input words given as bytes
+    s_pack: Selector,
 }
 
 impl<F: FieldExt> Table16Assignment<F> for MessageScheduleConfig {}
@@ -71,16 +76,17 @@ impl MessageScheduleConfig {
         message_schedule: Column<Advice>,
         extras: [Column<Advice>; 6],
     ) -> Self {
-        // Create fixed columns for the selectors we will require.
-        let s_word = meta.fixed_column();
-        let s_decompose_0 = meta.fixed_column();
-        let s_decompose_1 = meta.fixed_column();
-        let s_decompose_2 = meta.fixed_column();
-        let s_decompose_3 = meta.fixed_column();
-        let s_lower_sigma_0 = meta.fixed_column();
-        let s_lower_sigma_1 = meta.fixed_column();
-        let s_lower_sigma_0_v2 = meta.fixed_column();
-        let s_lower_sigma_1_v2 = meta.fixed_column();
+        // Create the selectors we will require.
+        let s_word = meta.selector();
+        let s_decompose_0 = meta.selector();
+        let s_decompose_1 = meta.selector();
+        let s_decompose_2 = meta.selector();
+        let s_decompose_3 = meta.selector();
+        let s_lower_sigma_0 = meta.selector();
+        let s_lower_sigma_1 = meta.selector();
+        let s_lower_sigma_0_v2 = meta.selector();
+        let s_lower_sigma_1_v2 = meta.selector();
+        let s_pack = meta.selector();
 
         // Rename these here for ease of matching the gates to the specification.
         let a_0 = lookup.tag;
@@ -96,7 +102,7 @@ impl MessageScheduleConfig {
 
         // s_word for W_[16..64]
         meta.create_gate("s_word for W_[16..64]", |meta| {
-            let s_word = meta.query_fixed(s_word, Rotation::cur());
+            let s_word = meta.query_selector(s_word);
 
             let sigma_0_lo = meta.query_advice(a_6, Rotation::prev());
             let sigma_0_hi = meta.query_advice(a_6, Rotation::cur());
@@ -130,7 +136,7 @@ impl MessageScheduleConfig {
 
         // s_decompose_0 for all words
         meta.create_gate("s_decompose_0", |meta| {
-            let s_decompose_0 = meta.query_fixed(s_decompose_0, Rotation::cur());
+            let s_decompose_0 = meta.query_selector(s_decompose_0);
             let lo = meta.query_advice(a_3, Rotation::cur());
             let hi = meta.query_advice(a_4, Rotation::cur());
             let word = meta.query_advice(a_5, Rotation::cur());
@@ -141,7 +147,7 @@ impl MessageScheduleConfig {
         // s_decompose_1 for W_[1..14]
         // (3, 4, 11, 14)-bit chunks
         meta.create_gate("s_decompose_1", |meta| {
-            let s_decompose_1 = meta.query_fixed(s_decompose_1, Rotation::cur());
+            let s_decompose_1 = meta.query_selector(s_decompose_1);
             let a = meta.query_advice(a_3, Rotation::next()); // 3-bit chunk
             let b = meta.query_advice(a_4, Rotation::next()); // 4-bit chunk
             let c = meta.query_advice(a_1, Rotation::next()); // 11-bit chunk
@@ -156,7 +162,7 @@ impl MessageScheduleConfig {
         // s_decompose_2 for W_[14..49]
         // (3, 4, 3, 7, 1, 1, 13)-bit chunks
         meta.create_gate("s_decompose_2", |meta| {
-            let s_decompose_2 = meta.query_fixed(s_decompose_2, Rotation::cur());
+            let s_decompose_2 = meta.query_selector(s_decompose_2);
             let a = meta.query_advice(a_3, Rotation::prev()); // 3-bit chunk
             let b = meta.query_advice(a_1, Rotation::next()); // 4-bit chunk
             let c = meta.query_advice(a_4, Rotation::prev()); // 3-bit chunk
@@ -174,7 +180,7 @@ impl MessageScheduleConfig {
         // s_decompose_3 for W_49 to W_61
         // (10, 7, 2, 13)-bit chunks
         meta.create_gate("s_decompose_3", |meta| {
-            let s_decompose_3 = meta.query_fixed(s_decompose_3, Rotation::cur());
+            let s_decompose_3 = meta.query_selector(s_decompose_3);
             let a = meta.query_advice(a_1, Rotation::next()); // 10-bit chunk
             let tag_a = meta.query_advice(a_0, Rotation::next());
             let b = meta.query_advice(a_4, Rotation::next()); // 7-bit chunk
@@ -190,20 +196,20 @@ impl MessageScheduleConfig {
         // (3, 4, 11, 14)-bit chunks
         meta.create_gate("sigma_0 v1", |meta| {
             ScheduleGate::s_lower_sigma_0(
-                meta.query_fixed(s_lower_sigma_0, Rotation::cur()), // s_lower_sigma_0
-                meta.query_advice(a_2, Rotation::prev()),           // spread_r0_even
-                meta.query_advice(a_2, Rotation::cur()),            // spread_r0_odd
-                meta.query_advice(a_2, Rotation::next()),           // spread_r1_even
-                meta.query_advice(a_3, Rotation::cur()),            // spread_r1_odd
-                meta.query_advice(a_5, Rotation::next()),           // a
-                meta.query_advice(a_6, Rotation::next()),           // spread_a
-                meta.query_advice(a_6, Rotation::cur()),            // b
-                meta.query_advice(a_3, Rotation::prev()),           // b_lo
-                meta.query_advice(a_4, Rotation::prev()),           // spread_b_lo
-                meta.query_advice(a_5, Rotation::prev()),           // b_hi
-                meta.query_advice(a_6, Rotation::prev()),           // spread_b_hi
-                meta.query_advice(a_4, Rotation::cur()),            // spread_c
-                meta.query_advice(a_5, Rotation::cur()),            // spread_d
+                meta.query_selector(s_lower_sigma_0),     // s_lower_sigma_0
+                meta.query_advice(a_2, Rotation::prev()), // spread_r0_even
+                meta.query_advice(a_2, Rotation::cur()),  // spread_r0_odd
+                meta.query_advice(a_2, Rotation::next()), // spread_r1_even
+                meta.query_advice(a_3, Rotation::cur()),  // spread_r1_odd
+                meta.query_advice(a_5, Rotation::next()), // a
+                meta.query_advice(a_6, Rotation::next()), // spread_a
+                meta.query_advice(a_6, Rotation::cur()),  // b
+                meta.query_advice(a_3, Rotation::prev()), // b_lo
+                meta.query_advice(a_4, Rotation::prev()), // spread_b_lo
+                meta.query_advice(a_5, Rotation::prev()), // b_hi
+                meta.query_advice(a_6, Rotation::prev()), // spread_b_hi
+                meta.query_advice(a_4, Rotation::cur()),  // spread_c
+                meta.query_advice(a_5, Rotation::cur()),  // spread_d
             )
         });
 
@@ -211,24 +217,24 @@ impl MessageScheduleConfig {
         // (3, 4, 3, 7, 1, 1, 13)-bit chunks
         meta.create_gate("sigma_0 v2", |meta| {
             ScheduleGate::s_lower_sigma_0_v2(
-                meta.query_fixed(s_lower_sigma_0_v2, Rotation::cur()), // s_lower_sigma_0_v2
-                meta.query_advice(a_2, Rotation::prev()),              // spread_r0_even
-                meta.query_advice(a_2, Rotation::cur()),               // spread_r0_odd
-                meta.query_advice(a_2, Rotation::next()),              // spread_r1_even
-                meta.query_advice(a_3, Rotation::cur()),               // spread_r1_odd
-                meta.query_advice(a_3, Rotation::next()),              // a
-                meta.query_advice(a_4, Rotation::next()),              // spread_a
-                meta.query_advice(a_6, Rotation::cur()),               // b
-                meta.query_advice(a_3, Rotation::prev()),              // b_lo
-                meta.query_advice(a_4, Rotation::prev()),              // spread_b_lo
-                meta.query_advice(a_5, Rotation::prev()),              // b_hi
-                meta.query_advice(a_6, Rotation::prev()),              // spread_b_hi
-                meta.query_advice(a_5, Rotation::next()),              // c
-                meta.query_advice(a_6, Rotation::next()),              // spread_c
-                meta.query_advice(a_4, Rotation::cur()),               // spread_d
-                meta.query_advice(a_7, Rotation::cur()),               // spread_e
-                meta.query_advice(a_7, Rotation::next()),              // spread_f
-                meta.query_advice(a_5, Rotation::cur()),               // spread_g
+                meta.query_selector(s_lower_sigma_0_v2), // s_lower_sigma_0_v2
+                meta.query_advice(a_2, Rotation::prev()), // spread_r0_even
+                meta.query_advice(a_2, Rotation::cur()), // spread_r0_odd
+                meta.query_advice(a_2, Rotation::next()), // spread_r1_even
+                meta.query_advice(a_3, Rotation::cur()), // spread_r1_odd
+                meta.query_advice(a_3, Rotation::next()), // a
+                meta.query_advice(a_4, Rotation::next()), // spread_a
+                meta.query_advice(a_6, Rotation::cur()), // b
+                meta.query_advice(a_3, Rotation::prev()), // b_lo
+                meta.query_advice(a_4, Rotation::prev()), // spread_b_lo
+                meta.query_advice(a_5, Rotation::prev()), // b_hi
+                meta.query_advice(a_6, Rotation::prev()), // spread_b_hi
+                meta.query_advice(a_5, Rotation::next()), // c
+                meta.query_advice(a_6, Rotation::next()), // spread_c
+                meta.query_advice(a_4, Rotation::cur()), // spread_d
+                meta.query_advice(a_7, Rotation::cur()), // spread_e
+                meta.query_advice(a_7, Rotation::next()), // spread_f
+                meta.query_advice(a_5, Rotation::cur()), // spread_g
             )
         });
 
@@ -236,24 +242,24 @@ impl MessageScheduleConfig {
         // (3, 4, 3, 7, 1, 1, 13)-bit chunks
         meta.create_gate("sigma_1 v2", |meta| {
             ScheduleGate::s_lower_sigma_1_v2(
-                meta.query_fixed(s_lower_sigma_1_v2, Rotation::cur()), // s_lower_sigma_1_v2
-                meta.query_advice(a_2, Rotation::prev()),              // spread_r0_even
-                meta.query_advice(a_2, Rotation::cur()),               // spread_r0_odd
-                meta.query_advice(a_2, Rotation::next()),              // spread_r1_even
-                meta.query_advice(a_3, Rotation::cur()),               // spread_r1_odd
-                meta.query_advice(a_3, Rotation::next()),              // a
-                meta.query_advice(a_4, Rotation::next()),              // spread_a
-                meta.query_advice(a_6, Rotation::cur()),               // b
-                meta.query_advice(a_3, Rotation::prev()),              // b_lo
-                meta.query_advice(a_4, Rotation::prev()),              // spread_b_lo
-                meta.query_advice(a_5, Rotation::prev()),              // b_hi
-                meta.query_advice(a_6, Rotation::prev()),              // spread_b_hi
-                meta.query_advice(a_5, Rotation::next()),              // c
-                meta.query_advice(a_6, Rotation::next()),              // spread_c
-                meta.query_advice(a_4, Rotation::cur()),               // spread_d
-                meta.query_advice(a_7, Rotation::cur()),               // spread_e
-                meta.query_advice(a_7, Rotation::next()),              // spread_f
-                meta.query_advice(a_5, Rotation::cur()),               // spread_g
+                meta.query_selector(s_lower_sigma_1_v2), // s_lower_sigma_1_v2
+                meta.query_advice(a_2, Rotation::prev()), // spread_r0_even
+                meta.query_advice(a_2, Rotation::cur()), // spread_r0_odd
+                meta.query_advice(a_2, Rotation::next()), // spread_r1_even
+                meta.query_advice(a_3, Rotation::cur()), // spread_r1_odd
+                meta.query_advice(a_3, Rotation::next()), // a
+                meta.query_advice(a_4, Rotation::next()), // spread_a
+                meta.query_advice(a_6, Rotation::cur()), // b
+                meta.query_advice(a_3, Rotation::prev()), // b_lo
+                meta.query_advice(a_4, Rotation::prev()), // spread_b_lo
+                meta.query_advice(a_5, Rotation::prev()), // b_hi
+                meta.query_advice(a_6, Rotation::prev()), // spread_b_hi
+                meta.query_advice(a_5, Rotation::next()), // c
+                meta.query_advice(a_6, Rotation::next()), // spread_c
+                meta.query_advice(a_4, Rotation::cur()), // spread_d
+                meta.query_advice(a_7, Rotation::cur()), // spread_e
+                meta.query_advice(a_7, Rotation::next()), // spread_f
+                meta.query_advice(a_5, Rotation::cur()), // spread_g
             )
         });
 
@@ -261,22 +267,38 @@ impl MessageScheduleConfig {
         // (10, 7, 2, 13)-bit chunks
         meta.create_gate("sigma_1 v1", |meta| {
             ScheduleGate::s_lower_sigma_1(
-                meta.query_fixed(s_lower_sigma_1, Rotation::cur()), // s_lower_sigma_1
-                meta.query_advice(a_2, Rotation::prev()),           // spread_r0_even
-                meta.query_advice(a_2, Rotation::cur()),            // spread_r0_odd
-                meta.query_advice(a_2, Rotation::next()),           // spread_r1_even
-                meta.query_advice(a_3, Rotation::cur()),            // spread_r1_odd
-                meta.query_advice(a_4, Rotation::cur()),            // spread_a
-                meta.query_advice(a_6, Rotation::cur()),            // b
-                meta.query_advice(a_3, Rotation::prev()),           // b_lo
-                meta.query_advice(a_4, Rotation::prev()),           // spread_b_lo
-                meta.query_advice(a_5, Rotation::prev()),           // b_mid
-                meta.query_advice(a_6, Rotation::prev()),           // spread_b_mid
-                meta.query_advice(a_5, Rotation::next()),           // b_hi
-                meta.query_advice(a_6, Rotation::next()),           // spread_b_hi
-                meta.query_advice(a_3, Rotation::next()),           // c
-                meta.query_advice(a_4, Rotation::next()),           // spread_c
-                meta.query_advice(a_5, Rotation::cur()),            // spread_d
+                meta.query_selector(s_lower_sigma_1),     // s_lower_sigma_1
+                meta.query_advice(a_2, Rotation::prev()), // spread_r0_even
+                meta.query_advice(a_2, Rotation::cur()),  // spread_r0_odd
+                meta.query_advice(a_2, Rotation::next()), // spread_r1_even
+                meta.query_advice(a_3, Rotation::cur()),  // spread_r1_odd
+                meta.query_advice(a_4, Rotation::cur()),  // spread_a
+                meta.query_advice(a_6, Rotation::cur()),  // b
+                meta.query_advice(a_3, Rotation::prev()), // b_lo
+                meta.query_advice(a_4, Rotation::prev()), // spread_b_lo
+                meta.query_advice(a_5, Rotation::prev()), // b_mid
+                meta.query_advice(a_6, Rotation::prev()), // spread_b_mid
+                meta.query_advice(a_5, Rotation::next()), // b_hi
+                meta.query_advice(a_6, Rotation::next()), // spread_b_hi
+                meta.query_advice(a_3, Rotation::next()), // c
+                meta.query_advice(a_4, Rotation::next()), // spread_c
+                meta.query_advice(a_5, Rotation::cur()),  // spread_d
+            )
+        });
+
+        // s_pack for input words given as bytes
+        meta.create_gate("s_pack", |meta| {
+            ScheduleGate::s_pack(
+                meta.query_selector(s_pack),             // s_pack
+                meta.query_advice(a_1, Rotation::cur()), // b_0
+                meta.query_advice(a_1, Rotation(1)),     // shifted_b_0
+                meta.query_advice(a_1, Rotation(2)),     // b_1
+                meta.query_advice(a_1, Rotation(3)),     // shifted_b_1
+                meta.query_advice(a_1, Rotation(4)),     // b_2
+                meta.query_advice(a_1, Rotation(5)),     // shifted_b_2
+                meta.query_advice(a_1, Rotation(6)),     // b_3
+                meta.query_advice(a_1, Rotation(7)),     // shifted_b_3
+                meta.query_advice(a_5, Rotation::cur()), // word
             )
         });
 
@@ -293,6 +315,7 @@ impl MessageScheduleConfig {
             s_lower_sigma_1,
             s_lower_sigma_0_v2,
             s_lower_sigma_1_v2,
+            s_pack,
         }
     }
 
@@ -301,142 +324,125 @@ impl MessageScheduleConfig {
         &self,
         layouter: &mut impl Layouter<F>,
         input: [BlockWord; BLOCK_SIZE],
+    ) -> Result<([MessageWord; ROUNDS], [(CellValue16, CellValue16); ROUNDS]), Error> {
+        layouter.assign_region(
+            || "process message block",
+            |mut region| self.assign_schedule(&mut region, input),
+        )
+    }
+
+    /// Assigns the message schedule for the block `input` within a single region.
+    #[allow(clippy::type_complexity)]
+    pub(super) fn assign_schedule<F: FieldExt>(
+        &self,
+        region: &mut Region<'_, F>,
+        input: [BlockWord; BLOCK_SIZE],
     ) -> Result<([MessageWord; ROUNDS], [(CellValue16, CellValue16); ROUNDS]), Error> {
         let mut w = Vec::<MessageWord>::with_capacity(ROUNDS);
         let mut w_halves = Vec::<(CellValue16, CellValue16)>::with_capacity(ROUNDS);
 
-        layouter.assign_region(
-            || "process message block",
-            |mut region| {
-                w = Vec::<MessageWord>::with_capacity(ROUNDS);
-                w_halves = Vec::<(CellValue16, CellValue16)>::with_capacity(ROUNDS);
-
-                // Assign all fixed columns
-                for index in 1..14 {
-                    let row = get_word_row(index);
-                    region.assign_fixed(
-                        || "s_decompose_1",
-                        self.s_decompose_1,
-                        row,
-                        || Ok(F::one()),
-                    )?;
-                    region.assign_fixed(
-                        || "s_lower_sigma_0",
-                        self.s_lower_sigma_0,
-                        row + 3,
-                        || Ok(F::one()),
-                    )?;
-                }
+        // Enable all selectors
+        for index in 1..14 {
+            let row = get_word_row(index);
+            self.s_decompose_1.enable(region, row)?;
+            self.s_lower_sigma_0.enable(region, row + 3)?;
+        }
 
-                for index in 14..49 {
-                    let row = get_word_row(index);
-                    region.assign_fixed(
-                        || "s_decompose_2",
-                        self.s_decompose_2,
-                        row,
-                        || Ok(F::one()),
-                    )?;
-                    region.assign_fixed(
-                        || "s_lower_sigma_0_v2",
-                        self.s_lower_sigma_0_v2,
-                        row + 3,
-                        || Ok(F::one()),
-                    )?;
-                    region.assign_fixed(
-                        || "s_lower_sigma_1_v2",
-                        self.s_lower_sigma_1_v2,
-                        row + SIGMA_0_V2_ROWS + 3,
-                        || Ok(F::one()),
-                    )?;
-
-                    let new_word_idx = index + 2;
-                    region.assign_fixed(
-                        || "s_word",
-                        self.s_word,
-                        get_word_row(new_word_idx - 16) + 1,
-                        || Ok(F::one()),
-                    )?;
-                }
+        for index in 14..49 {
+            let row = get_word_row(index);
+            self.s_decompose_2.enable(region, row)?;
+            self.s_lower_sigma_0_v2.enable(region, row + 3)?;
+            self.s_lower_sigma_1_v2
+                .enable(region, row + SIGMA_0_V2_ROWS + 3)?;
 
-                for index in 49..62 {
-                    let row = get_word_row(index);
-                    region.assign_fixed(
-                        || "s_decompose_3",
-                        self.s_decompose_3,
-                        row,
-                        || Ok(F::one()),
-                    )?;
-                    region.assign_fixed(
-                        || "s_lower_sigma_1",
-                        self.s_lower_sigma_1,
-                        row + 3,
-                        || Ok(F::one()),
-                    )?;
-
-                    let new_word_idx = index + 2;
-                    region.assign_fixed(
-                        || "s_word",
-                        self.s_word,
-                        get_word_row(new_word_idx - 16) + 1,
-                        || Ok(F::one()),
-                    )?;
-                }
+            let new_word_idx = index + 2;
+            self.s_word
+                .enable(region, get_word_row(new_word_idx - 16) + 1)?;
+        }
 
-                for index in 0..64 {
-                    let row = get_word_row(index);
-                    region.assign_fixed(
-                        || "s_decompose_0",
-                        self.s_decompose_0,
-                        row,
-                        || Ok(F::one()),
-                    )?;
-                }
+        for index in 49..62 {
+            let row = get_word_row(index);
+            self.s_decompose_3.enable(region, row)?;
+            self.s_lower_sigma_1.enable(region, row + 3)?;
 
-                // Assign W[0..16]
-                for (i, word) in input.iter().enumerate() {
-                    let (var, halves) = self.assign_word_and_halves(&mut region, word.0, i)?;
-                    w.push(MessageWord { var, value: word.0 });
-                    w_halves.push(halves);
-                }
+            let new_word_idx = index + 2;
+            self.s_word
+                .enable(region, get_word_row(new_word_idx - 16) + 1)?;
+        }
 
-                // Returns the output of sigma_0 on W_[1..14]
-                let lower_sigma_0_output = self.assign_subregion1(&mut region, &input[1..14])?;
+        for index in 0..64 {
+            let row = get_word_row(index);
+            self.s_decompose_0.enable(region, row)?;
+        }
 
-                // sigma_0_v2 and sigma_1_v2 on W_[14..49]
-                // Returns the output of sigma_0_v2 on W_[36..49], to be used in subregion3
-                let lower_sigma_0_v2_output = self.assign_subregion2(
-                    &mut region,
-                    lower_sigma_0_output,
-                    &mut w,
-                    &mut w_halves,
-                )?;
+        // Assign W[0..16]
+        for (i, word) in input.iter().enumerate() {
+            let (var, halves) = self.assign_block_word_and_halves(region, *word, i)?;
+            w.push(MessageWord {
+                var,
+                value: word.value(),
+            });
+            w_halves.push(halves);
+        }
 
-                // sigma_1 v1 on W[49..62]
-                self.assign_subregion3(
-                    &mut region,
-                    lower_sigma_0_v2_output,
-                    &mut w,
-                    &mut w_halves,
-                )?;
+        // The other input words are range-checked by their decompositions, but W_0
+        // is not decomposed, so its halves are looked up instead.
+        self.range_check_halves(region, w_halves[0], 0)?;
 
-                Ok(())
-            },
-        )?;
+        // Returns the output of sigma_0 on W_[1..14]
+        let lower_sigma_0_output = self.assign_subregion1(region, &input[1..14])?;
+
+        // sigma_0_v2 and sigma_1_v2 on W_[14..49]
+        // Returns the output of sigma_0_v2 on W_[36..49], to be used in subregion3
+        let lower_sigma_0_v2_output =
+            self.assign_subregion2(region, lower_sigma_0_output, &mut w, &mut w_halves)?;
+
+        // sigma_1 v1 on W[49..62]
+        self.assign_subregion3(region, lower_sigma_0_v2_output, &mut w, &mut w_halves)?;
 
         Ok((w.try_into().unwrap(), w_halves.try_into().unwrap()))
     }
+
+    /// Packs four bytes into a message word, big-endian, range-checking each byte to
+    /// 8 bits.
+    pub(super) fn pack_bytes<F: FieldExt>(
+        &self,
+        layouter: &mut impl Layouter<F>,
+        bytes: [Byte; 4],
+    ) -> Result<BlockWord, Error> {
+        layouter.assign_region(
+            || "pack bytes",
+            |mut region| self.assign_pack(&mut region, bytes),
+        )
+    }
+}
+
+// The rows taken by the regions of the message schedule, for sizing circuits in tests
+
+#[cfg(test)]
+pub(super) fn schedule_rows() -> usize {
+    get_word_row(ROUNDS - 1) + DECOMPOSE_0_ROWS
 }
 
+// Each byte and 2^8 times the byte are looked up on a row each.
+#[cfg(test)]
+pub(super) const PACK_ROWS: usize = 8;
+
 #[cfg(test)]
 mod tests {
-    use super::super::{super::BLOCK_SIZE, BlockWord, SpreadTableChip, Table16Chip, Table16Config};
+    use super::super::{
+        super::BLOCK_SIZE,
+        native,
+        tamper::{advice_columns, assert_gate_fails, assert_only_lookups_fail, overwrite, tamper},
+        BlockWord, Byte, CellValue32, SpreadTableChip, Table16Chip, Table16Config,
+    };
     use super::schedule_util::*;
     use halo2::{
         arithmetic::FieldExt,
         circuit::{Layouter, SimpleFloorPlanner},
         dev::MockProver,
         pasta::Fp,
-        plonk::{Circuit, ConstraintSystem, Error},
+        plonk::{Advice, Circuit, Column, ConstraintSystem, Error},
     };
 
     #[test]
@@ -469,9 +475,19 @@ mod tests {
 
                 // Run message_scheduler to get W_[0..64]
                 let (w, _) = config.message_schedule.process(&mut layouter, inputs)?;
-                for (word, test_word) in w.iter().zip(MSG_SCHEDULE_TEST_OUTPUT.iter()) {
+                let mut block = [0; BLOCK_SIZE];
+                for (word, input) in block.iter_mut().zip(inputs.iter()) {
+                    *word = input.value().unwrap();
+                }
+                let native_w = native::message_schedule(block);
+                for ((word, test_word), native_word) in w
+                    .iter()
+                    .zip(MSG_SCHEDULE_TEST_OUTPUT.iter())
+                    .zip(native_w.iter())
+                {
                     let word = word.value.unwrap();
                     assert_eq!(word, *test_word);
+                    assert_eq!(word, *native_word);
                 }
                 Ok(())
             }
@@ -485,4 +501,216 @@ mod tests {
         };
         assert_eq!(prover.verify(), Ok(()));
     }
+
+    #[test]
+    fn tampered_cells() {
+        struct MyCircuit {
+            // The region, column index and row of the cell to tamper with
+            tamper: Option<(&'static str, usize, usize)>,
+        }
+
+        impl<F: FieldExt> Circuit<F> for MyCircuit {
+            type Config = Table16Config;
+            type FloorPlanner = SimpleFloorPlanner;
+
+            fn without_witnesses(&self) -> Self {
+                MyCircuit { tamper: None }
+            }
+
+            fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+                Table16Chip::configure_with_byte_table(meta)
+            }
+
+            fn synthesize(
+                &self,
+                config: Self::Config,
+                mut layouter: impl Layouter<F>,
+            ) -> Result<(), Error> {
+                SpreadTableChip::load(config.lookup.clone(), &mut layouter)?;
+
+                let message_schedule = &config.message_schedule;
+                let columns = advice_columns(
+                    &message_schedule.lookup,
+                    message_schedule.message_schedule,
+                    message_schedule.extras,
+                );
+
+                let input: [BlockWord; BLOCK_SIZE] = msg_schedule_test_input();
+                layouter.assign_region(
+                    || "process message block",
+                    |mut region| {
+                        message_schedule.assign_schedule(&mut region, input)?;
+                        if let Some(("process message block", column, row)) = self.tamper {
+                            tamper(&mut region, columns[column], row)?;
+                        }
+                        Ok(())
+                    },
+                )?;
+
+                layouter.assign_region(
+                    || "pack bytes",
+                    |mut region| {
+                        message_schedule.assign_pack(&mut region, [Byte::Constant(0x61); 4])?;
+                        if let Some(("pack bytes", column, row)) = self.tamper {
+                            tamper(&mut region, columns[column], row)?;
+                        }
+                        Ok(())
+                    },
+                )
+            }
+        }
+
+        let circuit = MyCircuit { tamper: None };
+        let prover = match MockProver::<Fp>::run(12, &circuit, vec![]) {
+            Ok(prover) => prover,
+            Err(e) => panic!("{:?}", e),
+        };
+        assert_eq!(prover.verify(), Ok(()));
+
+        // For each gate, a cell it queries, given as (gate, region, column, row)
+        let schedule = "process message block";
+        let cells = [
+            // carry of W_16
+            ("s_word for W_[16..64]", schedule, 9, get_word_row(0) + 1),
+            // lo half of W_62
+            ("s_decompose_0", schedule, 3, get_word_row(62)),
+            // tag_d of W_1
+            ("s_decompose_1", schedule, 0, get_word_row(1)),
+            // tag_g of W_14
+            ("s_decompose_2", schedule, 0, get_word_row(14) - 1),
+            // tag_a of W_49
+            ("s_decompose_3", schedule, 0, get_word_row(49) + 1),
+            // spread_r0_even of sigma_0(W_1)
+            ("sigma_0 v1", schedule, 2, get_word_row(1) + 2),
+            // spread_r1_odd of sigma_0(W_14)
+            ("sigma_0 v2", schedule, 3, get_word_row(14) + 3),
+            // spread_r0_odd of sigma_1(W_14)
+            (
+                "sigma_1 v2",
+                schedule,
+                2,
+                get_word_row(14) + SIGMA_0_V2_ROWS + 3,
+            ),
+            // spread_r1_even of sigma_1(W_49)
+            ("sigma_1 v1", schedule, 2, get_word_row(49) + 4),
+            // shifted_b_0
+            ("s_pack", "pack bytes", 1, 1),
+        ];
+
+        for (gate, region, column, row) in cells.iter() {
+            let circuit = MyCircuit {
+                tamper: Some((region, *column, *row)),
+            };
+            let prover = match MockProver::<Fp>::run(12, &circuit, vec![]) {
+                Ok(prover) => prover,
+                Err(e) => panic!("{:?}", e),
+            };
+            assert_gate_fails(&prover, gate);
+        }
+    }
+
+    #[test]
+    fn assigned_word_out_of_range() {
+        struct MyCircuit {
+            // Whether W_0 and its halves are witnessed as 2^32 more than the input word
+            out_of_range: bool,
+        }
+
+        impl<F: FieldExt> Circuit<F> for MyCircuit {
+            type Config = (Table16Config, Column<Advice>);
+            type FloorPlanner = SimpleFloorPlanner;
+
+            fn without_witnesses(&self) -> Self {
+                MyCircuit {
+                    out_of_range: false,
+                }
+            }
+
+            fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+                let message = meta.advice_column();
+                meta.enable_equality(message.into());
+
+                (Table16Chip::configure(meta), message)
+            }
+
+            fn synthesize(
+                &self,
+                config: Self::Config,
+                mut layouter: impl Layouter<F>,
+            ) -> Result<(), Error> {
+                let (config, message) = config;
+                SpreadTableChip::load(config.lookup.clone(), &mut layouter)?;
+
+                let message_schedule = &config.message_schedule;
+                let columns = advice_columns(
+                    &message_schedule.lookup,
+                    message_schedule.message_schedule,
+                    message_schedule.extras,
+                );
+
+                // Test vector: "abc"
+                let mut input: [BlockWord; BLOCK_SIZE] = msg_schedule_test_input();
+                let word = input[0].value().unwrap();
+                let offset = if self.out_of_range { 1 << 32 } else { 0 };
+
+                // Assign W_0 in a column outside the chip, as another chip would.
+                input[0] = layouter.assign_region(
+                    || "W_0",
+                    |mut region| {
+                        let var = region.assign_advice(
+                            || "W_0",
+                            message,
+                            0,
+                            || Ok(F::from_u64(word as u64 + offset)),
+                        )?;
+                        Ok(BlockWord::Assigned(CellValue32::new(var, Some(word))))
+                    },
+                )?;
+
+                layouter.assign_region(
+                    || "process message block",
+                    |mut region| {
+                        message_schedule.assign_schedule(&mut region, input)?;
+                        if self.out_of_range {
+                            // Witness W_0 as the caller's cell and its hi half as 2^16 more,
+                            // so that W_0 still decomposes into its halves, and carry the
+                            // extra 2^32 out of W_16 = W_0.
+                            let row = get_word_row(0);
+                            let hi = F::from_u64((word >> 16) as u64 + (1 << 16));
+                            overwrite(
+                                &mut region,
+                                columns[5],
+                                row,
+                                F::from_u64(word as u64 + offset),
+                            )?;
+                            overwrite(&mut region, columns[4], row, hi)?;
+                            overwrite(&mut region, columns[1], row + 1, hi)?;
+                            overwrite(&mut region, columns[9], row + 1, F::one())?;
+                        }
+                        Ok(())
+                    },
+                )?;
+
+                Ok(())
+            }
+        }
+
+        let circuit = MyCircuit {
+            out_of_range: false,
+        };
+        let prover = match MockProver::<Fp>::run(17, &circuit, vec![]) {
+            Ok(prover) => prover,
+            Err(e) => panic!("{:?}", e),
+        };
+        assert_eq!(prover.verify(), Ok(()));
+
+        // A caller cell of 2^32 or more passes the copy constraint and s_decompose_0,
+        // and is rejected only by the range check on the halves of W_0.
+        let circuit = MyCircuit { out_of_range: true };
+        let prover = match MockProver::<Fp>::run(17, &circuit, vec![]) {
+            Ok(prover) => prover,
+            Err(e) => panic!("{:?}", e),
+        };
+        assert_only_lookups_fail(&prover);
+    }
 }
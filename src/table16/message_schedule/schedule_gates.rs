@@ -33,6 +33,40 @@ impl<F: FieldExt> ScheduleGate<F> {
             .map(move |(name, poly)| (name, s_word.clone() * poly))
     }
 
+    /// s_pack for a word packed big-endian from four bytes.
+    /// Each byte is range-checked to 8 bits by also looking up 2^8 times the byte in the
+    /// 16-bit spread table.
+    #[allow(clippy::too_many_arguments)]
+    pub fn s_pack(
+        s_pack: Expression<F>,
+        b_0: Expression<F>,
+        shifted_b_0: Expression<F>,
+        b_1: Expression<F>,
+        shifted_b_1: Expression<F>,
+        b_2: Expression<F>,
+        shifted_b_2: Expression<F>,
+        b_3: Expression<F>,
+        shifted_b_3: Expression<F>,
+        word: Expression<F>,
+    ) -> impl Iterator<Item = (&'static str, Expression<F>)> {
+        let pack_check = b_0.clone() * F::from_u64(1 << 24)
+            + b_1.clone() * F::from_u64(1 << 16)
+            + b_2.clone() * F::from_u64(1 << 8)
+            + b_3.clone()
+            + word * (-F::one());
+        let shift_check =
+            |byte: Expression<F>, shifted: Expression<F>| byte * F::from_u64(1 << 8) - shifted;
+
+        array::IntoIter::new([
+            ("pack_check", pack_check),
+            ("range_check_b_0", shift_check(b_0, shifted_b_0)),
+            ("range_check_b_1", shift_check(b_1, shifted_b_1)),
+            ("range_check_b_2", shift_check(b_2, shifted_b_2)),
+            ("range_check_b_3", shift_check(b_3, shifted_b_3)),
+        ])
+        .map(move |(name, poly)| (name, s_pack.clone() * poly))
+    }
+
     /// s_decompose_0 for all words
     pub fn s_decompose_0(
         s_decompose_0: Expression<F>,
@@ -1,4 +1,4 @@
-use super::super::CellValue16;
+use super::super::{BlockWord, CellValue16, SpreadVar, SpreadWord, Table16Assignment};
 use super::MessageScheduleConfig;
 use halo2::{
     arithmetic::FieldExt,
@@ -7,7 +7,7 @@ use halo2::{
 };
 
 #[cfg(test)]
-use super::super::{super::BLOCK_SIZE, BlockWord, ROUNDS};
+use super::super::{super::BLOCK_SIZE, ROUNDS};
 
 // Rows needed for each gate
 pub const DECOMPOSE_0_ROWS: usize = 2;
@@ -61,22 +61,22 @@ pub fn get_word_row(word_idx: usize) -> usize {
 #[cfg(test)]
 pub fn msg_schedule_test_input() -> [BlockWord; BLOCK_SIZE] {
     [
-        BlockWord(Some(0b01100001011000100110001110000000)),
-        BlockWord(Some(0b00000000000000000000000000000000)),
-        BlockWord(Some(0b00000000000000000000000000000000)),
-        BlockWord(Some(0b00000000000000000000000000000000)),
-        BlockWord(Some(0b00000000000000000000000000000000)),
-        BlockWord(Some(0b00000000000000000000000000000000)),
-        BlockWord(Some(0b00000000000000000000000000000000)),
-        BlockWord(Some(0b00000000000000000000000000000000)),
-        BlockWord(Some(0b00000000000000000000000000000000)),
-        BlockWord(Some(0b00000000000000000000000000000000)),
-        BlockWord(Some(0b00000000000000000000000000000000)),
-        BlockWord(Some(0b00000000000000000000000000000000)),
-        BlockWord(Some(0b00000000000000000000000000000000)),
-        BlockWord(Some(0b00000000000000000000000000000000)),
-        BlockWord(Some(0b00000000000000000000000000000000)),
-        BlockWord(Some(0b00000000000000000000000000011000)),
+        BlockWord::new(Some(0b01100001011000100110001110000000)),
+        BlockWord::new(Some(0b00000000000000000000000000000000)),
+        BlockWord::new(Some(0b00000000000000000000000000000000)),
+        BlockWord::new(Some(0b00000000000000000000000000000000)),
+        BlockWord::new(Some(0b00000000000000000000000000000000)),
+        BlockWord::new(Some(0b00000000000000000000000000000000)),
+        BlockWord::new(Some(0b00000000000000000000000000000000)),
+        BlockWord::new(Some(0b00000000000000000000000000000000)),
+        BlockWord::new(Some(0b00000000000000000000000000000000)),
+        BlockWord::new(Some(0b00000000000000000000000000000000)),
+        BlockWord::new(Some(0b00000000000000000000000000000000)),
+        BlockWord::new(Some(0b00000000000000000000000000000000)),
+        BlockWord::new(Some(0b00000000000000000000000000000000)),
+        BlockWord::new(Some(0b00000000000000000000000000000000)),
+        BlockWord::new(Some(0b00000000000000000000000000000000)),
+        BlockWord::new(Some(0b00000000000000000000000000011000)),
     ]
 }
 
@@ -156,10 +156,6 @@ impl MessageScheduleConfig {
         word: Option<u32>,
         word_idx: usize,
     ) -> Result<(Cell, (CellValue16, CellValue16)), Error> {
-        // Rename these here for ease of matching the gates to the specification.
-        let a_3 = self.extras[0];
-        let a_4 = self.extras[1];
-
         let row = get_word_row(word_idx);
 
         let var = {
@@ -171,6 +167,87 @@ impl MessageScheduleConfig {
                 || word.ok_or(Error::SynthesisError),
             )?
         };
+        let halves = self.assign_word_halves(region, word, word_idx)?;
+
+        Ok((var, halves))
+    }
+
+    // Assign a word of the input block and its hi and lo halves. A constant word is
+    // fixed in the circuit, e.g. for message padding, and an assigned word is copied.
+    pub fn assign_block_word_and_halves<F: FieldExt>(
+        &self,
+        region: &mut Region<'_, F>,
+        word: BlockWord,
+        word_idx: usize,
+    ) -> Result<(Cell, (CellValue16, CellValue16)), Error> {
+        match word {
+            BlockWord::Witness(word) => self.assign_word_and_halves(region, word, word_idx),
+            BlockWord::Constant(word) => {
+                let var = region.assign_advice_from_constant(
+                    || format!("W_{}", word_idx),
+                    self.message_schedule,
+                    get_word_row(word_idx),
+                    F::from_u64(word as u64),
+                )?;
+                let halves = self.assign_word_halves(region, Some(word), word_idx)?;
+
+                Ok((var, halves))
+            }
+            BlockWord::Assigned(word) => {
+                let var = self.assign_and_constrain(
+                    region,
+                    || format!("W_{}", word_idx),
+                    self.message_schedule,
+                    get_word_row(word_idx),
+                    word,
+                )?;
+                let halves = self.assign_word_halves(region, word.value, word_idx)?;
+
+                Ok((var, halves))
+            }
+        }
+    }
+
+    // Range-check the hi and lo halves of a word to 16 bits each, using the lookup
+    // columns at the rows of the word
+    pub fn range_check_halves<F: FieldExt>(
+        &self,
+        region: &mut Region<'_, F>,
+        halves: (CellValue16, CellValue16),
+        word_idx: usize,
+    ) -> Result<(), Error> {
+        let row = get_word_row(word_idx);
+
+        let lo = SpreadVar::with_lookup(
+            region,
+            &self.lookup,
+            row,
+            SpreadWord::opt_new(halves.0.value),
+        )?;
+        region.constrain_equal(lo.dense.var, halves.0.var)?;
+        let hi = SpreadVar::with_lookup(
+            region,
+            &self.lookup,
+            row + 1,
+            SpreadWord::opt_new(halves.1.value),
+        )?;
+        region.constrain_equal(hi.dense.var, halves.1.var)?;
+
+        Ok(())
+    }
+
+    // Assign the hi and lo halves of a word
+    fn assign_word_halves<F: FieldExt>(
+        &self,
+        region: &mut Region<'_, F>,
+        word: Option<u32>,
+        word_idx: usize,
+    ) -> Result<(CellValue16, CellValue16), Error> {
+        // Rename these here for ease of matching the gates to the specification.
+        let a_3 = self.extras[0];
+        let a_4 = self.extras[1];
+
+        let row = get_word_row(word_idx);
 
         let w_lo = {
             let w_lo_val = word.map(|word| word as u16);
@@ -201,6 +278,6 @@ impl MessageScheduleConfig {
             CellValue16::new(w_hi_cell, w_hi_val)
         };
 
-        Ok((var, (w_lo, w_hi)))
+        Ok((w_lo, w_hi))
     }
 }
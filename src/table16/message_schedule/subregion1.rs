@@ -30,7 +30,7 @@ impl MessageScheduleConfig {
             .map(|(idx, word)| {
                 // s_decompose_1 on W_[1..14]
                 let subregion1_word = self
-                    .decompose_subregion1_word(region, word.0, idx + 1)
+                    .decompose_subregion1_word(region, word.value(), idx + 1)
                     .unwrap();
 
                 // lower_sigma_0 on W_[1..14]
@@ -0,0 +1,56 @@
+use super::super::{BlockWord, Byte, CellValue32, SpreadVar, SpreadWord};
+use super::MessageScheduleConfig;
+use halo2::{arithmetic::FieldExt, circuit::Region, plonk::Error};
+
+impl MessageScheduleConfig {
+    // Pack four bytes into a word, big-endian. Byte b_i is looked up at row 2i, and
+    // 2^8 * b_i at row 2i + 1; the packed word is placed in the message schedule column.
+    pub fn assign_pack<F: FieldExt>(
+        &self,
+        region: &mut Region<'_, F>,
+        bytes: [Byte; 4],
+    ) -> Result<BlockWord, Error> {
+        self.s_pack.enable(region, 0)?;
+
+        for (idx, byte) in bytes.iter().enumerate() {
+            let row = 2 * idx;
+            let value = byte.value().map(|value| value as u16);
+
+            // Assign and copy or fix b_i
+            let var =
+                SpreadVar::with_lookup(region, &self.lookup, row, SpreadWord::opt_new(value))?;
+            match byte {
+                Byte::Assigned(byte) => region.constrain_equal(var.dense.var, byte.var)?,
+                Byte::Constant(byte) => {
+                    region.constrain_constant(var.dense.var, F::from_u64(*byte as u64))?
+                }
+            }
+
+            // Assign 2^8 * b_i
+            SpreadVar::with_lookup(
+                region,
+                &self.lookup,
+                row + 1,
+                SpreadWord::opt_new(value.map(|value| value << 8)),
+            )?;
+        }
+
+        // Assign the packed word
+        let word = bytes
+            .iter()
+            .map(|byte| byte.value())
+            .collect::<Option<Vec<u8>>>()
+            .map(|bytes| u32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]));
+        let var = region.assign_advice(
+            || "word",
+            self.message_schedule,
+            0,
+            || {
+                word.map(|word| F::from_u64(word as u64))
+                    .ok_or(Error::SynthesisError)
+            },
+        )?;
+
+        Ok(BlockWord::Assigned(CellValue32::new(var, word)))
+    }
+}
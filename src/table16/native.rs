@@ -0,0 +1,364 @@
+//! A native (out-of-circuit) implementation of SHA-256.
+//!
+//! The functions here compute the same values as the `Table16` chip, using the same
+//! decompositions into spread words, so that witnesses and test vectors can be
+//! cross-checked without an external hashing crate.
+
+use super::{util::*, ROUNDS, ROUND_CONSTANTS, STATE};
+use crate::{BLOCK_SIZE, SHA224_DIGEST_SIZE};
+
+/// The SHA-256 initialization vector.
+pub const IV: [u32; STATE] = super::IV;
+
+/// The SHA-224 initialization vector.
+pub const IV_224: [u32; STATE] = super::IV_224;
+
+// Spread form of a piece of at most 16 bits
+fn spread(piece: u32) -> u64 {
+    interleave_u16_with_zeros(piece as u16) as u64
+}
+
+// Spread form of a 32-bit word, as its spread lo and hi halves
+fn spread_halves(word: u32) -> (u64, u64) {
+    (spread(word & 0xffff), spread(word >> 16))
+}
+
+// Returns the even bits of a 64-bit spread sum, as assigned by the sigma gates
+fn even_bits(r: u64) -> u32 {
+    let r_pieces = chop_u64(r, &[32, 32]); // r_0, r_1
+    let (r_0_even, _) = get_even_and_odd_bits_u32(r_pieces[0] as u32);
+    let (r_1_even, _) = get_even_and_odd_bits_u32(r_pieces[1] as u32);
+    r_0_even as u32 + (1 << 16) * r_1_even as u32
+}
+
+// Returns the odd bits of a 64-bit spread sum, as assigned by the Ch and Maj gates
+fn odd_bits(r: u64) -> u32 {
+    let r_pieces = chop_u64(r, &[32, 32]); // r_0, r_1
+    let (_, r_0_odd) = get_even_and_odd_bits_u32(r_pieces[0] as u32);
+    let (_, r_1_odd) = get_even_and_odd_bits_u32(r_pieces[1] as u32);
+    r_0_odd as u32 + (1 << 16) * r_1_odd as u32
+}
+
+/// sigma_0 on a message schedule word, using the (3, 4, 11, 14)-bit decomposition
+/// with the 4-bit piece split into 2-bit halves.
+pub fn lower_sigma_0(word: u32) -> u32 {
+    let pieces = chop_u32(word, &[3, 2, 2, 11, 14]);
+    let spread_a = spread(pieces[0]);
+    let spread_b_lo = spread(pieces[1]);
+    let spread_b_hi = spread(pieces[2]);
+    let spread_c = spread(pieces[3]);
+    let spread_d = spread(pieces[4]);
+
+    let xor_0 = spread_b_lo + (1 << 4) * spread_b_hi + (1 << 8) * spread_c + (1 << 30) * spread_d;
+    let xor_1 = spread_c
+        + (1 << 22) * spread_d
+        + (1 << 50) * spread_a
+        + (1 << 56) * spread_b_lo
+        + (1 << 60) * spread_b_hi;
+    let xor_2 = spread_d
+        + (1 << 28) * spread_a
+        + (1 << 34) * spread_b_lo
+        + (1 << 38) * spread_b_hi
+        + (1 << 42) * spread_c;
+
+    even_bits(xor_0 + xor_1 + xor_2)
+}
+
+/// sigma_1 on a message schedule word, using the (10, 7, 2, 13)-bit decomposition
+/// with the 7-bit piece split into (2, 2, 3)-bit pieces.
+pub fn lower_sigma_1(word: u32) -> u32 {
+    let pieces = chop_u32(word, &[10, 2, 2, 3, 2, 13]);
+    let spread_a = spread(pieces[0]);
+    let spread_b_lo = spread(pieces[1]);
+    let spread_b_mid = spread(pieces[2]);
+    let spread_b_hi = spread(pieces[3]);
+    let spread_c = spread(pieces[4]);
+    let spread_d = spread(pieces[5]);
+
+    let xor_0 = spread_b_lo
+        + (1 << 4) * spread_b_mid
+        + (1 << 8) * spread_b_hi
+        + (1 << 14) * spread_c
+        + (1 << 18) * spread_d;
+    let xor_1 = spread_c
+        + (1 << 4) * spread_d
+        + (1 << 30) * spread_a
+        + (1 << 50) * spread_b_lo
+        + (1 << 54) * spread_b_mid
+        + (1 << 58) * spread_b_hi;
+    let xor_2 = spread_d
+        + (1 << 26) * spread_a
+        + (1 << 46) * spread_b_lo
+        + (1 << 50) * spread_b_mid
+        + (1 << 54) * spread_b_hi
+        + (1 << 60) * spread_c;
+
+    even_bits(xor_0 + xor_1 + xor_2)
+}
+
+/// Σ_0 on the A word of the compression state, using the (2, 11, 9, 10)-bit
+/// decomposition with the 9-bit piece split into 3-bit pieces.
+pub fn upper_sigma_0(word: u32) -> u32 {
+    let pieces = chop_u32(word, &[2, 11, 3, 3, 3, 10]);
+    let spread_a = spread(pieces[0]);
+    let spread_b = spread(pieces[1]);
+    let spread_c_lo = spread(pieces[2]);
+    let spread_c_mid = spread(pieces[3]);
+    let spread_c_hi = spread(pieces[4]);
+    let spread_d = spread(pieces[5]);
+
+    let xor_0 = spread_b
+        + (1 << 22) * spread_c_lo
+        + (1 << 28) * spread_c_mid
+        + (1 << 34) * spread_c_hi
+        + (1 << 40) * spread_d
+        + (1 << 60) * spread_a;
+    let xor_1 = spread_c_lo
+        + (1 << 6) * spread_c_mid
+        + (1 << 12) * spread_c_hi
+        + (1 << 18) * spread_d
+        + (1 << 38) * spread_a
+        + (1 << 42) * spread_b;
+    let xor_2 = spread_d
+        + (1 << 20) * spread_a
+        + (1 << 24) * spread_b
+        + (1 << 46) * spread_c_lo
+        + (1 << 52) * spread_c_mid
+        + (1 << 58) * spread_c_hi;
+
+    even_bits(xor_0 + xor_1 + xor_2)
+}
+
+/// Σ_1 on the E word of the compression state, using the (6, 5, 14, 7)-bit
+/// decomposition with the 6- and 5-bit pieces split into (3, 3) and (2, 3)-bit pieces.
+pub fn upper_sigma_1(word: u32) -> u32 {
+    let pieces = chop_u32(word, &[3, 3, 2, 3, 14, 7]);
+    let spread_a_lo = spread(pieces[0]);
+    let spread_a_hi = spread(pieces[1]);
+    let spread_b_lo = spread(pieces[2]);
+    let spread_b_hi = spread(pieces[3]);
+    let spread_c = spread(pieces[4]);
+    let spread_d = spread(pieces[5]);
+
+    let xor_0 = spread_b_lo
+        + (1 << 4) * spread_b_hi
+        + (1 << 10) * spread_c
+        + (1 << 38) * spread_d
+        + (1 << 52) * spread_a_lo
+        + (1 << 58) * spread_a_hi;
+    let xor_1 = spread_c
+        + (1 << 28) * spread_d
+        + (1 << 42) * spread_a_lo
+        + (1 << 48) * spread_a_hi
+        + (1 << 54) * spread_b_lo
+        + (1 << 58) * spread_b_hi;
+    let xor_2 = spread_d
+        + (1 << 14) * spread_a_lo
+        + (1 << 20) * spread_a_hi
+        + (1 << 26) * spread_b_lo
+        + (1 << 30) * spread_b_hi
+        + (1 << 36) * spread_c;
+
+    even_bits(xor_0 + xor_1 + xor_2)
+}
+
+/// Ch(E, F, G), computed as the odd bits of spread(E) + spread(F) plus the odd bits
+/// of spread(¬E) + spread(G).
+pub fn ch(e: u32, f: u32, g: u32) -> u32 {
+    let (spread_e_lo, spread_e_hi) = spread_halves(e);
+    let (spread_f_lo, spread_f_hi) = spread_halves(f);
+    let (spread_g_lo, spread_g_hi) = spread_halves(g);
+    let spread_neg_e_lo = MASK_EVEN_32 as u64 - spread_e_lo;
+    let spread_neg_e_hi = MASK_EVEN_32 as u64 - spread_e_hi;
+
+    let p = spread_e_lo + spread_f_lo + (1 << 32) * spread_e_hi + (1 << 32) * spread_f_hi;
+    let q = spread_neg_e_lo + spread_g_lo + (1 << 32) * spread_neg_e_hi + (1 << 32) * spread_g_hi;
+
+    odd_bits(p) + odd_bits(q)
+}
+
+/// Maj(A, B, C), computed as the odd bits of spread(A) + spread(B) + spread(C).
+pub fn maj(a: u32, b: u32, c: u32) -> u32 {
+    let (spread_a_lo, spread_a_hi) = spread_halves(a);
+    let (spread_b_lo, spread_b_hi) = spread_halves(b);
+    let (spread_c_lo, spread_c_hi) = spread_halves(c);
+
+    let m = spread_a_lo
+        + spread_b_lo
+        + spread_c_lo
+        + (1 << 32) * spread_a_hi
+        + (1 << 32) * spread_b_hi
+        + (1 << 32) * spread_c_hi;
+
+    odd_bits(m)
+}
+
+/// Expands a message block into the message schedule W_[0..64].
+pub fn message_schedule(block: [u32; BLOCK_SIZE]) -> [u32; ROUNDS] {
+    let mut w = [0; ROUNDS];
+    w[..BLOCK_SIZE].copy_from_slice(&block);
+    for i in BLOCK_SIZE..ROUNDS {
+        w[i] = lower_sigma_1(w[i - 2])
+            .wrapping_add(w[i - 7])
+            .wrapping_add(lower_sigma_0(w[i - 15]))
+            .wrapping_add(w[i - 16]);
+    }
+    w
+}
+
+/// Performs one round of compression on the state (A, B, C, D, E, F, G, H), with round
+/// constant `k` and message schedule word `w`.
+pub fn round(state: [u32; STATE], k: u32, w: u32) -> [u32; STATE] {
+    let [a, b, c, d, e, f, g, h] = state;
+
+    // H' = H + Ch(E, F, G) + Σ_1(E) + K + W
+    let h_prime = h
+        .wrapping_add(ch(e, f, g))
+        .wrapping_add(upper_sigma_1(e))
+        .wrapping_add(k)
+        .wrapping_add(w);
+    // E_new = H' + D
+    let e_new = h_prime.wrapping_add(d);
+    // A_new = H' + Maj(A, B, C) + Σ_0(A)
+    let a_new = h_prime
+        .wrapping_add(maj(a, b, c))
+        .wrapping_add(upper_sigma_0(a));
+
+    [a_new, a, b, c, e_new, e, f, g]
+}
+
+/// Returns the state after each of the compression rounds over the message schedule
+/// `w`, starting from `state`.
+pub fn compression_rounds(state: [u32; STATE], w: &[u32; ROUNDS]) -> [[u32; STATE]; ROUNDS] {
+    let mut rounds = [[0; STATE]; ROUNDS];
+    let mut state = state;
+    for (idx, round_state) in rounds.iter_mut().enumerate() {
+        state = round(state, ROUND_CONSTANTS[idx], w[idx]);
+        *round_state = state;
+    }
+    rounds
+}
+
+/// Compresses a message block into `state`, including the final feed-forward.
+pub fn compress(state: [u32; STATE], block: [u32; BLOCK_SIZE]) -> [u32; STATE] {
+    let w = message_schedule(block);
+    let rounds = compression_rounds(state, &w);
+
+    let mut digest = [0; STATE];
+    for (idx, word) in digest.iter_mut().enumerate() {
+        *word = state[idx].wrapping_add(rounds[ROUNDS - 1][idx]);
+    }
+    digest
+}
+
+/// Pads a message into blocks of big-endian words, as specified in FIPS 180-4.
+pub fn pad(message: &[u8]) -> Vec<[u32; BLOCK_SIZE]> {
+    let mut bytes = message.to_vec();
+    bytes.push(0x80);
+    while bytes.len() % (BLOCK_SIZE * 4) != (BLOCK_SIZE - 2) * 4 {
+        bytes.push(0);
+    }
+    bytes.extend_from_slice(&(message.len() as u64 * 8).to_be_bytes());
+
+    bytes
+        .chunks(BLOCK_SIZE * 4)
+        .map(|chunk| {
+            let mut block = [0; BLOCK_SIZE];
+            for (word, bytes) in block.iter_mut().zip(chunk.chunks(4)) {
+                *word = u32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]);
+            }
+            block
+        })
+        .collect()
+}
+
+/// Computes the SHA-256 digest of a message.
+pub fn digest(message: &[u8]) -> [u32; STATE] {
+    pad(message).into_iter().fold(IV, compress)
+}
+
+/// Computes the SHA-224 digest of a message.
+pub fn digest_224(message: &[u8]) -> [u32; SHA224_DIGEST_SIZE] {
+    let state = pad(message).into_iter().fold(IV_224, compress);
+    let mut digest = [0; SHA224_DIGEST_SIZE];
+    digest.copy_from_slice(&state[..SHA224_DIGEST_SIZE]);
+    digest
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sigma_ch_maj() {
+        // Compare the spread computations against the rotations in the specification
+        let mut x: u32 = 0x243f6a88;
+        let mut next = || {
+            // xorshift32
+            x ^= x << 13;
+            x ^= x >> 17;
+            x ^= x << 5;
+            x
+        };
+        for _ in 0..1000 {
+            let (a, b, c) = (next(), next(), next());
+
+            assert_eq!(
+                lower_sigma_0(a),
+                a.rotate_right(7) ^ a.rotate_right(18) ^ (a >> 3)
+            );
+            assert_eq!(
+                lower_sigma_1(a),
+                a.rotate_right(17) ^ a.rotate_right(19) ^ (a >> 10)
+            );
+            assert_eq!(
+                upper_sigma_0(a),
+                a.rotate_right(2) ^ a.rotate_right(13) ^ a.rotate_right(22)
+            );
+            assert_eq!(
+                upper_sigma_1(a),
+                a.rotate_right(6) ^ a.rotate_right(11) ^ a.rotate_right(25)
+            );
+            assert_eq!(ch(a, b, c), (a & b) ^ (!a & c));
+            assert_eq!(maj(a, b, c), (a & b) ^ (a & c) ^ (b & c));
+        }
+    }
+
+    #[test]
+    #[allow(clippy::unreadable_literal)]
+    fn digest_test_vectors() {
+        // Test vector: ""
+        assert_eq!(
+            digest(b""),
+            [
+                0xe3b0c442, 0x98fc1c14, 0x9afbf4c8, 0x996fb924, 0x27ae41e4, 0x649b934c, 0xa495991b,
+                0x7852b855,
+            ]
+        );
+
+        // Test vector: "abc"
+        assert_eq!(
+            digest(b"abc"),
+            [
+                0xba7816bf, 0x8f01cfea, 0x414140de, 0x5dae2223, 0xb00361a3, 0x96177a9c, 0xb410ff61,
+                0xf20015ad,
+            ]
+        );
+
+        // Test vector: "abcdbcdecdefdefgefghfghighijhijkijkljklmklmnlmnomnopnopq"
+        // The padding is a block of its own.
+        assert_eq!(
+            digest(b"abcdbcdecdefdefgefghfghighijhijkijkljklmklmnlmnomnopnopq"),
+            [
+                0x248d6a61, 0xd20638b8, 0xe5c02693, 0x0c3e6039, 0xa33ce459, 0x64ff2167, 0xf6ecedd4,
+                0x19db06c1,
+            ]
+        );
+
+        // Test vector: "abc", SHA-224
+        assert_eq!(
+            digest_224(b"abc"),
+            [0x23097d22, 0x3405d822, 0x8642a477, 0xbda255b3, 0x2aadbce4, 0xbda0b3f7, 0xe36c9da7,]
+        );
+    }
+}
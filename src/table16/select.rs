@@ -0,0 +1,392 @@
+use super::{
+    super::{BLOCK_SIZE, DIGEST_SIZE},
+    Bit, BlockWord, CellValue16, CellValue32, DigestWord, Gate, Table16Assignment, STATE,
+};
+
+use halo2::{
+    arithmetic::FieldExt,
+    circuit::{Layouter, Region},
+    plonk::{Advice, Column, ConstraintSystem, Error, Selector},
+    poly::Rotation,
+};
+
+/// Configuration for choosing between two values under a boolean selector, used to select
+/// between states or blocks, and to order the children of a node in a Merkle path.
+///
+/// Each pair of values takes a row, in which the selector bit is copied:
+///
+/// | $a_3$ | $a_4$ | $a_5$ | $a_6$  | $a_7$   | $s_{bool}$ | $s_{select}$ | $s_{swap}$ |
+/// |-------|-------|-------|--------|---------|------------|--------------|------------|
+/// | bit   | A     | B     | left   | right   | 1          | 1            | 0/1        |
+///
+/// If the bit is set, left = B and right = A; otherwise left = A and right = B. When only
+/// selecting, the right output is left unassigned.
+#[derive(Clone, Debug)]
+pub(super) struct SelectConfig {
+    message_schedule: Column<Advice>,
+    extras: [Column<Advice>; 6],
+
+    /// Boolean check on the selector bit
+    s_bool: Selector,
+    /// Select gate for each pair of values
+    s_select: Selector,
+    /// Swap gate for each pair of values, which also outputs the unselected value
+    s_swap: Selector,
+}
+
+impl<F: FieldExt> Table16Assignment<F> for SelectConfig {}
+
+impl SelectConfig {
+    pub(super) fn configure<F: FieldExt>(
+        meta: &mut ConstraintSystem<F>,
+        message_schedule: Column<Advice>,
+        extras: [Column<Advice>; 6],
+    ) -> Self {
+        let s_bool = meta.selector();
+        let s_select = meta.selector();
+        let s_swap = meta.selector();
+
+        // Rename these here for ease of matching the gates to the layout.
+        let a_3 = extras[0];
+        let a_4 = extras[1];
+        let a_5 = message_schedule;
+        let a_6 = extras[2];
+        let a_7 = extras[3];
+
+        meta.create_gate("s_bool", |meta| {
+            let s_bool = meta.query_selector(s_bool);
+            let bit = meta.query_advice(a_3, Rotation::cur());
+
+            vec![("bit_check", s_bool * Gate::range_check(bit, 0, 1))]
+        });
+
+        meta.create_gate("s_select", |meta| {
+            let s_select = meta.query_selector(s_select);
+            let bit = meta.query_advice(a_3, Rotation::cur());
+            let a = meta.query_advice(a_4, Rotation::cur());
+            let b = meta.query_advice(a_5, Rotation::cur());
+            let left = meta.query_advice(a_6, Rotation::cur());
+
+            vec![("left_check", s_select * (a.clone() + bit * (b - a) - left))]
+        });
+
+        meta.create_gate("s_swap", |meta| {
+            let s_swap = meta.query_selector(s_swap);
+            let bit = meta.query_advice(a_3, Rotation::cur());
+            let a = meta.query_advice(a_4, Rotation::cur());
+            let b = meta.query_advice(a_5, Rotation::cur());
+            let right = meta.query_advice(a_7, Rotation::cur());
+
+            vec![("right_check", s_swap * (b.clone() + bit * (a - b) - right))]
+        });
+
+        SelectConfig {
+            message_schedule,
+            extras,
+            s_bool,
+            s_select,
+            s_swap,
+        }
+    }
+
+    /// Witnesses a boolean-constrained bit.
+    pub(super) fn witness_bit<F: FieldExt>(
+        &self,
+        layouter: &mut impl Layouter<F>,
+        value: Option<bool>,
+    ) -> Result<Bit, Error> {
+        let a_3 = self.extras[0];
+
+        layouter.assign_region(
+            || "witness bit",
+            |mut region| {
+                self.s_bool.enable(&mut region, 0)?;
+                let var = region.assign_advice(
+                    || "bit",
+                    a_3,
+                    0,
+                    || {
+                        value
+                            .map(|value| F::from_u64(value as u64))
+                            .ok_or(Error::SynthesisError)
+                    },
+                )?;
+                Ok(Bit::new(var, value))
+            },
+        )
+    }
+
+    /// Returns the dense halves of the words of `a` if the bit is unset, and those of `b`
+    /// if it is set.
+    pub(super) fn select_halves<F: FieldExt>(
+        &self,
+        layouter: &mut impl Layouter<F>,
+        bit: Bit,
+        a: [(CellValue16, CellValue16); STATE],
+        b: [(CellValue16, CellValue16); STATE],
+    ) -> Result<[(CellValue16, CellValue16); STATE], Error> {
+        layouter.assign_region(
+            || "select halves",
+            |mut region| {
+                let mut halves = a;
+                for (idx, (a, b)) in a.iter().zip(b.iter()).enumerate() {
+                    let lo = self.assign_select_half(&mut region, 2 * idx, bit, a.0, b.0)?;
+                    let hi = self.assign_select_half(&mut region, 2 * idx + 1, bit, a.1, b.1)?;
+                    halves[idx] = (lo, hi);
+                }
+                Ok(halves)
+            },
+        )
+    }
+
+    /// Returns the words of `a` if the bit is unset, and those of `b` if it is set.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `a` and `b` are of different lengths.
+    pub(super) fn select_words<F: FieldExt>(
+        &self,
+        layouter: &mut impl Layouter<F>,
+        bit: Bit,
+        a: &[BlockWord],
+        b: &[BlockWord],
+    ) -> Result<Vec<BlockWord>, Error> {
+        assert_eq!(a.len(), b.len());
+
+        let a_4 = self.extras[1];
+        let a_5 = self.message_schedule;
+
+        layouter.assign_region(
+            || "select words",
+            |mut region| {
+                a.iter()
+                    .zip(b.iter())
+                    .enumerate()
+                    .map(|(row, (a, b))| {
+                        self.assign_select_row(&mut region, row, bit, false)?;
+                        self.assign_block_word(&mut region, || "A", a_4, row, *a)?;
+                        self.assign_block_word(&mut region, || "B", a_5, row, *b)?;
+
+                        let word = self.assign_left(&mut region, row, bit, a.value(), b.value())?;
+                        Ok(BlockWord::Assigned(word))
+                    })
+                    .collect()
+            },
+        )
+    }
+
+    /// Returns the block formed by the two digests, in the order given by the bit: `a`
+    /// followed by `b` if the bit is unset, and `b` followed by `a` if it is set.
+    pub(super) fn swap<F: FieldExt>(
+        &self,
+        layouter: &mut impl Layouter<F>,
+        bit: Bit,
+        a: &[DigestWord; DIGEST_SIZE],
+        b: &[DigestWord; DIGEST_SIZE],
+    ) -> Result<[BlockWord; BLOCK_SIZE], Error> {
+        layouter.assign_region(
+            || "swap",
+            |mut region| self.assign_swap(&mut region, bit, a, b),
+        )
+    }
+
+    fn assign_swap<F: FieldExt>(
+        &self,
+        region: &mut Region<'_, F>,
+        bit: Bit,
+        a: &[DigestWord; DIGEST_SIZE],
+        b: &[DigestWord; DIGEST_SIZE],
+    ) -> Result<[BlockWord; BLOCK_SIZE], Error> {
+        let a_4 = self.extras[1];
+        let a_5 = self.message_schedule;
+        let a_7 = self.extras[3];
+
+        let mut block = [BlockWord::default(); BLOCK_SIZE];
+        for (row, (a, b)) in a.iter().zip(b.iter()).enumerate() {
+            self.assign_select_row(region, row, bit, true)?;
+            self.assign_and_constrain(region, || "A", a_4, row, *a)?;
+            self.assign_and_constrain(region, || "B", a_5, row, *b)?;
+
+            let left = self.assign_left(region, row, bit, a.value(), b.value())?;
+            let right = bit
+                .value
+                .and_then(|bit| if bit { a.value() } else { b.value() });
+            let right_var = region.assign_advice(
+                || "right",
+                a_7,
+                row,
+                || {
+                    right
+                        .map(|right| F::from_u64(right as u64))
+                        .ok_or(Error::SynthesisError)
+                },
+            )?;
+
+            block[row] = BlockWord::Assigned(left);
+            block[DIGEST_SIZE + row] = BlockWord::Assigned(CellValue32::new(right_var, right));
+        }
+
+        Ok(block)
+    }
+
+    fn assign_select_half<F: FieldExt>(
+        &self,
+        region: &mut Region<'_, F>,
+        row: usize,
+        bit: Bit,
+        a: CellValue16,
+        b: CellValue16,
+    ) -> Result<CellValue16, Error> {
+        let a_4 = self.extras[1];
+        let a_5 = self.message_schedule;
+
+        self.assign_select_row(region, row, bit, false)?;
+        self.assign_and_constrain(region, || "A", a_4, row, a)?;
+        self.assign_and_constrain(region, || "B", a_5, row, b)?;
+
+        let a = a.value.map(|a| a as u32);
+        let b = b.value.map(|b| b as u32);
+        let half = self.assign_left(region, row, bit, a, b)?;
+        Ok(CellValue16::new(
+            half.var,
+            half.value.map(|half| half as u16),
+        ))
+    }
+
+    // Enables the gates on the given row and copies the selector bit into it.
+    fn assign_select_row<F: FieldExt>(
+        &self,
+        region: &mut Region<'_, F>,
+        row: usize,
+        bit: Bit,
+        swap: bool,
+    ) -> Result<(), Error> {
+        let a_3 = self.extras[0];
+
+        self.s_bool.enable(region, row)?;
+        self.s_select.enable(region, row)?;
+        if swap {
+            self.s_swap.enable(region, row)?;
+        }
+        self.assign_and_constrain(region, || "bit", a_3, row, bit)?;
+
+        Ok(())
+    }
+
+    // Assigns left = A if the bit is unset, and B if it is set.
+    fn assign_left<F: FieldExt>(
+        &self,
+        region: &mut Region<'_, F>,
+        row: usize,
+        bit: Bit,
+        a: Option<u32>,
+        b: Option<u32>,
+    ) -> Result<CellValue32, Error> {
+        let a_6 = self.extras[2];
+
+        let value = bit.value.and_then(|bit| if bit { b } else { a });
+        let var = region.assign_advice(
+            || "left",
+            a_6,
+            row,
+            || {
+                value
+                    .map(|value| F::from_u64(value as u64))
+                    .ok_or(Error::SynthesisError)
+            },
+        )?;
+
+        Ok(CellValue32::new(var, value))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::{super::BLOCK_SIZE, native, BlockWord, Table16Chip, Table16Config, IV};
+    use crate::Sha256Instructions;
+    use halo2::{
+        arithmetic::FieldExt,
+        circuit::{Layouter, SimpleFloorPlanner},
+        dev::MockProver,
+        pasta::Fp,
+        plonk::{Circuit, ConstraintSystem, Error},
+    };
+    use std::convert::TryInto;
+
+    fn blocks() -> ([u32; BLOCK_SIZE], [u32; BLOCK_SIZE]) {
+        let mut block_0 = [0; BLOCK_SIZE];
+        let mut block_1 = [0; BLOCK_SIZE];
+        for (idx, (word_0, word_1)) in block_0.iter_mut().zip(block_1.iter_mut()).enumerate() {
+            *word_0 = 0x0101_0101 * idx as u32;
+            *word_1 = 0xdead_beef ^ ((idx as u32) << 24);
+        }
+        (block_0, block_1)
+    }
+
+    #[test]
+    fn select_state() {
+        struct MyCircuit {
+            bit: bool,
+        }
+
+        impl<F: FieldExt> Circuit<F> for MyCircuit {
+            type Config = Table16Config;
+            type FloorPlanner = SimpleFloorPlanner;
+
+            fn without_witnesses(&self) -> Self {
+                MyCircuit { bit: self.bit }
+            }
+
+            fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+                Table16Chip::configure(meta)
+            }
+
+            fn synthesize(
+                &self,
+                config: Self::Config,
+                mut layouter: impl Layouter<F>,
+            ) -> Result<(), Error> {
+                Table16Chip::<F>::load(config.clone(), &mut layouter)?;
+                let chip = Table16Chip::<F>::construct(config);
+
+                let (block_0, block_1) = blocks();
+                let words_0: Vec<_> = block_0.iter().map(|w| BlockWord::Constant(*w)).collect();
+                let words_1: Vec<_> = block_1.iter().map(|w| BlockWord::new(Some(*w))).collect();
+
+                // Compress each block from the IV, and select between the resulting states.
+                let iv = chip.initialization_vector(&mut layouter)?;
+                let state_0 =
+                    chip.compress(&mut layouter, &iv, words_0.clone().try_into().unwrap())?;
+                let state_1 =
+                    chip.compress(&mut layouter, &iv, words_1.clone().try_into().unwrap())?;
+                let bit = chip.witness_bit(&mut layouter, Some(self.bit))?;
+                let state = chip.select_state(&mut layouter, bit, &state_0, &state_1)?;
+
+                // Compress the other block from the selected state.
+                let words = chip.select_words(&mut layouter, bit, &words_1, &words_0)?;
+                let state = chip.compress(&mut layouter, &state, words.try_into().unwrap())?;
+                let digest = chip.digest(&mut layouter, &state)?;
+
+                let expected = if self.bit {
+                    native::compress(native::compress(IV, block_1), block_0)
+                } else {
+                    native::compress(native::compress(IV, block_0), block_1)
+                };
+                for (word, expected) in digest.iter().zip(expected.iter()) {
+                    assert_eq!(word.value().unwrap(), *expected);
+                }
+
+                Ok(())
+            }
+        }
+
+        for bit in [false, true].iter() {
+            let circuit = MyCircuit { bit: *bit };
+            let prover = match MockProver::<Fp>::run(17, &circuit, vec![]) {
+                Ok(prover) => prover,
+                Err(e) => panic!("{:?}", e),
+            };
+            assert_eq!(prover.verify(), Ok(()));
+        }
+    }
+}
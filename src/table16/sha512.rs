@@ -0,0 +1,270 @@
+use std::marker::PhantomData;
+
+use super::{spread_table::*, Byte, CellValue16, CellValue64};
+use crate::{Sha512Instructions, SHA512_BLOCK_SIZE, SHA512_DIGEST_SIZE};
+use halo2::{
+    arithmetic::FieldExt,
+    circuit::{Chip, Layouter},
+    plonk::{Advice, Column, ConstraintSystem, Error, Fixed, Selector},
+    poly::Rotation,
+};
+
+mod compression;
+mod linear;
+mod sha512_gates;
+mod sha512_util;
+
+use sha512_gates::Sha512Gate;
+use sha512_util::*;
+
+#[derive(Clone, Copy, Debug)]
+/// A 64-bit word in a `Table16Sha512` message block.
+pub enum BlockWord64 {
+    /// A word witnessed by the prover.
+    Witness(Option<u64>),
+    /// A word fixed in the circuit, such as message padding.
+    Constant(u64),
+    /// A word assigned elsewhere in the circuit, such as one packed from bytes or output
+    /// by another chip, which is copied into the message schedule. The cell must be in a
+    /// column with equality enabled; its value is range-checked to 64 bits.
+    Assigned(CellValue64),
+}
+
+impl Default for BlockWord64 {
+    fn default() -> Self {
+        BlockWord64::Witness(None)
+    }
+}
+
+impl BlockWord64 {
+    /// Returns the value of this word, if known.
+    pub fn value(&self) -> Option<u64> {
+        match self {
+            BlockWord64::Witness(value) => *value,
+            BlockWord64::Constant(value) => Some(*value),
+            BlockWord64::Assigned(word) => word.value,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+/// A 64-bit word assigned in the circuit as four 16-bit limbs, little-endian. Each limb is
+/// range-checked by the spread table.
+pub struct Word64 {
+    limbs: [CellValue16; LIMBS],
+}
+
+impl Word64 {
+    fn from_limbs(limbs: &[CellValue16]) -> Self {
+        Word64 {
+            limbs: [limbs[0], limbs[1], limbs[2], limbs[3]],
+        }
+    }
+
+    /// Returns the limbs of this word, little-endian, for copying them elsewhere in the
+    /// circuit.
+    pub fn limbs(&self) -> &[CellValue16; LIMBS] {
+        &self.limbs
+    }
+
+    /// Returns the value of this word, if known.
+    pub fn value(&self) -> Option<u64> {
+        self.limbs.iter().rev().try_fold(0, |word, limb| {
+            limb.value.map(|limb| (word << 16) + limb as u64)
+        })
+    }
+}
+
+#[derive(Clone, Debug)]
+/// The state of a `Table16Sha512` compression: the words A, B, C, D, E, F, G, H.
+pub struct State64([Word64; STATE_512]);
+
+/// Configuration for a [`Table16Sha512Chip`].
+#[derive(Clone, Debug)]
+pub struct Table16Sha512Config {
+    lookup: SpreadTableConfig,
+    acc_dense: Column<Advice>,
+    acc_spread: Column<Advice>,
+
+    /// Accumulation gate for each term of a linear combination
+    s_acc: Selector,
+    /// Coefficients of the dense and spread forms of each term
+    c_dense: Column<Fixed>,
+    c_spread: Column<Fixed>,
+    /// Shift of a term that is range-checked to fewer than 16 bits
+    c_shift: Column<Fixed>,
+}
+
+impl Table16Sha512Config {
+    /// Returns the spread table of the chip, to share it with other chips as in
+    /// [`Table16Config::spread_table`](super::Table16Config::spread_table).
+    pub fn spread_table(&self) -> &SpreadTableConfig {
+        &self.lookup
+    }
+}
+
+/// A chip that implements SHA-512, SHA-384 and SHA-512/256 with a maximum lookup table
+/// size of $2^16$.
+///
+/// Each 64-bit word is held as four 16-bit limbs, looked up in the same spread table as
+/// [`Table16Chip`](crate::Table16Chip). Every operation is a region of looked-up values
+/// whose dense and spread forms, weighted by coefficients fixed in the circuit, sum to
+/// zero: the Σ and σ functions decompose a word into pieces that are rotated by their
+/// coefficients, Ch and Maj sum the spread forms of their inputs, and additions modulo
+/// $2^64$ subtract a carry.
+#[derive(Clone, Debug)]
+pub struct Table16Sha512Chip<F: FieldExt> {
+    config: Table16Sha512Config,
+    _marker: PhantomData<F>,
+}
+
+impl<F: FieldExt> Chip<F> for Table16Sha512Chip<F> {
+    type Config = Table16Sha512Config;
+    type Loaded = ();
+
+    fn config(&self) -> &Self::Config {
+        &self.config
+    }
+
+    fn loaded(&self) -> &Self::Loaded {
+        &()
+    }
+}
+
+impl<F: FieldExt> Table16Sha512Chip<F> {
+    pub fn construct(config: <Self as Chip<F>>::Config) -> Self {
+        Self {
+            config,
+            _marker: PhantomData,
+        }
+    }
+
+    pub fn configure(meta: &mut ConstraintSystem<F>) -> <Self as Chip<F>>::Config {
+        // Columns required by this chip:
+        // - Three advice columns to interact with the lookup table.
+        let input_tag = meta.advice_column();
+        let input_dense = meta.advice_column();
+        let input_spread = meta.advice_column();
+
+        let lookup = SpreadTableChip::configure(meta, input_tag, input_dense, input_spread);
+
+        // - Two advice columns to accumulate the dense and spread linear combinations.
+        let acc_dense = meta.advice_column();
+        let acc_spread = meta.advice_column();
+
+        for column in [input_dense, acc_dense, acc_spread].iter() {
+            meta.enable_equality((*column).into());
+        }
+
+        // - One fixed column to load constants (e.g. the IV) into the advice columns.
+        let constants = meta.fixed_column();
+        meta.enable_constant(constants);
+
+        let s_acc = meta.selector();
+        let c_dense = meta.fixed_column();
+        let c_spread = meta.fixed_column();
+        let c_shift = meta.fixed_column();
+
+        meta.create_gate("s_acc", |meta| {
+            let s_acc = meta.query_selector(s_acc);
+            let c_dense = meta.query_fixed(c_dense, Rotation::cur());
+            let c_spread = meta.query_fixed(c_spread, Rotation::cur());
+            let dense = meta.query_advice(input_dense, Rotation::cur());
+            let spread = meta.query_advice(input_spread, Rotation::cur());
+            let acc_dense_cur = meta.query_advice(acc_dense, Rotation::cur());
+            let acc_dense_next = meta.query_advice(acc_dense, Rotation::next());
+            let acc_spread_cur = meta.query_advice(acc_spread, Rotation::cur());
+            let acc_spread_next = meta.query_advice(acc_spread, Rotation::next());
+
+            Sha512Gate::s_acc(
+                s_acc,
+                c_dense,
+                c_spread,
+                dense,
+                spread,
+                acc_dense_cur,
+                acc_dense_next,
+                acc_spread_cur,
+                acc_spread_next,
+            )
+        });
+
+        meta.create_gate("c_shift", |meta| {
+            let c_shift = meta.query_fixed(c_shift, Rotation::cur());
+            let dense = meta.query_advice(input_dense, Rotation::cur());
+            let dense_next = meta.query_advice(input_dense, Rotation::next());
+
+            Sha512Gate::c_shift(c_shift, dense, dense_next)
+        });
+
+        Table16Sha512Config {
+            lookup,
+            acc_dense,
+            acc_spread,
+            s_acc,
+            c_dense,
+            c_spread,
+            c_shift,
+        }
+    }
+
+    pub fn load(config: Table16Sha512Config, layouter: &mut impl Layouter<F>) -> Result<(), Error> {
+        SpreadTableChip::load(config.lookup, layouter)
+    }
+}
+
+impl<F: FieldExt> Sha512Instructions<F> for Table16Sha512Chip<F> {
+    type State = State64;
+    type BlockWord = BlockWord64;
+    type Byte = Byte;
+    type DigestWord = Word64;
+
+    fn initialization_vector(&self, layouter: &mut impl Layouter<F>) -> Result<State64, Error> {
+        self.config().initialize(layouter, IV_512)
+    }
+
+    fn initialization_vector_384(&self, layouter: &mut impl Layouter<F>) -> Result<State64, Error> {
+        self.config().initialize(layouter, IV_384)
+    }
+
+    fn initialization_vector_512_256(
+        &self,
+        layouter: &mut impl Layouter<F>,
+    ) -> Result<State64, Error> {
+        self.config().initialize(layouter, IV_512_256)
+    }
+
+    fn constant_word(value: u64) -> Self::BlockWord {
+        BlockWord64::Constant(value)
+    }
+
+    fn constant_byte(value: u8) -> Self::Byte {
+        Byte::Constant(value)
+    }
+
+    fn pack_bytes(
+        &self,
+        layouter: &mut impl Layouter<F>,
+        bytes: [Self::Byte; 8],
+    ) -> Result<Self::BlockWord, Error> {
+        self.config().pack_bytes(layouter, bytes)
+    }
+
+    fn compress(
+        &self,
+        layouter: &mut impl Layouter<F>,
+        state: &State64,
+        input: [Self::BlockWord; SHA512_BLOCK_SIZE],
+    ) -> Result<State64, Error> {
+        self.config().compress(layouter, state, input)
+    }
+
+    fn digest(
+        &self,
+        _layouter: &mut impl Layouter<F>,
+        state: &State64,
+    ) -> Result<[Self::DigestWord; SHA512_DIGEST_SIZE], Error> {
+        // The state words are already range-checked limbs.
+        Ok(state.0)
+    }
+}
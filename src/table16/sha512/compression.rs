@@ -0,0 +1,389 @@
+use super::{
+    super::{super::SHA512_BLOCK_SIZE, util::MASK_EVEN_32},
+    linear::{Term, TermInput},
+    sha512_util::*,
+    BlockWord64, Byte, CellValue16, CellValue64, State64, Table16Sha512Config, Word64,
+};
+use halo2::{arithmetic::FieldExt, circuit::Layouter, plonk::Error};
+
+// Returns the weights of limb `idx` in the dense and spread forms of a word,
+// 2^(16 * idx) and 4^(16 * idx)
+fn limb_weights<F: FieldExt>(idx: usize) -> (F, F) {
+    (F::from_u128(1 << (16 * idx)), F::from_u128(1 << (32 * idx)))
+}
+
+// Terms copying the limbs of a word, with the given coefficients for each limb
+fn copy_terms<'a, F: FieldExt>(
+    word: &'a Word64,
+    coeffs: impl Fn(usize) -> (F, F) + 'a,
+) -> impl Iterator<Item = Term<F>> + 'a {
+    word.limbs.iter().enumerate().map(move |(idx, limb)| {
+        let (dense, spread) = coeffs(idx);
+        Term::new(TermInput::Copy(*limb), 16, dense, spread)
+    })
+}
+
+// Terms for the even and odd bits of a spread sum, which together subtract the sum.
+// The even and odd limbs are interleaved.
+fn spread_output_terms<F: FieldExt>(
+    even: Option<u64>,
+    odd: Option<u64>,
+) -> impl Iterator<Item = Term<F>> {
+    let even = even.map(limbs_u64);
+    let odd = odd.map(limbs_u64);
+    (0..LIMBS).flat_map(move |idx| {
+        let (_, spread) = limb_weights::<F>(idx);
+        vec![
+            Term::new(
+                TermInput::Witness(even.map(|even| even[idx])),
+                16,
+                F::zero(),
+                -spread,
+            ),
+            Term::new(
+                TermInput::Witness(odd.map(|odd| odd[idx])),
+                16,
+                F::zero(),
+                -spread * F::from_u64(2),
+            ),
+        ]
+    })
+}
+
+// Selects the even or odd limbs of the outputs of a spread sum
+fn spread_outputs(vars: &[super::CellValue16], odd: bool) -> Word64 {
+    let limbs: Vec<_> = vars.iter().skip(odd as usize).step_by(2).copied().collect();
+    Word64::from_limbs(&limbs)
+}
+
+impl Table16Sha512Config {
+    /// Places the given IV in the circuit, returning the initial state.
+    pub(super) fn initialize<F: FieldExt>(
+        &self,
+        layouter: &mut impl Layouter<F>,
+        iv: [u64; STATE_512],
+    ) -> Result<State64, Error> {
+        let iv: Vec<_> = iv.iter().map(|word| BlockWord64::Constant(*word)).collect();
+        let words = self.assign_words(layouter, "IV", &iv)?;
+        Ok(State64([
+            words[0], words[1], words[2], words[3], words[4], words[5], words[6], words[7],
+        ]))
+    }
+
+    /// Processes a block of input, returning the chaining value: the final state added to
+    /// the given state.
+    pub(super) fn compress<F: FieldExt>(
+        &self,
+        layouter: &mut impl Layouter<F>,
+        state: &State64,
+        input: [BlockWord64; SHA512_BLOCK_SIZE],
+    ) -> Result<State64, Error> {
+        // W_[0..16] is the input block, and
+        // W_t = σ_1(W_{t - 2}) + W_{t - 7} + σ_0(W_{t - 15}) + W_{t - 16}
+        let mut w = self.assign_words(layouter, "message block", &input)?;
+        for idx in SHA512_BLOCK_SIZE..ROUNDS_512 {
+            let sigma_0 = self.assign_sigma(
+                layouter,
+                &format!("σ_0(W_{})", idx - 15),
+                &LOWER_SIGMA_0,
+                &w[idx - 15],
+            )?;
+            let sigma_1 = self.assign_sigma(
+                layouter,
+                &format!("σ_1(W_{})", idx - 2),
+                &LOWER_SIGMA_1,
+                &w[idx - 2],
+            )?;
+            let word = self.assign_add(
+                layouter,
+                &format!("W_{}", idx),
+                &[&sigma_1, &w[idx - 7], &sigma_0, &w[idx - 16]],
+                0,
+            )?;
+            w.push(word);
+        }
+
+        let mut working = state.0;
+        for (idx, w) in w.iter().enumerate() {
+            working = self.assign_round(layouter, idx, working, w)?;
+        }
+
+        // Feed-forward
+        let mut chaining = state.0;
+        for (idx, (word, working)) in chaining.iter_mut().zip(working.iter()).enumerate() {
+            *word = self.assign_add(
+                layouter,
+                &format!("feed forward {}", idx),
+                &[&*word, working],
+                0,
+            )?;
+        }
+
+        Ok(State64(chaining))
+    }
+
+    // Assigns a round of compression, returning the new working state
+    fn assign_round<F: FieldExt>(
+        &self,
+        layouter: &mut impl Layouter<F>,
+        idx: usize,
+        state: [Word64; STATE_512],
+        w: &Word64,
+    ) -> Result<[Word64; STATE_512], Error> {
+        let [a, b, c, d, e, f, g, h] = state;
+
+        // H' = H + Ch(E, F, G) + Σ_1(E) + K + W
+        let sigma_1 =
+            self.assign_sigma(layouter, &format!("Σ_1(E_{})", idx), &UPPER_SIGMA_1, &e)?;
+        let ch = self.assign_spread_sum(
+            layouter,
+            &format!("Ch_{}", idx),
+            &[(&e, false), (&f, false)],
+        )?;
+        let ch_neg = self.assign_spread_sum(
+            layouter,
+            &format!("Ch_neg_{}", idx),
+            &[(&e, true), (&g, false)],
+        )?;
+        let h_prime = self.assign_add(
+            layouter,
+            &format!("H'_{}", idx),
+            &[&h, &ch, &ch_neg, &sigma_1, w],
+            ROUND_CONSTANTS_512[idx],
+        )?;
+
+        // E_new = H' + D
+        let e_new = self.assign_add(layouter, &format!("E_new_{}", idx), &[&h_prime, &d], 0)?;
+
+        // A_new = H' + Maj(A, B, C) + Σ_0(A)
+        let sigma_0 =
+            self.assign_sigma(layouter, &format!("Σ_0(A_{})", idx), &UPPER_SIGMA_0, &a)?;
+        let maj = self.assign_spread_sum(
+            layouter,
+            &format!("Maj_{}", idx),
+            &[(&a, false), (&b, false), (&c, false)],
+        )?;
+        let a_new = self.assign_add(
+            layouter,
+            &format!("A_new_{}", idx),
+            &[&h_prime, &maj, &sigma_0],
+            0,
+        )?;
+
+        Ok([a_new, a, b, c, e_new, e, f, g])
+    }
+
+    /// Packs eight bytes into a word, big-endian, range-checking each byte to 8 bits.
+    pub(super) fn pack_bytes<F: FieldExt>(
+        &self,
+        layouter: &mut impl Layouter<F>,
+        bytes: [Byte; 8],
+    ) -> Result<BlockWord64, Error> {
+        let terms: Vec<_> = bytes
+            .iter()
+            .enumerate()
+            .map(|(idx, byte)| {
+                let input = match byte {
+                    Byte::Assigned(byte) => TermInput::Copy(CellValue16::new(
+                        byte.var,
+                        byte.value.map(|value| value as u16),
+                    )),
+                    Byte::Constant(value) => TermInput::Constant(*value as u16),
+                };
+                Term::new(input, 8, F::from_u64(1 << (8 * (7 - idx))), F::zero())
+            })
+            .collect();
+        let value = bytes.iter().try_fold(0, |word, byte| {
+            byte.value().map(|byte| (word << 8) + byte as u64)
+        });
+
+        let (_, word) = self.assign_sum(layouter, "pack bytes", &terms, None)?;
+        Ok(BlockWord64::Assigned(CellValue64::new(word, value)))
+    }
+
+    // Assigns words, range-checking each limb. The limbs of an assigned word are
+    // constrained to sum to its cell, in a region of their own.
+    fn assign_words<F: FieldExt>(
+        &self,
+        layouter: &mut impl Layouter<F>,
+        name: &str,
+        words: &[BlockWord64],
+    ) -> Result<Vec<Word64>, Error> {
+        let mut terms = Vec::with_capacity(words.len() * LIMBS);
+        for word in words {
+            let limbs = word.value().map(limbs_u64);
+            for idx in 0..LIMBS {
+                let input = match word {
+                    BlockWord64::Witness(_) => TermInput::Witness(limbs.map(|limbs| limbs[idx])),
+                    BlockWord64::Constant(word) => TermInput::Constant(limbs_u64(*word)[idx]),
+                    BlockWord64::Assigned(_) => continue,
+                };
+                terms.push(Term::new(input, 16, F::zero(), F::zero()));
+            }
+        }
+
+        let vars = if terms.is_empty() {
+            vec![]
+        } else {
+            self.assign_linear(layouter, name, (F::zero(), F::zero()), &terms)?
+        };
+        let mut vars = vars.chunks(LIMBS).map(Word64::from_limbs);
+
+        words
+            .iter()
+            .enumerate()
+            .map(|(idx, word)| match word {
+                BlockWord64::Assigned(word) => {
+                    let limbs = word.value.map(limbs_u64);
+                    let terms: Vec<_> = (0..LIMBS)
+                        .map(|idx| {
+                            let input = TermInput::Witness(limbs.map(|limbs| limbs[idx]));
+                            Term::new(input, 16, limb_weights::<F>(idx).0, F::zero())
+                        })
+                        .collect();
+                    let (limbs, _) = self.assign_sum(
+                        layouter,
+                        &format!("{} word {}", name, idx),
+                        &terms,
+                        Some(word.var),
+                    )?;
+                    Ok(Word64::from_limbs(&limbs))
+                }
+                _ => Ok(vars
+                    .next()
+                    .expect("a word of limbs for each unassigned word")),
+            })
+            .collect()
+    }
+
+    // Assigns a Σ or σ function on a word. The word is decomposed into pieces, whose
+    // dense forms sum to the word. The spread forms of the pieces, weighted by their
+    // positions in each shifted word, sum to R; the even bits of R are the output.
+    fn assign_sigma<F: FieldExt>(
+        &self,
+        layouter: &mut impl Layouter<F>,
+        name: &str,
+        sigma: &Sigma,
+        word: &Word64,
+    ) -> Result<Word64, Error> {
+        let value = word.value();
+
+        let mut terms: Vec<_> = copy_terms(word, |idx| {
+            let (dense, _) = limb_weights::<F>(idx);
+            (-dense, F::zero())
+        })
+        .collect();
+
+        let pieces = value.map(|value| chop_pieces(value, sigma.pieces));
+        for (idx, (len, (offset, coeff))) in
+            sigma.pieces.iter().zip(sigma.piece_coeffs()).enumerate()
+        {
+            terms.push(Term::new(
+                TermInput::Witness(pieces.as_ref().map(|pieces| pieces[idx])),
+                *len,
+                F::from_u64(1 << offset),
+                F::from_u128(coeff),
+            ));
+        }
+
+        let r = value.map(|value| even_and_odd_bits_u64(&sigma.shifted(value)));
+        let outputs = terms.len();
+        terms.extend(spread_output_terms(
+            r.map(|(even, _)| even),
+            r.map(|(_, odd)| odd),
+        ));
+
+        let vars = self.assign_linear(layouter, name, (F::zero(), F::zero()), &terms)?;
+        Ok(spread_outputs(&vars[outputs..], false))
+    }
+
+    // Sums the spread forms of words, each negated if its flag is set, returning the odd
+    // bits of the sum. This computes the AND of two words, or the majority of three.
+    fn assign_spread_sum<F: FieldExt>(
+        &self,
+        layouter: &mut impl Layouter<F>,
+        name: &str,
+        words: &[(&Word64, bool)],
+    ) -> Result<Word64, Error> {
+        // The spread form of the negation of a 16-bit limb is MASK_EVEN_32 minus its
+        // spread form.
+        let mut start = F::zero();
+        let mut terms = Vec::with_capacity(words.len() * LIMBS + 2 * LIMBS);
+        for (word, negate) in words {
+            terms.extend(copy_terms(word, |idx| {
+                let (_, spread) = limb_weights::<F>(idx);
+                if *negate {
+                    (F::zero(), -spread)
+                } else {
+                    (F::zero(), spread)
+                }
+            }));
+            if *negate {
+                for idx in 0..LIMBS {
+                    let (_, spread) = limb_weights::<F>(idx);
+                    start += spread * F::from_u64(MASK_EVEN_32 as u64);
+                }
+            }
+        }
+
+        let values: Option<Vec<u64>> = words
+            .iter()
+            .map(|(word, negate)| {
+                word.value()
+                    .map(|value| if *negate { !value } else { value })
+            })
+            .collect();
+        let r = values.map(|values| even_and_odd_bits_u64(&values));
+        let outputs = terms.len();
+        terms.extend(spread_output_terms(
+            r.map(|(even, _)| even),
+            r.map(|(_, odd)| odd),
+        ));
+
+        let vars = self.assign_linear(layouter, name, (F::zero(), start), &terms)?;
+        Ok(spread_outputs(&vars[outputs..], true))
+    }
+
+    // Adds words and a constant modulo 2^64. The limbs of the words sum to the constant
+    // plus the limbs of the output and a carry times 2^64.
+    fn assign_add<F: FieldExt>(
+        &self,
+        layouter: &mut impl Layouter<F>,
+        name: &str,
+        words: &[&Word64],
+        constant: u64,
+    ) -> Result<Word64, Error> {
+        let mut terms: Vec<_> = words
+            .iter()
+            .flat_map(|word| copy_terms(word, |idx| (limb_weights::<F>(idx).0, F::zero())))
+            .collect();
+
+        let values: Option<Vec<u64>> = words.iter().map(|word| word.value()).collect();
+        let sum = values.map(|values| {
+            values
+                .iter()
+                .fold(constant as u128, |sum, value| sum + *value as u128)
+        });
+        let limbs = sum.map(|sum| limbs_u64(sum as u64));
+        let outputs = terms.len();
+        for idx in 0..LIMBS {
+            let (dense, _) = limb_weights::<F>(idx);
+            terms.push(Term::new(
+                TermInput::Witness(limbs.map(|limbs| limbs[idx])),
+                16,
+                -dense,
+                F::zero(),
+            ));
+        }
+        terms.push(Term::new(
+            TermInput::Witness(sum.map(|sum| (sum >> 64) as u16)),
+            16,
+            -F::from_u128(1 << 64),
+            F::zero(),
+        ));
+
+        let vars =
+            self.assign_linear(layouter, name, (F::from_u64(constant), F::zero()), &terms)?;
+        Ok(Word64::from_limbs(&vars[outputs..outputs + LIMBS]))
+    }
+}
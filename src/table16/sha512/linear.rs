@@ -0,0 +1,212 @@
+use super::{
+    super::{CellValue16, SpreadVar, SpreadWord},
+    Table16Sha512Config,
+};
+use halo2::{
+    arithmetic::FieldExt,
+    circuit::{Cell, Layouter, Region},
+    plonk::Error,
+};
+
+/// The value looked up in a row of a linear combination.
+#[derive(Clone, Copy, Debug)]
+pub(super) enum TermInput {
+    /// A value witnessed by the prover.
+    Witness(Option<u16>),
+    /// A value copied from a cell elsewhere in the circuit.
+    Copy(CellValue16),
+    /// A value fixed in the circuit.
+    Constant(u16),
+}
+
+impl TermInput {
+    fn value(&self) -> Option<u16> {
+        match self {
+            TermInput::Witness(value) => *value,
+            TermInput::Copy(cell) => cell.value,
+            TermInput::Constant(value) => Some(*value),
+        }
+    }
+}
+
+/// A term of a linear combination: a value looked up in the spread table, range-checked
+/// to `bits` bits, with coefficients for its dense and spread forms.
+#[derive(Clone, Copy, Debug)]
+pub(super) struct Term<F: FieldExt> {
+    input: TermInput,
+    bits: u8,
+    dense: F,
+    spread: F,
+}
+
+impl<F: FieldExt> Term<F> {
+    pub(super) fn new(input: TermInput, bits: u8, dense: F, spread: F) -> Self {
+        assert!(bits > 0 && bits <= 16);
+        Term {
+            input,
+            bits,
+            dense,
+            spread,
+        }
+    }
+}
+
+impl Table16Sha512Config {
+    /// Assigns a region in which the dense forms of the terms, weighted by their dense
+    /// coefficients, sum to zero, as do their spread forms weighted by their spread
+    /// coefficients. `start` holds the constant terms of the two sums.
+    ///
+    /// Each term takes a row, plus a row if it is range-checked to fewer than 16 bits.
+    /// Returns the dense cell of each term.
+    pub(super) fn assign_linear<F: FieldExt>(
+        &self,
+        layouter: &mut impl Layouter<F>,
+        name: &str,
+        start: (F, F),
+        terms: &[Term<F>],
+    ) -> Result<Vec<CellValue16>, Error> {
+        layouter.assign_region(
+            || name,
+            |mut region| {
+                let (vars, acc_vars) = self.assign_terms(&mut region, start, terms)?;
+
+                // Both linear combinations sum to zero
+                if let Some((acc_dense, acc_spread)) = acc_vars {
+                    region.constrain_constant(acc_dense, F::zero())?;
+                    region.constrain_constant(acc_spread, F::zero())?;
+                }
+
+                Ok(vars)
+            },
+        )
+    }
+
+    /// Assigns a region in which the dense forms of the terms, weighted by their dense
+    /// coefficients, sum to the value of a cell. If `sum` is given, the cell is constrained
+    /// to equal it. The spread coefficients of the terms must be zero.
+    ///
+    /// Returns the dense cell of each term, and the cell of the sum.
+    pub(super) fn assign_sum<F: FieldExt>(
+        &self,
+        layouter: &mut impl Layouter<F>,
+        name: &str,
+        terms: &[Term<F>],
+        sum: Option<Cell>,
+    ) -> Result<(Vec<CellValue16>, Cell), Error> {
+        assert!(!terms.is_empty());
+        layouter.assign_region(
+            || name,
+            |mut region| {
+                let (vars, acc_vars) =
+                    self.assign_terms(&mut region, (F::zero(), F::zero()), terms)?;
+                let (acc_dense, acc_spread) = acc_vars.expect("terms is not empty");
+
+                region.constrain_constant(acc_spread, F::zero())?;
+                if let Some(sum) = sum {
+                    region.constrain_equal(acc_dense, sum)?;
+                }
+
+                Ok((vars, acc_dense))
+            },
+        )
+    }
+
+    // Assigns the terms and their running sums, returning the dense cell of each term and
+    // the cells of the final sums, if there are any terms.
+    #[allow(clippy::type_complexity)]
+    fn assign_terms<F: FieldExt>(
+        &self,
+        region: &mut Region<'_, F>,
+        start: (F, F),
+        terms: &[Term<F>],
+    ) -> Result<(Vec<CellValue16>, Option<(Cell, Cell)>), Error> {
+        let mut vars = Vec::with_capacity(terms.len());
+
+        region.assign_advice_from_constant(|| "acc_dense", self.acc_dense, 0, start.0)?;
+        region.assign_advice_from_constant(|| "acc_spread", self.acc_spread, 0, start.1)?;
+        let mut acc_dense = Some(start.0);
+        let mut acc_spread = Some(start.1);
+        let mut acc_vars = None;
+
+        let mut row = 0;
+        for term in terms {
+            self.s_acc.enable(region, row)?;
+            region.assign_fixed(|| "c_dense", self.c_dense, row, || Ok(term.dense))?;
+            region.assign_fixed(|| "c_spread", self.c_spread, row, || Ok(term.spread))?;
+
+            // Look up the term, and copy or fix it
+            let value = term.input.value();
+            let var = SpreadVar::with_lookup(
+                region,
+                &self.lookup.input,
+                row,
+                SpreadWord::opt_new(value),
+            )?;
+            match term.input {
+                TermInput::Witness(_) => (),
+                TermInput::Copy(cell) => region.constrain_equal(var.dense.var, cell.var)?,
+                TermInput::Constant(value) => {
+                    region.constrain_constant(var.dense.var, F::from_u64(value as u64))?
+                }
+            }
+            vars.push(var.dense);
+
+            acc_dense = acc_dense
+                .zip(var.dense.value)
+                .map(|(acc, dense)| acc + term.dense * F::from_u64(dense as u64));
+            acc_spread = acc_spread
+                .zip(var.spread.value)
+                .map(|(acc, spread)| acc + term.spread * F::from_u64(spread as u64));
+            row += 1;
+            acc_vars = Some(self.assign_acc(region, row, acc_dense, acc_spread)?);
+
+            // Range-check the term to `bits` bits by also looking up its shift by
+            // 16 - bits, which must fit in 16 bits
+            if term.bits < 16 {
+                let shift = 16 - term.bits;
+                region.assign_fixed(
+                    || "c_shift",
+                    self.c_shift,
+                    row - 1,
+                    || Ok(F::from_u64(1 << shift)),
+                )?;
+                // The shifted term is not part of the sums
+                self.s_acc.enable(region, row)?;
+                region.assign_fixed(|| "c_dense", self.c_dense, row, || Ok(F::zero()))?;
+                region.assign_fixed(|| "c_spread", self.c_spread, row, || Ok(F::zero()))?;
+                SpreadVar::with_lookup(
+                    region,
+                    &self.lookup.input,
+                    row,
+                    SpreadWord::opt_new(value.map(|value| value << shift)),
+                )?;
+                row += 1;
+                acc_vars = Some(self.assign_acc(region, row, acc_dense, acc_spread)?);
+            }
+        }
+
+        Ok((vars, acc_vars))
+    }
+
+    fn assign_acc<F: FieldExt>(
+        &self,
+        region: &mut Region<'_, F>,
+        row: usize,
+        acc_dense: Option<F>,
+        acc_spread: Option<F>,
+    ) -> Result<(Cell, Cell), Error> {
+        let acc_dense = region.assign_advice(
+            || "acc_dense",
+            self.acc_dense,
+            row,
+            || acc_dense.ok_or(Error::SynthesisError),
+        )?;
+        let acc_spread = region.assign_advice(
+            || "acc_spread",
+            self.acc_spread,
+            row,
+            || acc_spread.ok_or(Error::SynthesisError),
+        )?;
+        Ok((acc_dense, acc_spread))
+    }
+}
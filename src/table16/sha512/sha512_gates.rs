@@ -0,0 +1,40 @@
+use halo2::{arithmetic::FieldExt, plonk::Expression};
+use std::marker::PhantomData;
+
+pub struct Sha512Gate<F: FieldExt>(PhantomData<F>);
+
+impl<F: FieldExt> Sha512Gate<F> {
+    // Accumulate a linear combination of the dense and spread values looked up in each
+    // row, with coefficients fixed in the circuit
+    #[allow(clippy::too_many_arguments)]
+    pub fn s_acc(
+        s_acc: Expression<F>,
+        c_dense: Expression<F>,
+        c_spread: Expression<F>,
+        dense: Expression<F>,
+        spread: Expression<F>,
+        acc_dense: Expression<F>,
+        acc_dense_next: Expression<F>,
+        acc_spread: Expression<F>,
+        acc_spread_next: Expression<F>,
+    ) -> impl Iterator<Item = (&'static str, Expression<F>)> {
+        let dense_check = acc_dense + c_dense * dense - acc_dense_next;
+        let spread_check = acc_spread + c_spread * spread - acc_spread_next;
+
+        vec![("dense_check", dense_check), ("spread_check", spread_check)]
+            .into_iter()
+            .map(move |(name, poly)| (name, s_acc.clone() * poly))
+    }
+
+    // Range-check a value looked up in the current row to fewer than 16 bits, given that
+    // the value looked up in the next row is the current value times c_shift = 2^(16 - bits)
+    pub fn c_shift(
+        c_shift: Expression<F>,
+        dense: Expression<F>,
+        dense_next: Expression<F>,
+    ) -> impl Iterator<Item = (&'static str, Expression<F>)> {
+        let shift_check = dense_next - c_shift.clone() * dense;
+
+        std::iter::once(("shift_check", c_shift * shift_check))
+    }
+}
@@ -0,0 +1,250 @@
+use super::super::util::*;
+
+/// The number of rounds in the SHA-512 compression function.
+pub const ROUNDS_512: usize = 80;
+/// The number of 64-bit words in the SHA-512 state.
+pub const STATE_512: usize = 8;
+/// The number of 16-bit limbs in a 64-bit word.
+pub const LIMBS: usize = 4;
+
+#[allow(clippy::unreadable_literal)]
+pub(super) const ROUND_CONSTANTS_512: [u64; ROUNDS_512] = [
+    0x428a2f98d728ae22,
+    0x7137449123ef65cd,
+    0xb5c0fbcfec4d3b2f,
+    0xe9b5dba58189dbbc,
+    0x3956c25bf348b538,
+    0x59f111f1b605d019,
+    0x923f82a4af194f9b,
+    0xab1c5ed5da6d8118,
+    0xd807aa98a3030242,
+    0x12835b0145706fbe,
+    0x243185be4ee4b28c,
+    0x550c7dc3d5ffb4e2,
+    0x72be5d74f27b896f,
+    0x80deb1fe3b1696b1,
+    0x9bdc06a725c71235,
+    0xc19bf174cf692694,
+    0xe49b69c19ef14ad2,
+    0xefbe4786384f25e3,
+    0x0fc19dc68b8cd5b5,
+    0x240ca1cc77ac9c65,
+    0x2de92c6f592b0275,
+    0x4a7484aa6ea6e483,
+    0x5cb0a9dcbd41fbd4,
+    0x76f988da831153b5,
+    0x983e5152ee66dfab,
+    0xa831c66d2db43210,
+    0xb00327c898fb213f,
+    0xbf597fc7beef0ee4,
+    0xc6e00bf33da88fc2,
+    0xd5a79147930aa725,
+    0x06ca6351e003826f,
+    0x142929670a0e6e70,
+    0x27b70a8546d22ffc,
+    0x2e1b21385c26c926,
+    0x4d2c6dfc5ac42aed,
+    0x53380d139d95b3df,
+    0x650a73548baf63de,
+    0x766a0abb3c77b2a8,
+    0x81c2c92e47edaee6,
+    0x92722c851482353b,
+    0xa2bfe8a14cf10364,
+    0xa81a664bbc423001,
+    0xc24b8b70d0f89791,
+    0xc76c51a30654be30,
+    0xd192e819d6ef5218,
+    0xd69906245565a910,
+    0xf40e35855771202a,
+    0x106aa07032bbd1b8,
+    0x19a4c116b8d2d0c8,
+    0x1e376c085141ab53,
+    0x2748774cdf8eeb99,
+    0x34b0bcb5e19b48a8,
+    0x391c0cb3c5c95a63,
+    0x4ed8aa4ae3418acb,
+    0x5b9cca4f7763e373,
+    0x682e6ff3d6b2b8a3,
+    0x748f82ee5defb2fc,
+    0x78a5636f43172f60,
+    0x84c87814a1f0ab72,
+    0x8cc702081a6439ec,
+    0x90befffa23631e28,
+    0xa4506cebde82bde9,
+    0xbef9a3f7b2c67915,
+    0xc67178f2e372532b,
+    0xca273eceea26619c,
+    0xd186b8c721c0c207,
+    0xeada7dd6cde0eb1e,
+    0xf57d4f7fee6ed178,
+    0x06f067aa72176fba,
+    0x0a637dc5a2c898a6,
+    0x113f9804bef90dae,
+    0x1b710b35131c471b,
+    0x28db77f523047d84,
+    0x32caab7b40c72493,
+    0x3c9ebe0a15c9bebc,
+    0x431d67c49c100d4c,
+    0x4cc5d4becb3e42b6,
+    0x597f299cfc657e2a,
+    0x5fcb6fab3ad6faec,
+    0x6c44198c4a475817,
+];
+
+// SHA-512 initialization vector
+pub(super) const IV_512: [u64; STATE_512] = [
+    0x6a09_e667_f3bc_c908,
+    0xbb67_ae85_84ca_a73b,
+    0x3c6e_f372_fe94_f82b,
+    0xa54f_f53a_5f1d_36f1,
+    0x510e_527f_ade6_82d1,
+    0x9b05_688c_2b3e_6c1f,
+    0x1f83_d9ab_fb41_bd6b,
+    0x5be0_cd19_137e_2179,
+];
+
+// SHA-384 initialization vector
+pub(super) const IV_384: [u64; STATE_512] = [
+    0xcbbb_9d5d_c105_9ed8,
+    0x629a_292a_367c_d507,
+    0x9159_015a_3070_dd17,
+    0x152f_ecd8_f70e_5939,
+    0x6733_2667_ffc0_0b31,
+    0x8eb4_4a87_6858_1511,
+    0xdb0c_2e0d_64f9_8fa7,
+    0x47b5_481d_befa_4fa4,
+];
+
+// SHA-512/256 initialization vector, generated as specified in FIPS 180-4, section 5.3.6
+pub(super) const IV_512_256: [u64; STATE_512] = [
+    0x2231_2194_fc2b_f72c,
+    0x9f55_5fa3_c84c_64c2,
+    0x2393_b86b_6f53_b151,
+    0x9638_7719_5940_eabd,
+    0x9628_3ee2_a88e_ffe3,
+    0xbe5e_1e25_5386_3992,
+    0x2b01_99fc_2c85_b8aa,
+    0x0eb7_2ddc_81c5_2ca2,
+];
+
+/// A rotation or shift of a 64-bit word.
+#[derive(Clone, Copy, Debug)]
+pub(super) enum Shift {
+    Rotr(u8),
+    Shr(u8),
+}
+
+impl Shift {
+    fn apply(&self, word: u64) -> u64 {
+        match self {
+            Shift::Rotr(n) => word.rotate_right(*n as u32),
+            Shift::Shr(n) => word >> *n,
+        }
+    }
+
+    // Returns the position that the piece of `len` bits at `offset` is moved to, or
+    // `None` if it is shifted out. The piece must not straddle the rotation or shift.
+    fn position(&self, offset: u8, len: u8) -> Option<u8> {
+        let n = match self {
+            Shift::Rotr(n) | Shift::Shr(n) => *n,
+        };
+        assert!(offset + len <= n || offset >= n);
+        match self {
+            Shift::Rotr(n) => Some((offset + 64 - n) % 64),
+            Shift::Shr(n) => offset.checked_sub(*n),
+        }
+    }
+}
+
+/// The XOR of three rotations or shifts of a 64-bit word, as used in the SHA-512 Σ and σ
+/// functions. The word is decomposed into pieces of at most 16 bits, given by their
+/// lengths from the little end, such that no piece straddles a rotation or shift.
+#[derive(Clone, Copy, Debug)]
+pub(super) struct Sigma {
+    pub pieces: &'static [u8],
+    pub shifts: [Shift; 3],
+}
+
+// Σ_0 on the A word: (28, 6, 5, 25)-bit pieces
+pub(super) const UPPER_SIGMA_0: Sigma = Sigma {
+    pieces: &[14, 14, 6, 5, 13, 12],
+    shifts: [Shift::Rotr(28), Shift::Rotr(34), Shift::Rotr(39)],
+};
+
+// Σ_1 on the E word: (14, 4, 23, 23)-bit pieces
+pub(super) const UPPER_SIGMA_1: Sigma = Sigma {
+    pieces: &[14, 4, 12, 11, 12, 11],
+    shifts: [Shift::Rotr(14), Shift::Rotr(18), Shift::Rotr(41)],
+};
+
+// σ_0 on a message schedule word: (1, 6, 1, 56)-bit pieces
+pub(super) const LOWER_SIGMA_0: Sigma = Sigma {
+    pieces: &[1, 6, 1, 14, 14, 14, 14],
+    shifts: [Shift::Rotr(1), Shift::Rotr(8), Shift::Shr(7)],
+};
+
+// σ_1 on a message schedule word: (6, 13, 42, 3)-bit pieces
+pub(super) const LOWER_SIGMA_1: Sigma = Sigma {
+    pieces: &[6, 13, 14, 14, 14, 3],
+    shifts: [Shift::Rotr(19), Shift::Rotr(61), Shift::Shr(6)],
+};
+
+impl Sigma {
+    // Returns the three shifted words, whose XOR is the output of the function.
+    pub fn shifted(&self, word: u64) -> [u64; 3] {
+        [
+            self.shifts[0].apply(word),
+            self.shifts[1].apply(word),
+            self.shifts[2].apply(word),
+        ]
+    }
+
+    // Returns the offset of each piece, and the coefficient of its spread form in the sum
+    // of the spread forms of the three shifted words.
+    pub fn piece_coeffs(&self) -> Vec<(u8, u128)> {
+        assert_eq!(self.pieces.iter().sum::<u8>(), 64);
+        let mut offset = 0;
+        self.pieces
+            .iter()
+            .map(|&len| {
+                assert!(len <= 16);
+                let coeff = self
+                    .shifts
+                    .iter()
+                    .filter_map(|shift| shift.position(offset, len))
+                    .map(|position| 1u128 << (2 * position))
+                    .sum();
+                let piece = (offset, coeff);
+                offset += len;
+                piece
+            })
+            .collect()
+    }
+}
+
+// Splits a 64-bit word into 16-bit limbs, little-endian
+pub fn limbs_u64(word: u64) -> [u16; LIMBS] {
+    [
+        word as u16,
+        (word >> 16) as u16,
+        (word >> 32) as u16,
+        (word >> 48) as u16,
+    ]
+}
+
+// Returns the even and odd bits of the sum of the spread forms of up to three words,
+// i.e. their XOR and their majority.
+pub fn even_and_odd_bits_u64(words: &[u64]) -> (u64, u64) {
+    assert!(words.len() <= 3);
+    let word = |idx: usize| words.get(idx).copied().unwrap_or(0);
+    let (a, b, c) = (word(0), word(1), word(2));
+    (a ^ b ^ c, (a & b) | (a & c) | (b & c))
+}
+
+// Splits a word into pieces of the given lengths, from the little end
+pub fn chop_pieces(word: u64, pieces: &[u8]) -> Vec<u16> {
+    chop_u64(word, pieces)
+        .into_iter()
+        .map(|piece| piece as u16)
+        .collect()
+}
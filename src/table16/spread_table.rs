@@ -2,21 +2,22 @@ use super::{util::*, CellValue16, CellValue32};
 use halo2::{
     arithmetic::FieldExt,
     circuit::{Chip, Layouter, Region},
-    plonk::{Advice, Column, ConstraintSystem, Error, Fixed},
+    plonk::{Advice, Column, ConstraintSystem, Error, Expression, Fixed, Selector},
     poly::Rotation,
 };
 use std::marker::PhantomData;
 
 /// An input word into a lookup, containing (tag, dense, spread)
 #[derive(Copy, Clone, Debug)]
-pub(super) struct SpreadWord {
+pub struct SpreadWord {
     pub tag: u8,
     pub dense: u16,
     pub spread: u32,
 }
 
 impl SpreadWord {
-    pub(super) fn new(word: u16) -> Self {
+    /// Computes the row of the spread table for the given word.
+    pub fn new(word: u16) -> Self {
         SpreadWord {
             tag: get_tag(word),
             dense: word,
@@ -79,6 +80,10 @@ impl SpreadVar {
             },
         )?;
 
+        if let Some(bytes) = &cols.bytes {
+            bytes.assign(region, row, dense_val)?;
+        }
+
         Ok(SpreadVar {
             tag,
             dense: CellValue16::new(dense_var, dense_val),
@@ -131,6 +136,71 @@ pub(super) struct SpreadInputs {
     pub(super) tag: Column<Advice>,
     pub(super) dense: Column<Advice>,
     pub(super) spread: Column<Advice>,
+    /// The bytes of each input word, if words are looked up byte by byte
+    pub(super) bytes: Option<SpreadByteInputs>,
+}
+
+/// Columns holding the low and high bytes of each input word, along with their spread
+/// forms, when the spread table is over bytes rather than 16-bit words.
+///
+/// | $s_{bytes}$ | $a_0$ | $a_1$ | $a_2$  | $b_0$ | $b_1$ | $b_2$ | $b_3$ |
+/// |-------------|-------|-------|--------|-------|-------|-------|-------|
+/// | 1           | tag   | W     | spread(W) | W^{lo} | spread(W^{lo}) | W^{hi} | spread(W^{hi}) |
+///
+/// Each byte is looked up along with the tag of the word, in the rows of the table for
+/// its half, which only hold the bytes of words that fit in the number of bits given by
+/// the tag.
+#[derive(Clone, Debug)]
+pub(super) struct SpreadByteInputs {
+    /// Recomposition gate for each input word
+    pub(super) s_bytes: Selector,
+    pub(super) lo: Column<Advice>,
+    pub(super) spread_lo: Column<Advice>,
+    pub(super) hi: Column<Advice>,
+    pub(super) spread_hi: Column<Advice>,
+}
+
+impl SpreadByteInputs {
+    fn assign<F: FieldExt>(
+        &self,
+        region: &mut Region<'_, F>,
+        row: usize,
+        dense: Option<u16>,
+    ) -> Result<(), Error> {
+        self.s_bytes.enable(region, row)?;
+
+        let lo = dense.map(|dense| dense & 0xff);
+        let hi = dense.map(|dense| dense >> 8);
+        for (name, column, value) in [
+            ("lo", self.lo, lo.map(|lo| lo as u32)),
+            (
+                "spread_lo",
+                self.spread_lo,
+                lo.map(interleave_u16_with_zeros),
+            ),
+            ("hi", self.hi, hi.map(|hi| hi as u32)),
+            (
+                "spread_hi",
+                self.spread_hi,
+                hi.map(interleave_u16_with_zeros),
+            ),
+        ]
+        .iter()
+        {
+            region.assign_advice(
+                || *name,
+                *column,
+                row,
+                || {
+                    value
+                        .map(|value| F::from_u64(value as u64))
+                        .ok_or(Error::SynthesisError)
+                },
+            )?;
+        }
+
+        Ok(())
+    }
 }
 
 #[derive(Clone, Debug)]
@@ -138,16 +208,76 @@ pub(super) struct SpreadTable {
     pub(super) tag: Column<Fixed>,
     pub(super) dense: Column<Fixed>,
     pub(super) spread: Column<Fixed>,
+    /// Whether a row is for the low or the high byte of a word, if the table is over bytes
+    pub(super) half: Option<Column<Fixed>>,
 }
 
+/// Configuration for a [`SpreadTableChip`].
 #[derive(Clone, Debug)]
-pub(super) struct SpreadTableConfig {
-    pub input: SpreadInputs,
-    pub table: SpreadTable,
+pub struct SpreadTableConfig {
+    pub(super) input: SpreadInputs,
+    pub(super) table: SpreadTable,
+}
+
+impl SpreadTableConfig {
+    /// Adds a lookup of the `(tag, dense, spread)` words held in the given columns of
+    /// another chip into the fixed columns of the table, on the rows where `selector` is
+    /// enabled. The selector must be created with
+    /// [`ConstraintSystem::complex_selector`], as it is queried in a lookup. A row
+    /// `(tag, dense, spread)` is in the table if `dense` fits in the number of bits given
+    /// by `tag` (7, 10, 11, 13, 14 or 16 bits for tags 0 to 5), and `spread` is `dense`
+    /// with a zero interleaved before each bit; the values of a row can be computed with
+    /// [`SpreadWord::new`].
+    ///
+    /// Returns an error if the table is over bytes (see
+    /// [`SpreadTableChip::configure_bytes`]), whose rows are the spread forms of bytes
+    /// rather than of 16-bit words; no lookup is added then.
+    pub fn lookup<F: FieldExt>(
+        &self,
+        meta: &mut ConstraintSystem<F>,
+        selector: Selector,
+        tag: Column<Advice>,
+        dense: Column<Advice>,
+        spread: Column<Advice>,
+    ) -> Result<(), Error> {
+        if self.table.half.is_some() {
+            return Err(Error::SynthesisError);
+        }
+        let table_tag = self.table.tag;
+        let table_dense = self.table.dense;
+        let table_spread = self.table.spread;
+
+        meta.lookup(|meta| {
+            // The table contains the row (0, 0, 0), which the inputs are set to on the
+            // rows where the selector is not set.
+            let selector = meta.query_selector(selector);
+            let tag_cur = meta.query_advice(tag, Rotation::cur());
+            let dense_cur = meta.query_advice(dense, Rotation::cur());
+            let spread_cur = meta.query_advice(spread, Rotation::cur());
+            let table_tag_cur = meta.query_fixed(table_tag, Rotation::cur());
+            let table_dense_cur = meta.query_fixed(table_dense, Rotation::cur());
+            let table_spread_cur = meta.query_fixed(table_spread, Rotation::cur());
+
+            vec![
+                (selector.clone() * tag_cur, table_tag_cur),
+                (selector.clone() * dense_cur, table_dense_cur),
+                (selector * spread_cur, table_spread_cur),
+            ]
+        });
+
+        Ok(())
+    }
 }
 
+/// A chip that loads a table of the spread forms of 16-bit words, and looks up every row
+/// of its three input columns in it.
+///
+/// The table can be shared with other chips: they can issue lookups against it from
+/// their own columns with [`SpreadTableConfig::lookup`], or assign words to look up in
+/// the input columns of the chip with [`SpreadTableChip::assign_word`]. The table is
+/// loaded once, by [`SpreadTableChip::load`] or by the chip that configured it.
 #[derive(Clone, Debug)]
-pub(super) struct SpreadTableChip<F: FieldExt> {
+pub struct SpreadTableChip<F: FieldExt> {
     config: SpreadTableConfig,
     _marker: PhantomData<F>,
 }
@@ -166,6 +296,13 @@ impl<F: FieldExt> Chip<F> for SpreadTableChip<F> {
 }
 
 impl<F: FieldExt> SpreadTableChip<F> {
+    pub fn construct(config: <Self as Chip<F>>::Config) -> Self {
+        Self {
+            config,
+            _marker: PhantomData,
+        }
+    }
+
     pub fn configure(
         meta: &mut ConstraintSystem<F>,
         input_tag: Column<Advice>,
@@ -196,11 +333,89 @@ impl<F: FieldExt> SpreadTableChip<F> {
                 tag: input_tag,
                 dense: input_dense,
                 spread: input_spread,
+                bytes: None,
+            },
+            table: SpreadTable {
+                tag: table_tag,
+                dense: table_dense,
+                spread: table_spread,
+                half: None,
+            },
+        }
+    }
+
+    /// Configures a spread table over bytes, in which each input word is looked up as its
+    /// low and high bytes, held in the given columns. The table has fewer than $2^{11}$
+    /// rows.
+    pub fn configure_bytes(
+        meta: &mut ConstraintSystem<F>,
+        input_tag: Column<Advice>,
+        input_dense: Column<Advice>,
+        input_spread: Column<Advice>,
+        bytes: [Column<Advice>; 4],
+    ) -> <Self as Chip<F>>::Config {
+        let s_bytes = meta.selector();
+        let table_half = meta.fixed_column();
+        let table_tag = meta.fixed_column();
+        let table_dense = meta.fixed_column();
+        let table_spread = meta.fixed_column();
+
+        let [lo, spread_lo, hi, spread_hi] = bytes;
+
+        meta.create_gate("s_bytes", |meta| {
+            let s_bytes = meta.query_selector(s_bytes);
+            let dense = meta.query_advice(input_dense, Rotation::cur());
+            let spread = meta.query_advice(input_spread, Rotation::cur());
+            let lo = meta.query_advice(lo, Rotation::cur());
+            let spread_lo = meta.query_advice(spread_lo, Rotation::cur());
+            let hi = meta.query_advice(hi, Rotation::cur());
+            let spread_hi = meta.query_advice(spread_hi, Rotation::cur());
+
+            let dense_check = lo + hi * F::from_u64(1 << 8) - dense;
+            let spread_check = spread_lo + spread_hi * F::from_u64(1 << 16) - spread;
+
+            vec![("dense_check", dense_check), ("spread_check", spread_check)]
+                .into_iter()
+                .map(move |(name, poly)| (name, s_bytes.clone() * poly))
+        });
+
+        for (half, byte, spread_byte) in [(0, lo, spread_lo), (1, hi, spread_hi)].iter() {
+            meta.lookup(|meta| {
+                let tag_cur = meta.query_advice(input_tag, Rotation::cur());
+                let byte_cur = meta.query_advice(*byte, Rotation::cur());
+                let spread_byte_cur = meta.query_advice(*spread_byte, Rotation::cur());
+                let table_half_cur = meta.query_fixed(table_half, Rotation::cur());
+                let table_tag_cur = meta.query_fixed(table_tag, Rotation::cur());
+                let table_dense_cur = meta.query_fixed(table_dense, Rotation::cur());
+                let table_spread_cur = meta.query_fixed(table_spread, Rotation::cur());
+
+                vec![
+                    (Expression::Constant(F::from_u64(*half)), table_half_cur),
+                    (tag_cur, table_tag_cur),
+                    (byte_cur, table_dense_cur),
+                    (spread_byte_cur, table_spread_cur),
+                ]
+            });
+        }
+
+        SpreadTableConfig {
+            input: SpreadInputs {
+                tag: input_tag,
+                dense: input_dense,
+                spread: input_spread,
+                bytes: Some(SpreadByteInputs {
+                    s_bytes,
+                    lo,
+                    spread_lo,
+                    hi,
+                    spread_hi,
+                }),
             },
             table: SpreadTable {
                 tag: table_tag,
                 dense: table_dense,
                 spread: table_spread,
+                half: Some(table_half),
             },
         }
     }
@@ -209,6 +424,10 @@ impl<F: FieldExt> SpreadTableChip<F> {
         config: SpreadTableConfig,
         layouter: &mut impl Layouter<F>,
     ) -> Result<<Self as Chip<F>>::Loaded, Error> {
+        if let Some(half) = config.table.half {
+            return Self::load_bytes(&config.table, half, layouter);
+        }
+
         layouter.assign_region(
             || "spread table",
             |mut gate| {
@@ -246,9 +465,71 @@ impl<F: FieldExt> SpreadTableChip<F> {
             },
         )
     }
+
+    /// Assigns the given word to the input columns of the chip at the given row of the
+    /// region, along with its tag and spread form, so that they are looked up in the
+    /// table. Returns the dense and spread cells, which can be copied into other cells if
+    /// equality is enabled on the input columns.
+    pub fn assign_word(
+        &self,
+        region: &mut Region<'_, F>,
+        row: usize,
+        word: Option<u16>,
+    ) -> Result<(CellValue16, CellValue32), Error> {
+        let var =
+            SpreadVar::with_lookup(region, &self.config.input, row, SpreadWord::opt_new(word))?;
+        Ok((var.dense, var.spread))
+    }
+
+    fn load_bytes(
+        table: &SpreadTable,
+        half: Column<Fixed>,
+        layouter: &mut impl Layouter<F>,
+    ) -> Result<<Self as Chip<F>>::Loaded, Error> {
+        layouter.assign_region(
+            || "spread table",
+            |mut gate| {
+                for (index, row) in SpreadTableConfig::generate_bytes::<F>().enumerate() {
+                    let (half_val, tag, dense, spread) = row;
+                    gate.assign_fixed(|| "half", half, index, || Ok(half_val))?;
+                    gate.assign_fixed(|| "tag", table.tag, index, || Ok(tag))?;
+                    gate.assign_fixed(|| "dense", table.dense, index, || Ok(dense))?;
+                    gate.assign_fixed(|| "spread", table.spread, index, || Ok(spread))?;
+                }
+                Ok(())
+            },
+        )
+    }
 }
 
 impl SpreadTableConfig {
+    /// Generates the rows `(half, tag, byte, spread)` of a spread table over bytes. For
+    /// each tag, the rows of the low half hold the bytes that can be the low byte of a
+    /// word with that tag or a smaller one, and similarly for the high half. The first row
+    /// is all zeros.
+    fn generate_bytes<F: FieldExt>() -> impl Iterator<Item = (F, F, F, F)> {
+        // The exclusive upper bound on words with each tag
+        const BOUNDS: [usize; 6] = [BITS_7, BITS_10, BITS_11, BITS_13, BITS_14, 1 << 16];
+
+        (0..2).flat_map(|half| {
+            BOUNDS.iter().enumerate().flat_map(move |(tag, bound)| {
+                let end = if half == 0 {
+                    std::cmp::min(*bound, 1 << 8)
+                } else {
+                    std::cmp::max(*bound >> 8, 1)
+                };
+                (0..end).map(move |byte| {
+                    (
+                        F::from_u64(half),
+                        F::from_u64(tag as u64),
+                        F::from_u64(byte as u64),
+                        F::from_u64(interleave_u16_with_zeros(byte as u16) as u64),
+                    )
+                })
+            })
+        })
+    }
+
     fn generate<F: FieldExt>() -> impl Iterator<Item = (F, F, F)> {
         (1..=(1 << 16)).scan(
             (F::zero(), F::zero(), F::zero()),
@@ -283,7 +564,7 @@ impl SpreadTableConfig {
 
 #[cfg(test)]
 mod tests {
-    use super::{SpreadTableChip, SpreadTableConfig};
+    use super::{SpreadTableChip, SpreadTableConfig, SpreadVar, SpreadWord};
     use rand::Rng;
 
     use crate::table16::util::{get_tag, interleave_u16_with_zeros};
@@ -292,7 +573,7 @@ mod tests {
         circuit::{Layouter, SimpleFloorPlanner},
         dev::MockProver,
         pasta::Fp,
-        plonk::{Advice, Circuit, Column, ConstraintSystem, Error},
+        plonk::{Advice, Circuit, Column, ConstraintSystem, Error, Selector},
     };
 
     #[test]
@@ -434,4 +715,269 @@ mod tests {
         };
         assert_eq!(prover.verify(), Ok(()));
     }
+
+    #[test]
+    fn lookup_table_bytes() {
+        struct MyCircuit {
+            words: Vec<SpreadWord>,
+        }
+
+        impl<F: FieldExt> Circuit<F> for MyCircuit {
+            type Config = SpreadTableConfig;
+            type FloorPlanner = SimpleFloorPlanner;
+
+            fn without_witnesses(&self) -> Self {
+                MyCircuit {
+                    words: self.words.clone(),
+                }
+            }
+
+            fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+                let input_tag = meta.advice_column();
+                let input_dense = meta.advice_column();
+                let input_spread = meta.advice_column();
+                let bytes = [
+                    meta.advice_column(),
+                    meta.advice_column(),
+                    meta.advice_column(),
+                    meta.advice_column(),
+                ];
+
+                SpreadTableChip::configure_bytes(meta, input_tag, input_dense, input_spread, bytes)
+            }
+
+            fn synthesize(
+                &self,
+                config: Self::Config,
+                mut layouter: impl Layouter<F>,
+            ) -> Result<(), Error> {
+                SpreadTableChip::load(config.clone(), &mut layouter)?;
+
+                layouter.assign_region(
+                    || "spread_test",
+                    |mut region| {
+                        for (row, word) in self.words.iter().enumerate() {
+                            SpreadVar::with_lookup(&mut region, &config.input, row, Some(*word))?;
+                        }
+                        Ok(())
+                    },
+                )
+            }
+        }
+
+        // Test the tag boundaries, and random lookup values.
+        let mut words: Vec<_> = [
+            0,
+            1,
+            0b1111111,
+            0b10000000,
+            0b1111111111,
+            0b10000000000,
+            0b11111111111,
+            0b100000000000,
+            0b1111111111111,
+            0b10000000000000,
+            0b11111111111111,
+            0b100000000000000,
+            0xffff,
+        ]
+        .iter()
+        .map(|word| SpreadWord::new(*word))
+        .collect();
+        let mut rng = rand::thread_rng();
+        words.extend((0..10).map(|_| SpreadWord::new(rng.gen())));
+
+        let circuit = MyCircuit {
+            words: words.clone(),
+        };
+        let prover = match MockProver::<Fp>::run(11, &circuit, vec![]) {
+            Ok(prover) => prover,
+            Err(e) => panic!("{:?}", e),
+        };
+        assert_eq!(prover.verify(), Ok(()));
+
+        // A tag above that of the word is accepted, as it only bounds the word.
+        let mut word = SpreadWord::new(0b1111111);
+        word.tag = 2;
+        let circuit = MyCircuit { words: vec![word] };
+        let prover = match MockProver::<Fp>::run(11, &circuit, vec![]) {
+            Ok(prover) => prover,
+            Err(e) => panic!("{:?}", e),
+        };
+        assert_eq!(prover.verify(), Ok(()));
+
+        // A tag below that of the word, or a wrong spread form, is rejected.
+        for (tag, dense, spread) in [
+            (0, 0b10000000, interleave_u16_with_zeros(0b10000000)),
+            (
+                3,
+                0b10000000000000,
+                interleave_u16_with_zeros(0b10000000000000),
+            ),
+            (5, 0b101, 0b10001 << 1),
+        ]
+        .iter()
+        {
+            let word = SpreadWord {
+                tag: *tag,
+                dense: *dense,
+                spread: *spread,
+            };
+            let circuit = MyCircuit { words: vec![word] };
+            let prover = match MockProver::<Fp>::run(11, &circuit, vec![]) {
+                Ok(prover) => prover,
+                Err(e) => panic!("{:?}", e),
+            };
+            assert!(prover.verify().is_err());
+        }
+    }
+
+    #[test]
+    fn external_lookup() {
+        struct MyCircuit {
+            words: Vec<SpreadWord>,
+        }
+
+        impl<F: FieldExt> Circuit<F> for MyCircuit {
+            type Config = (SpreadTableConfig, Selector, [Column<Advice>; 3]);
+            type FloorPlanner = SimpleFloorPlanner;
+
+            fn without_witnesses(&self) -> Self {
+                MyCircuit {
+                    words: self.words.clone(),
+                }
+            }
+
+            fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+                let input_tag = meta.advice_column();
+                let input_dense = meta.advice_column();
+                let input_spread = meta.advice_column();
+                meta.enable_equality(input_dense.into());
+                let table = SpreadTableChip::configure(meta, input_tag, input_dense, input_spread);
+
+                // Columns of another chip, looking up words in the same table.
+                let s_word = meta.complex_selector();
+                let words = [
+                    meta.advice_column(),
+                    meta.advice_column(),
+                    meta.advice_column(),
+                ];
+                meta.enable_equality(words[1].into());
+                table
+                    .lookup(meta, s_word, words[0], words[1], words[2])
+                    .expect("the table is over 16-bit words");
+
+                (table, s_word, words)
+            }
+
+            fn synthesize(
+                &self,
+                config: Self::Config,
+                mut layouter: impl Layouter<F>,
+            ) -> Result<(), Error> {
+                let (table, s_word, words) = config;
+                SpreadTableChip::load(table.clone(), &mut layouter)?;
+                let chip = SpreadTableChip::construct(table);
+
+                layouter.assign_region(
+                    || "external words",
+                    |mut region| {
+                        for (row, word) in self.words.iter().enumerate() {
+                            s_word.enable(&mut region, row)?;
+                            let mut cells = [word.tag as u32, word.dense as u32, word.spread]
+                                .iter()
+                                .zip(words.iter())
+                                .map(|(value, column)| {
+                                    region.assign_advice(
+                                        || "word",
+                                        *column,
+                                        row,
+                                        || Ok(F::from_u64(*value as u64)),
+                                    )
+                                })
+                                .collect::<Result<Vec<_>, Error>>()?;
+
+                            // Copy the dense word into the input columns of the chip.
+                            let (dense, _) =
+                                chip.assign_word(&mut region, row, Some(word.dense))?;
+                            region.constrain_equal(cells.remove(1), dense.var)?;
+                        }
+
+                        // Rows where the selector is not set are not looked up.
+                        let row = self.words.len();
+                        for column in words.iter() {
+                            region.assign_advice(|| "unused", *column, row, || Ok(F::one()))?;
+                        }
+
+                        Ok(())
+                    },
+                )
+            }
+        }
+
+        let mut rng = rand::thread_rng();
+        let words: Vec<_> = [0, 0b1111111, 0b10000000000, 0xffff, rng.gen()]
+            .iter()
+            .map(|word| SpreadWord::new(*word))
+            .collect();
+        let circuit = MyCircuit {
+            words: words.clone(),
+        };
+        let prover = match MockProver::<Fp>::run(17, &circuit, vec![]) {
+            Ok(prover) => prover,
+            Err(e) => panic!("{:?}", e),
+        };
+        assert_eq!(prover.verify(), Ok(()));
+
+        // A tag below that of the word, or a wrong spread form, is rejected.
+        for (tag, dense, spread) in [
+            (0, 0b10000000, interleave_u16_with_zeros(0b10000000)),
+            (5, 0b101, 0b10001 << 1),
+        ]
+        .iter()
+        {
+            let word = SpreadWord {
+                tag: *tag,
+                dense: *dense,
+                spread: *spread,
+            };
+            let circuit = MyCircuit { words: vec![word] };
+            let prover = match MockProver::<Fp>::run(17, &circuit, vec![]) {
+                Ok(prover) => prover,
+                Err(e) => panic!("{:?}", e),
+            };
+            assert!(prover.verify().is_err());
+        }
+    }
+
+    #[test]
+    fn external_lookup_bytes() {
+        let mut meta = ConstraintSystem::<Fp>::default();
+        let input_tag = meta.advice_column();
+        let input_dense = meta.advice_column();
+        let input_spread = meta.advice_column();
+        let bytes = [
+            meta.advice_column(),
+            meta.advice_column(),
+            meta.advice_column(),
+            meta.advice_column(),
+        ];
+        let table = SpreadTableChip::configure_bytes(
+            &mut meta,
+            input_tag,
+            input_dense,
+            input_spread,
+            bytes,
+        );
+
+        let s_word = meta.complex_selector();
+        let words = [
+            meta.advice_column(),
+            meta.advice_column(),
+            meta.advice_column(),
+        ];
+        assert!(table
+            .lookup(&mut meta, s_word, words[0], words[1], words[2])
+            .is_err());
+    }
 }
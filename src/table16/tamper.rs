@@ -0,0 +1,105 @@
+//! Helpers for tests that check the gates reject a witness with a single tampered cell.
+
+use super::SpreadInputs;
+use halo2::{
+    arithmetic::FieldExt,
+    circuit::Region,
+    dev::MockProver,
+    pasta::Fp,
+    plonk::{Advice, Column, Error},
+};
+
+/// Returns the advice columns `[a_0, ..., a_9]`, named as in the configuration of the
+/// compression and message schedule gates.
+pub(super) fn advice_columns(
+    lookup: &SpreadInputs,
+    message_schedule: Column<Advice>,
+    extras: [Column<Advice>; 6],
+) -> [Column<Advice>; 10] {
+    [
+        lookup.tag,
+        lookup.dense,
+        lookup.spread,
+        extras[0],
+        extras[1],
+        message_schedule,
+        extras[2],
+        extras[3],
+        extras[4],
+        extras[5],
+    ]
+}
+
+/// Overwrites the cell at `row` of `column` with -1, which no honest assignment in
+/// Table16 produces.
+pub(super) fn tamper<F: FieldExt>(
+    region: &mut Region<'_, F>,
+    column: Column<Advice>,
+    row: usize,
+) -> Result<(), Error> {
+    overwrite(region, column, row, -F::one())
+}
+
+/// Overwrites the cell at `row` of `column` with `value`, for tampering with several
+/// cells consistently.
+pub(super) fn overwrite<F: FieldExt>(
+    region: &mut Region<'_, F>,
+    column: Column<Advice>,
+    row: usize,
+    value: F,
+) -> Result<(), Error> {
+    region.assign_advice(|| "tampered", column, row, || Ok(value))?;
+    Ok(())
+}
+
+/// Asserts that `prover` reports a constraint of the gate named `gate` as not satisfied.
+/// A tampered cell may break lookups, copy constraints and other gates as well, so
+/// further failures are allowed.
+pub(super) fn assert_gate_fails(prover: &MockProver<Fp>, gate: &str) {
+    let failures = match prover.verify() {
+        Ok(()) => panic!("gate {} was satisfied", gate),
+        Err(failures) => failures,
+    };
+    // Gate names are quoted in the debug output of a failure.
+    let name = format!("{:?}", gate);
+    assert!(
+        failures
+            .iter()
+            .any(|failure| format!("{:?}", failure).contains(&name)),
+        "gate {} not among the failures {:?}",
+        gate,
+        failures
+    );
+}
+
+/// Asserts that `prover` reports a copy constraint as not satisfied. Further failures
+/// are allowed, as for [`assert_gate_fails`].
+pub(super) fn assert_copy_fails(prover: &MockProver<Fp>) {
+    let failures = match prover.verify() {
+        Ok(()) => panic!("all copy constraints were satisfied"),
+        Err(failures) => failures,
+    };
+    assert!(
+        failures
+            .iter()
+            .any(|failure| format!("{:?}", failure).starts_with("Permutation")),
+        "no copy constraint among the failures {:?}",
+        failures
+    );
+}
+
+/// Asserts that `prover` reports only lookups as not satisfied, so that no gate or copy
+/// constraint would have caught the tampered cells.
+pub(super) fn assert_only_lookups_fail(prover: &MockProver<Fp>) {
+    let failures = match prover.verify() {
+        Ok(()) => panic!("all lookups were satisfied"),
+        Err(failures) => failures,
+    };
+    assert!(
+        failures
+            .iter()
+            .all(|failure| format!("{:?}", failure).starts_with("Lookup")),
+        "failures other than lookups {:?}",
+        failures
+    );
+}
@@ -0,0 +1,341 @@
+use super::{
+    super::DIGEST_SIZE, Byte, DigestWord, SpreadInputs, SpreadVar, SpreadWord, Table16Assignment,
+};
+
+use halo2::{
+    arithmetic::FieldExt,
+    circuit::{Cell, Layouter, Region},
+    plonk::{Advice, Column, ConstraintSystem, Error, Expression, Selector},
+    poly::Rotation,
+};
+
+// Bytes of the hash and of the target
+const HASH_BYTES: usize = DIGEST_SIZE * 4;
+// Rows taken by each byte
+const BYTE_ROWS: usize = 4;
+// Bytes past the hash, in which the position flags of the target are fixed to zero
+const PADDING_BYTES: usize = 2;
+
+/// Configuration for comparing a hash against a target in Bitcoin's compact `nBits`
+/// encoding, as for a block header's proof of work.
+///
+/// The hash is read as a little-endian integer: byte `p` of the digest has weight
+/// $2^{8p}$. The compact target has a mantissa of three bytes $n_0, n_1, n_2$ and an
+/// exponent byte $e$, and encodes the integer whose bytes $e - 3, e - 2, e - 1$ are the
+/// mantissa and whose other bytes are zero. Exponents from 3 to 32 are supported.
+///
+/// Each byte `p` takes four rows, in which the hash byte $h_p$ and the difference byte
+/// $d_p$ are range-checked by also looking up $2^8 h_p$ and $2^8 d_p$. Subtracting the
+/// hash from the target byte by byte, $t_p - h_p - b_p + 2^8 b_{p + 1} = d_p$ with borrows
+/// $b_0 = b_{32} = 0$, shows that the hash does not exceed the target. The position of the
+/// mantissa is given by flags $u_p = (p = e - 1)$, with running sums $c_p$ of the flags and
+/// $z_p$ of the unset $c_{p + 1}$ such that $c_{32} = 1$ and $z_{32} = e - 1$.
+///
+/// | Row    | $a_1$      | $a_3$ | $a_4$ | $a_5$ | $a_6$ | $a_7$ | $a_8$ |
+/// |--------|------------|-------|-------|-------|-------|-------|-------|
+/// | 4p     | $h_p$      | $t_p$ | $b_p$ | $u_p$ | $c_p$ | $z_p$ | $n_0$ |
+/// | 4p + 1 | $2^8 h_p$  | $W_j$ |       |       |       |       | $n_1$ |
+/// | 4p + 2 | $d_p$      |       |       |       |       |       | $n_2$ |
+/// | 4p + 3 | $2^8 d_p$  |       |       |       |       |       | $e$   |
+///
+/// Digest word $W_j$ sits in the rows of its first byte, $p = 4j$.
+#[derive(Clone, Debug)]
+pub(super) struct TargetConfig {
+    lookup: SpreadInputs,
+    message_schedule: Column<Advice>,
+    extras: [Column<Advice>; 6],
+
+    /// Comparison gate for each byte
+    s_target: Selector,
+    /// Decomposition gate for each digest word
+    s_target_word: Selector,
+    /// Exponent gate after the last byte
+    s_target_end: Selector,
+}
+
+impl<F: FieldExt> Table16Assignment<F> for TargetConfig {}
+
+impl TargetConfig {
+    pub(super) fn configure<F: FieldExt>(
+        meta: &mut ConstraintSystem<F>,
+        lookup: SpreadInputs,
+        message_schedule: Column<Advice>,
+        extras: [Column<Advice>; 6],
+    ) -> Self {
+        let s_target = meta.selector();
+        let s_target_word = meta.selector();
+        let s_target_end = meta.selector();
+
+        // Rename these here for ease of matching the gates to the layout.
+        let a_1 = lookup.dense;
+        let a_3 = extras[0];
+        let a_4 = extras[1];
+        let a_5 = message_schedule;
+        let a_6 = extras[2];
+        let a_7 = extras[3];
+        let a_8 = extras[4];
+
+        let next_byte = Rotation(BYTE_ROWS as i32);
+
+        meta.create_gate("s_target", |meta| {
+            let s_target = meta.query_selector(s_target);
+            let h = meta.query_advice(a_1, Rotation::cur());
+            let shifted_h = meta.query_advice(a_1, Rotation(1));
+            let d = meta.query_advice(a_1, Rotation(2));
+            let shifted_d = meta.query_advice(a_1, Rotation(3));
+            let t = meta.query_advice(a_3, Rotation::cur());
+            let borrow = meta.query_advice(a_4, Rotation::cur());
+            let borrow_next = meta.query_advice(a_4, next_byte);
+            let u = meta.query_advice(a_5, Rotation::cur());
+            let u_next = meta.query_advice(a_5, next_byte);
+            let u_next_next = meta.query_advice(a_5, Rotation(2 * BYTE_ROWS as i32));
+            let count = meta.query_advice(a_6, Rotation::cur());
+            let count_next = meta.query_advice(a_6, next_byte);
+            let z = meta.query_advice(a_7, Rotation::cur());
+            let z_next = meta.query_advice(a_7, next_byte);
+            let n = (0..BYTE_ROWS)
+                .map(|idx| meta.query_advice(a_8, Rotation(idx as i32)))
+                .collect::<Vec<_>>();
+            let n_next = (0..BYTE_ROWS)
+                .map(|idx| meta.query_advice(a_8, Rotation((BYTE_ROWS + idx) as i32)))
+                .collect::<Vec<_>>();
+
+            let one = Expression::Constant(F::one());
+            let shift_check =
+                |byte: Expression<F>, shifted: Expression<F>| byte * F::from_u64(1 << 8) - shifted;
+            let bool_check = |bit: Expression<F>| bit.clone() * (one.clone() - bit);
+
+            // The byte of the target at this position is the mantissa byte, if any, that
+            // the flags place here.
+            let target_check =
+                u.clone() * n[2].clone() + u_next * n[1].clone() + u_next_next * n[0].clone()
+                    - t.clone();
+            let sub_check =
+                t - h.clone() - borrow.clone() + borrow_next * F::from_u64(1 << 8) - d.clone();
+
+            let mut checks = vec![
+                ("range_check_h", shift_check(h, shifted_h)),
+                ("range_check_d", shift_check(d, shifted_d)),
+                ("target_check", target_check),
+                ("sub_check", sub_check),
+                ("borrow_check", bool_check(borrow)),
+                ("u_check", bool_check(u.clone())),
+                ("count_check", count + u - count_next.clone()),
+                ("z_check", z + one - count_next - z_next),
+            ];
+            // The compact target is the same in every byte.
+            for (n, n_next) in n.into_iter().zip(n_next) {
+                checks.push(("n_check", n - n_next));
+            }
+
+            checks
+                .into_iter()
+                .map(move |(name, poly)| (name, s_target.clone() * poly))
+        });
+
+        meta.create_gate("s_target_word", |meta| {
+            let s_target_word = meta.query_selector(s_target_word);
+            let word = meta.query_advice(a_3, Rotation::next());
+            let bytes = (0..4)
+                .map(|idx| meta.query_advice(a_1, Rotation((idx * BYTE_ROWS) as i32)))
+                .fold(Expression::Constant(F::zero()), |acc, byte| {
+                    acc * F::from_u64(1 << 8) + byte
+                });
+
+            vec![("word_check", s_target_word * (bytes - word))]
+        });
+
+        meta.create_gate("s_target_end", |meta| {
+            let s_target_end = meta.query_selector(s_target_end);
+            let z = meta.query_advice(a_7, Rotation::cur());
+            let exponent = meta.query_advice(a_8, Rotation(3));
+
+            vec![(
+                "exponent_check",
+                s_target_end * (z + Expression::Constant(F::one()) - exponent),
+            )]
+        });
+
+        TargetConfig {
+            lookup,
+            message_schedule,
+            extras,
+            s_target,
+            s_target_word,
+            s_target_end,
+        }
+    }
+
+    /// Constrains the digest, read as a little-endian integer, to be at most the target
+    /// encoded by the compact `bits`, given little-endian.
+    pub(super) fn check_target<F: FieldExt>(
+        &self,
+        layouter: &mut impl Layouter<F>,
+        hash: &[DigestWord; DIGEST_SIZE],
+        bits: [Byte; 4],
+    ) -> Result<(), Error> {
+        layouter.assign_region(
+            || "check target",
+            |mut region| self.assign_target(&mut region, hash, bits),
+        )
+    }
+
+    fn assign_target<F: FieldExt>(
+        &self,
+        region: &mut Region<'_, F>,
+        hash: &[DigestWord; DIGEST_SIZE],
+        bits: [Byte; 4],
+    ) -> Result<(), Error> {
+        let a_3 = self.extras[0];
+        let a_4 = self.extras[1];
+        let a_5 = self.message_schedule;
+        let a_6 = self.extras[2];
+        let a_7 = self.extras[3];
+        let a_8 = self.extras[4];
+
+        let hash_bytes: Option<Vec<u8>> = hash
+            .iter()
+            .map(|word| word.value().map(u32::to_be_bytes))
+            .collect::<Option<Vec<_>>>()
+            .map(|words| words.concat());
+        let bits_value: Option<Vec<u8>> = bits.iter().map(|byte| byte.value()).collect();
+
+        // The flags place the top byte of the mantissa at byte e - 1.
+        let flags: Option<Vec<bool>> = bits_value.as_ref().map(|bits| {
+            (0..HASH_BYTES + PADDING_BYTES)
+                .map(|p| p + 1 == bits[3] as usize)
+                .collect()
+        });
+        let target: Option<Vec<u8>> =
+            bits_value
+                .as_ref()
+                .zip(flags.as_ref())
+                .map(|(bits, flags)| {
+                    (0..HASH_BYTES)
+                        .map(|p| {
+                            (0..3)
+                                .filter(|idx| flags[p + 2 - idx])
+                                .map(|idx| bits[idx])
+                                .sum()
+                        })
+                        .collect()
+                });
+
+        // The compact target is copied in the first byte, and repeated in the others.
+        for (idx, byte) in bits.iter().enumerate() {
+            match byte {
+                Byte::Assigned(byte) => {
+                    self.assign_and_constrain(region, || "n", a_8, idx, *byte)?;
+                }
+                Byte::Constant(byte) => {
+                    region.assign_advice_from_constant(
+                        || "n",
+                        a_8,
+                        idx,
+                        F::from_u64(*byte as u64),
+                    )?;
+                }
+            }
+        }
+
+        let mut borrow = Some(0u16);
+        let mut count = Some(0u64);
+        let mut z = Some(0u64);
+        for p in 0..HASH_BYTES + PADDING_BYTES {
+            let row = p * BYTE_ROWS;
+
+            if p > 0 {
+                for idx in 0..BYTE_ROWS {
+                    let value = bits_value.as_ref().map(|bits| bits[idx]);
+                    self.assign_u64(region, || "n", a_8, row + idx, value.map(|v| v as u64))?;
+                }
+            }
+
+            let flag = flags.as_ref().map(|flags| flags[p] as u64);
+            let flag_var = self.assign_u64(region, || "u", a_5, row, flag)?;
+            if !(2..HASH_BYTES).contains(&p) {
+                // The exponent is at least 3, and the mantissa fits in the target.
+                region.constrain_constant(flag_var, F::zero())?;
+            }
+
+            // The borrow into the first byte, and out of the last, is zero.
+            let borrow_var = self.assign_u64(region, || "b", a_4, row, borrow.map(u64::from))?;
+            let count_var = self.assign_u64(region, || "c", a_6, row, count)?;
+            let z_var = self.assign_u64(region, || "z", a_7, row, z)?;
+            if p == 0 {
+                region.constrain_constant(borrow_var, F::zero())?;
+                region.constrain_constant(count_var, F::zero())?;
+                region.constrain_constant(z_var, F::zero())?;
+            }
+            if p == HASH_BYTES {
+                self.s_target_end.enable(region, row)?;
+                region.constrain_constant(borrow_var, F::zero())?;
+                region.constrain_constant(count_var, F::one())?;
+            }
+            if p >= HASH_BYTES {
+                continue;
+            }
+
+            self.s_target.enable(region, row)?;
+
+            // Look up the hash byte, copying its word into the first byte of each word
+            let h = hash_bytes.as_ref().map(|bytes| bytes[p]);
+            self.assign_byte(region, row, h)?;
+            if p % 4 == 0 {
+                self.s_target_word.enable(region, row)?;
+                self.assign_and_constrain(region, || "W", a_3, row + 1, hash[p / 4])?;
+            }
+
+            // Subtract the hash byte from the target byte
+            let t = target.as_ref().map(|target| target[p]);
+            self.assign_u64(region, || "t", a_3, row, t.map(u64::from))?;
+            let diff = t
+                .zip(h)
+                .zip(borrow)
+                .map(|((t, h), borrow)| t as i32 - h as i32 - borrow as i32);
+            let d = diff.map(|diff| diff.rem_euclid(1 << 8) as u8);
+            self.assign_byte(region, row + 2, d)?;
+
+            borrow = diff.map(|diff| (diff < 0) as u16);
+            count = count.zip(flag).map(|(count, flag)| count + flag);
+            z = z.zip(count).map(|(z, count)| z + 1 - count);
+        }
+
+        Ok(())
+    }
+
+    // Assign a byte, looking up it and 2^8 times it at the given row and the next.
+    fn assign_byte<F: FieldExt>(
+        &self,
+        region: &mut Region<'_, F>,
+        row: usize,
+        byte: Option<u8>,
+    ) -> Result<(), Error> {
+        let byte = byte.map(u16::from);
+        SpreadVar::with_lookup(region, &self.lookup, row, SpreadWord::opt_new(byte))?;
+        SpreadVar::with_lookup(
+            region,
+            &self.lookup,
+            row + 1,
+            SpreadWord::opt_new(byte.map(|byte| byte << 8)),
+        )?;
+        Ok(())
+    }
+
+    fn assign_u64<F: FieldExt, A, AR>(
+        &self,
+        region: &mut Region<'_, F>,
+        annotation: A,
+        column: Column<Advice>,
+        row: usize,
+        value: Option<u64>,
+    ) -> Result<Cell, Error>
+    where
+        A: Fn() -> AR,
+        AR: Into<String>,
+    {
+        region.assign_advice(annotation, column, row, || {
+            value.map(F::from_u64).ok_or(Error::SynthesisError)
+        })
+    }
+}
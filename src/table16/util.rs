@@ -53,7 +53,7 @@ pub fn chop_u32(word: u32, lengths: &[u8]) -> Vec<u32> {
     for i in 0..lengths.len() {
         assert!(lengths[i] > 0);
         // lengths[i] bitstring of all 1's
-        let mask: u32 = (1 << lengths[i]) as u32 - 1;
+        let mask: u32 = u32::MAX >> (32 - lengths[i]);
         // Shift mask by bits already shifted
         let offset: u8 = lengths[0..i].iter().sum();
         let mask: u32 = mask << offset;
@@ -70,7 +70,7 @@ pub fn chop_u64(word: u64, lengths: &[u8]) -> Vec<u64> {
     for i in 0..lengths.len() {
         assert!(lengths[i] > 0);
         // lengths[i] bitstring of all 1's
-        let mask: u64 = (1u64 << lengths[i]) - 1;
+        let mask: u64 = u64::MAX >> (64 - lengths[i]);
         // Shift mask by bits already shifted
         let offset: u8 = lengths[0..i].iter().sum();
         let mask: u64 = mask << offset;
@@ -122,3 +122,183 @@ pub fn sum_with_carry(words: Vec<(Option<u16>, Option<u16>)>) -> (Option<u32>, O
 
     (sum, carry)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::{rngs::StdRng, Rng, SeedableRng};
+
+    // The number of random inputs each property is checked on, besides the edge cases.
+    const SAMPLES: usize = 10_000;
+
+    // The random inputs are drawn from a fixed seed, so that a failure can be reproduced.
+    fn rng() -> StdRng {
+        StdRng::seed_from_u64(0x5a256)
+    }
+
+    // Straightforward definitions of the helpers, bit by bit.
+
+    fn spread_bits(word: u16) -> u32 {
+        (0..16).fold(0, |spread, i| {
+            spread | (((word as u32 >> i) & 1) << (2 * i))
+        })
+    }
+
+    fn dense_bits(spread: u32, offset: u32) -> u16 {
+        (0..16).fold(0, |dense, i| {
+            dense | ((((spread >> (2 * i + offset)) & 1) as u16) << i)
+        })
+    }
+
+    fn bits(word: u32, offset: u8, len: u8) -> u32 {
+        (0..len).fold(0, |piece, i| piece | (((word >> (offset + i)) & 1) << i))
+    }
+
+    fn words_u16() -> impl Iterator<Item = u16> {
+        let mut rng = rng();
+        [0, 1, 0x5555, 0xaaaa, u16::MAX]
+            .iter()
+            .copied()
+            .chain((0..SAMPLES).map(move |_| rng.gen()))
+    }
+
+    fn words_u32() -> impl Iterator<Item = u32> {
+        let mut rng = rng();
+        [0, 1, MASK_EVEN_32, MASK_ODD_32, u32::MAX]
+            .iter()
+            .copied()
+            .chain((0..SAMPLES).map(move |_| rng.gen()))
+    }
+
+    #[test]
+    fn interleave_and_compress() {
+        for word in words_u16() {
+            let spread = interleave_u16_with_zeros(word);
+            assert_eq!(spread, spread_bits(word));
+            assert_eq!(spread & MASK_ODD_32, 0);
+            assert_eq!(compress_u32(spread), word);
+            assert_eq!(compress_u32(spread), dense_bits(spread, 0));
+        }
+    }
+
+    #[test]
+    #[should_panic]
+    fn compress_odd_bits() {
+        compress_u32(0b10);
+    }
+
+    #[test]
+    fn even_and_odd_bits() {
+        for word in words_u32() {
+            let (even, odd) = get_even_and_odd_bits_u32(word);
+            assert_eq!(even, dense_bits(word, 0));
+            assert_eq!(odd, dense_bits(word, 1));
+            assert_eq!(
+                interleave_u16_with_zeros(even) | (interleave_u16_with_zeros(odd) << 1),
+                word
+            );
+        }
+    }
+
+    #[test]
+    fn chop() {
+        let mut rng = rng();
+        for word in words_u32() {
+            // Split 32 bits into random lengths, including a single piece of 32 bits.
+            let mut lengths = vec![];
+            let mut remaining = 32;
+            while remaining > 0 {
+                let len = rng.gen_range(1..=remaining);
+                lengths.push(len);
+                remaining -= len;
+            }
+
+            let pieces = chop_u32(word, &lengths);
+            assert_eq!(pieces.len(), lengths.len());
+            let mut offset = 0;
+            for (piece, len) in pieces.iter().zip(lengths.iter()) {
+                assert_eq!(
+                    *piece,
+                    bits(word, offset, *len),
+                    "word = {:#x}, lengths = {:?}",
+                    word,
+                    lengths
+                );
+                offset += len;
+            }
+        }
+
+        // The chunk lengths used by the compression and message schedule gates
+        for lengths in [
+            &[2, 11, 3, 3, 3, 10][..],
+            &[3, 3, 2, 3, 14, 7],
+            &[3, 4, 11, 14],
+            &[3, 4, 3, 7, 1, 1, 13],
+            &[10, 7, 2, 13],
+            &[16, 16],
+            &[32],
+        ]
+        .iter()
+        {
+            let pieces = chop_u32(u32::MAX, lengths);
+            for (piece, len) in pieces.iter().zip(lengths.iter()) {
+                assert_eq!(*piece, u32::MAX >> (32 - len));
+            }
+        }
+
+        for word in words_u32() {
+            let wide = ((word as u64) << 32) | word.rotate_left(7) as u64;
+            assert_eq!(
+                chop_u64(wide, &[32, 32]),
+                vec![word.rotate_left(7) as u64, word as u64]
+            );
+            assert_eq!(chop_u64(wide, &[64]), vec![wide]);
+        }
+    }
+
+    #[test]
+    fn sum() {
+        let mut rng = rng();
+        for _ in 0..SAMPLES {
+            let len = rng.gen_range(1..=8);
+            let words: Vec<(u16, u16)> = (0..len).map(|_| (rng.gen(), rng.gen())).collect();
+            let expected: u64 = words
+                .iter()
+                .map(|(lo, hi)| *lo as u64 + ((*hi as u64) << 16))
+                .sum();
+
+            let (sum, carry) = sum_with_carry(
+                words
+                    .iter()
+                    .map(|(lo, hi)| (Some(*lo), Some(*hi)))
+                    .collect(),
+            );
+            assert_eq!(sum, Some(expected as u32), "words = {:?}", words);
+            assert_eq!(carry, Some(expected >> 32), "words = {:?}", words);
+            assert!(carry.unwrap() < len as u64, "words = {:?}", words);
+
+            // An unknown half makes the sum unknown.
+            let mut words: Vec<_> = words
+                .iter()
+                .map(|(lo, hi)| (Some(*lo), Some(*hi)))
+                .collect();
+            let idx = rng.gen_range(0..len);
+            if rng.gen() {
+                words[idx].0 = None;
+            } else {
+                words[idx].1 = None;
+            }
+            assert_eq!(
+                sum_with_carry(words.clone()),
+                (None, None),
+                "words = {:?}",
+                words
+            );
+        }
+
+        // The largest sum of the compression round, with a carry of 6
+        let (sum, carry) = sum_with_carry(vec![(Some(u16::MAX), Some(u16::MAX)); 7]);
+        assert_eq!(sum, Some(u32::MAX - 6));
+        assert_eq!(carry, Some(6));
+    }
+}
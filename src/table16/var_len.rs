@@ -0,0 +1,428 @@
+use super::{
+    super::{BLOCK_SIZE, DIGEST_SIZE},
+    Bit, BlockWord, Byte, CellValue32, CellValue8, DigestWord, Gate, Table16Assignment,
+};
+use std::convert::TryInto;
+
+use halo2::{
+    arithmetic::FieldExt,
+    circuit::{Layouter, Region},
+    plonk::{Advice, Column, ConstraintSystem, Error, Expression, Selector, VirtualCells},
+    poly::Rotation,
+};
+
+// The size of a block, in bytes
+const BLOCK_BYTES: usize = BLOCK_SIZE * 4;
+// The size of the message length at the end of the final block, in bytes
+const LEN_BYTES: usize = 8;
+// Rows before the first message byte, in which the message flag is fixed to 1. The
+// flag is queried as far back as the byte `LEN_BYTES + 1` before the first block.
+const HEADER_ROWS: usize = LEN_BYTES + 1;
+
+/// Configuration for hashing messages of a witnessed length.
+///
+/// A message of `len` bytes is padded in a single region with a row per byte of the
+/// padded message. Each row carries a flag `m_i = (i < len)`, which is boolean and
+/// non-increasing, so that the `0x80` marker byte sits where it drops from 1 to 0. Each
+/// block carries a bit that is set only for the final block of the padded message, i.e.
+/// the block in which the marker leaves room for the 64-bit message length. The bytes of
+/// the length in bits are witnessed in the header rows, and added to the last bytes of
+/// the final block.
+#[derive(Clone, Debug)]
+pub(super) struct VarLenConfig {
+    message_schedule: Column<Advice>,
+    extras: [Column<Advice>; 6],
+
+    /// Message flag gate for each byte
+    s_var_len: Selector,
+    /// Padding gate for each byte but the last `LEN_BYTES` of each block
+    s_pad: Selector,
+    /// Padding gate for the last `LEN_BYTES` bytes of each block
+    s_pad_len: Selector,
+    /// Final block gate for the first of the last `LEN_BYTES` bytes of each block
+    s_fin: Selector,
+    /// Gate decomposing the message length in bits into bytes
+    s_len: Selector,
+    /// Accumulation gate for selecting a digest
+    s_select: Selector,
+}
+
+impl<F: FieldExt> Table16Assignment<F> for VarLenConfig {}
+
+impl VarLenConfig {
+    pub(super) fn configure<F: FieldExt>(
+        meta: &mut ConstraintSystem<F>,
+        message_schedule: Column<Advice>,
+        extras: [Column<Advice>; 6],
+    ) -> Self {
+        let s_var_len = meta.selector();
+        let s_pad = meta.selector();
+        let s_pad_len = meta.selector();
+        let s_fin = meta.selector();
+        let s_len = meta.selector();
+        let s_select = meta.selector();
+
+        // Rename these here for ease of matching the gates to the layout.
+        let a_3 = extras[0];
+        let a_4 = extras[1];
+        let a_5 = message_schedule;
+        let a_6 = extras[2];
+        let a_7 = extras[3];
+        let a_8 = extras[4];
+
+        meta.create_gate("s_var_len", |meta| {
+            let s_var_len = meta.query_selector(s_var_len);
+            let m = meta.query_advice(a_4, Rotation::cur());
+            let m_prev = meta.query_advice(a_4, Rotation::prev());
+            let count = meta.query_advice(a_6, Rotation::cur());
+            let count_prev = meta.query_advice(a_6, Rotation::prev());
+
+            let m_check = Gate::range_check(m.clone(), 0, 1);
+            let monotone_check = m.clone() * (Expression::Constant(F::one()) - m_prev);
+            let count_check = count_prev + m - count;
+
+            vec![
+                ("m_check", m_check),
+                ("monotone_check", monotone_check),
+                ("count_check", count_check),
+            ]
+            .into_iter()
+            .map(move |(name, poly)| (name, s_var_len.clone() * poly))
+        });
+
+        // The padded byte is the message byte, the marker, or zero.
+        let padded_byte = |meta: &mut VirtualCells<'_, F>| {
+            let byte = meta.query_advice(a_3, Rotation::cur());
+            let m = meta.query_advice(a_4, Rotation::cur());
+            let m_prev = meta.query_advice(a_4, Rotation::prev());
+
+            m.clone() * byte + (m_prev - m) * F::from_u64(0x80)
+        };
+
+        meta.create_gate("s_pad", |meta| {
+            let s_pad = meta.query_selector(s_pad);
+            let padded = meta.query_advice(a_5, Rotation::cur());
+
+            vec![("padded_check", s_pad * (padded_byte(meta) - padded))]
+        });
+
+        // In the final block, a byte of the message length in bits is added.
+        meta.create_gate("s_pad_len", |meta| {
+            let s_pad_len = meta.query_selector(s_pad_len);
+            let padded = meta.query_advice(a_5, Rotation::cur());
+            let len_byte = meta.query_advice(a_7, Rotation::cur());
+            let fin = meta.query_advice(a_8, Rotation::cur());
+
+            vec![(
+                "padded_check",
+                s_pad_len * (padded_byte(meta) + fin * len_byte - padded),
+            )]
+        });
+
+        // fin for block b is the sum of the markers of bytes [64b - 8, 64b + 56), which
+        // telescopes to m_{64b - 9} - m_{64b + 55}.
+        meta.create_gate("s_fin", |meta| {
+            let s_fin = meta.query_selector(s_fin);
+            let m_start = meta.query_advice(a_4, Rotation(-(BLOCK_BYTES as i32) - 1));
+            let m_end = meta.query_advice(a_4, Rotation::prev());
+            let fin = meta.query_advice(a_8, Rotation::cur());
+
+            vec![("fin_check", s_fin * (m_start - m_end - fin))]
+        });
+
+        // The big-endian bytes of the message length in bits, in the rows above, recompose
+        // to 8 * len.
+        meta.create_gate("s_len", |meta| {
+            let s_len = meta.query_selector(s_len);
+            let len = meta.query_advice(a_8, Rotation::cur());
+            let len_bits = (0..LEN_BYTES).fold(Expression::Constant(F::zero()), |acc, idx| {
+                let byte = meta.query_advice(a_7, Rotation(idx as i32 + 1 - LEN_BYTES as i32));
+                acc * F::from_u64(1 << 8) + byte
+            });
+
+            vec![("len_check", s_len * (len_bits - len * F::from_u64(8)))]
+        });
+
+        meta.create_gate("s_select", |meta| {
+            let s_select = meta.query_selector(s_select);
+            let bit = meta.query_advice(a_3, Rotation::cur());
+            let word = meta.query_advice(a_4, Rotation::cur());
+            let acc = meta.query_advice(a_6, Rotation::cur());
+            let acc_prev = meta.query_advice(a_6, Rotation::prev());
+
+            vec![("select_check", s_select * (acc_prev + bit * word - acc))]
+        });
+
+        VarLenConfig {
+            message_schedule,
+            extras,
+            s_var_len,
+            s_pad,
+            s_pad_len,
+            s_fin,
+            s_len,
+            s_select,
+        }
+    }
+
+    /// Pads a message of `len` bytes to `max_blocks` blocks. Returns the bytes of the
+    /// padded blocks, and for each block a bit that is set only for the final block.
+    /// Returns an error if `data` does not fit in `max_blocks` blocks.
+    pub(super) fn pad<F: FieldExt>(
+        &self,
+        layouter: &mut impl Layouter<F>,
+        max_blocks: usize,
+        len: BlockWord,
+        data: &[Byte],
+    ) -> Result<(Vec<Byte>, Vec<Bit>), Error> {
+        let num_bytes = max_blocks * BLOCK_BYTES;
+        if data.len() > num_bytes {
+            return Err(Error::SynthesisError);
+        }
+
+        let mut padded = Vec::with_capacity(num_bytes);
+        let mut bits = Vec::with_capacity(max_blocks);
+
+        layouter.assign_region(
+            || "pad variable-length message",
+            |mut region| {
+                padded = Vec::with_capacity(num_bytes);
+                bits = Vec::with_capacity(max_blocks);
+                self.assign_pad(&mut region, num_bytes, len, data, &mut padded, &mut bits)
+            },
+        )?;
+
+        Ok((padded, bits))
+    }
+
+    fn assign_pad<F: FieldExt>(
+        &self,
+        region: &mut Region<'_, F>,
+        num_bytes: usize,
+        len: BlockWord,
+        data: &[Byte],
+        padded_bytes: &mut Vec<Byte>,
+        bits: &mut Vec<Bit>,
+    ) -> Result<(), Error> {
+        let a_3 = self.extras[0];
+        let a_4 = self.extras[1];
+        let a_5 = self.message_schedule;
+        let a_6 = self.extras[2];
+        let a_7 = self.extras[3];
+        let a_8 = self.extras[4];
+
+        // The message flags of the header rows are fixed to 1, and the count starts at 0.
+        for row in 0..HEADER_ROWS {
+            region.assign_advice_from_constant(|| "m", a_4, row, F::one())?;
+        }
+        region.assign_advice_from_constant(|| "count", a_6, HEADER_ROWS - 1, F::zero())?;
+        self.s_len.enable(region, HEADER_ROWS - 1)?;
+        let len_var = self.assign_block_word(region, || "len", a_8, HEADER_ROWS - 1, len)?;
+        let len = CellValue32::new(len_var, len.value());
+
+        // The bytes of the message length in bits, big-endian.
+        let len_bytes = len.value.map(|len| (len as u64 * 8).to_be_bytes());
+        let len_byte_vars = (0..LEN_BYTES)
+            .map(|idx| {
+                let value = len_bytes.map(|bytes| bytes[idx]);
+                let var = region.assign_advice(
+                    || format!("len byte {}", idx),
+                    a_7,
+                    HEADER_ROWS - LEN_BYTES + idx,
+                    || {
+                        value
+                            .map(|value| F::from_u64(value as u64))
+                            .ok_or(Error::SynthesisError)
+                    },
+                )?;
+                Ok(CellValue8::new(var, value))
+            })
+            .collect::<Result<Vec<_>, Error>>()?;
+
+        let mut fin = None;
+        for idx in 0..num_bytes {
+            let row = HEADER_ROWS + idx;
+            let block_idx = idx / BLOCK_BYTES;
+            let pos = idx % BLOCK_BYTES;
+            self.s_var_len.enable(region, row)?;
+
+            // Bytes beyond the provided data are ignored, so are fixed to zero.
+            let byte = data.get(idx).copied().unwrap_or(Byte::Constant(0));
+            match byte {
+                Byte::Assigned(byte) => {
+                    self.assign_and_constrain(region, || format!("byte {}", idx), a_3, row, byte)?;
+                }
+                Byte::Constant(value) => {
+                    region.assign_advice_from_constant(
+                        || format!("byte {}", idx),
+                        a_3,
+                        row,
+                        F::from_u64(value as u64),
+                    )?;
+                }
+            }
+
+            let m = len.value.map(|len| idx < len as usize);
+            let m_var = region.assign_advice(
+                || "m",
+                a_4,
+                row,
+                || {
+                    m.map(|m| F::from_u64(m as u64))
+                        .ok_or(Error::SynthesisError)
+                },
+            )?;
+
+            let count = len.value.map(|len| std::cmp::min(idx + 1, len as usize));
+            let count_var = region.assign_advice(
+                || "count",
+                a_6,
+                row,
+                || {
+                    count
+                        .map(|count| F::from_u64(count as u64))
+                        .ok_or(Error::SynthesisError)
+                },
+            )?;
+
+            // The final block needs room for the marker and the message length.
+            let is_fin = len
+                .value
+                .map(|len| (len as usize + LEN_BYTES) / BLOCK_BYTES == block_idx);
+            let padded =
+                len.value
+                    .zip(byte.value())
+                    .zip(len_bytes)
+                    .map(|((len, byte), len_bytes)| {
+                        let len = len as usize;
+                        if idx < len {
+                            byte
+                        } else if idx == len {
+                            0x80
+                        } else if pos >= BLOCK_BYTES - LEN_BYTES
+                            && (len + LEN_BYTES) / BLOCK_BYTES == block_idx
+                        {
+                            len_bytes[pos - (BLOCK_BYTES - LEN_BYTES)]
+                        } else {
+                            0
+                        }
+                    });
+            let padded_var = region.assign_advice(
+                || "padded",
+                a_5,
+                row,
+                || {
+                    padded
+                        .map(|padded| F::from_u64(padded as u64))
+                        .ok_or(Error::SynthesisError)
+                },
+            )?;
+            padded_bytes.push(Byte::Assigned(CellValue8::new(padded_var, padded)));
+
+            if pos >= BLOCK_BYTES - LEN_BYTES {
+                self.s_pad_len.enable(region, row)?;
+                let len_byte = len_byte_vars[pos - (BLOCK_BYTES - LEN_BYTES)];
+                self.assign_and_constrain(region, || "len byte", a_7, row, len_byte)?;
+
+                // fin is computed for the first byte of the length, and copied to the
+                // others.
+                if pos == BLOCK_BYTES - LEN_BYTES {
+                    self.s_fin.enable(region, row)?;
+                    let fin_var = region.assign_advice(
+                        || "fin",
+                        a_8,
+                        row,
+                        || {
+                            is_fin
+                                .map(|fin| F::from_u64(fin as u64))
+                                .ok_or(Error::SynthesisError)
+                        },
+                    )?;
+                    let bit = Bit::new(fin_var, is_fin);
+                    bits.push(bit);
+                    fin = Some(bit);
+                } else {
+                    let fin = fin.expect("fin is assigned for the first byte of the length");
+                    self.assign_and_constrain(region, || "fin", a_8, row, fin)?;
+                }
+            } else {
+                self.s_pad.enable(region, row)?;
+            }
+
+            // The message must leave room for the marker and the message length.
+            if idx == num_bytes - LEN_BYTES - 1 {
+                region.constrain_constant(m_var, F::zero())?;
+            }
+            // The message flags must sum to the message length.
+            if idx == num_bytes - 1 {
+                region.constrain_equal(count_var, len.var)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Selects the digest whose bit is set, given bits of which exactly one is set.
+    pub(super) fn select_digest<F: FieldExt>(
+        &self,
+        layouter: &mut impl Layouter<F>,
+        bits: &[Bit],
+        digests: &[[DigestWord; DIGEST_SIZE]],
+    ) -> Result<[DigestWord; DIGEST_SIZE], Error> {
+        assert_eq!(bits.len(), digests.len());
+
+        let digest = layouter.assign_region(
+            || "select digest",
+            |mut region| {
+                (0..DIGEST_SIZE)
+                    .map(|word_idx| self.assign_select(&mut region, word_idx, bits, digests))
+                    .collect::<Result<Vec<_>, Error>>()
+            },
+        )?;
+
+        Ok(digest.try_into().unwrap())
+    }
+
+    // Accumulate bit_b * word_b over the digests, starting from zero.
+    fn assign_select<F: FieldExt>(
+        &self,
+        region: &mut Region<'_, F>,
+        word_idx: usize,
+        bits: &[Bit],
+        digests: &[[DigestWord; DIGEST_SIZE]],
+    ) -> Result<DigestWord, Error> {
+        let a_3 = self.extras[0];
+        let a_4 = self.extras[1];
+        let a_6 = self.extras[2];
+
+        let start_row = word_idx * (bits.len() + 1);
+        let mut acc_var =
+            region.assign_advice_from_constant(|| "acc", a_6, start_row, F::zero())?;
+        let mut acc = Some(0);
+
+        for (idx, (bit, digest)) in bits.iter().zip(digests.iter()).enumerate() {
+            let row = start_row + idx + 1;
+            self.s_select.enable(region, row)?;
+
+            self.assign_and_constrain(region, || "bit", a_3, row, *bit)?;
+            let word = digest[word_idx];
+            self.assign_and_constrain(region, || "word", a_4, row, word)?;
+
+            acc = acc
+                .zip(bit.value)
+                .zip(word.value())
+                .map(|((acc, bit), word)| if bit { word } else { acc });
+            acc_var = region.assign_advice(
+                || "acc",
+                a_6,
+                row,
+                || {
+                    acc.map(|acc| F::from_u64(acc as u64))
+                        .ok_or(Error::SynthesisError)
+                },
+            )?;
+        }
+
+        Ok(DigestWord::new(acc_var, acc))
+    }
+}
@@ -0,0 +1,193 @@
+use super::{
+    util::*, BlockWord, CellValue32, SpreadInputs, SpreadVar, SpreadWord, Table16Assignment,
+};
+
+use halo2::{
+    arithmetic::FieldExt,
+    circuit::{Layouter, Region},
+    plonk::{Advice, Column, ConstraintSystem, Error, Fixed, Selector},
+    poly::Rotation,
+};
+
+// Rows taken by the XOR of each word
+const XOR_ROWS: usize = 6;
+
+/// Configuration for XORing words with a constant, such as the HMAC key with its inner
+/// and outer pads.
+///
+/// Each word takes six rows. Its 16-bit halves are looked up in the spread table; adding
+/// the spread form of the constant's halves gives sums whose even bits are the XOR of the
+/// word and the constant, and whose odd bits are their AND.
+///
+/// | Row | $a_0$ | $a_1$ | $a_2$ | $a_3$ | $s_{xor}$ | $c_{xor}$ |
+/// |-----|-------|-------|-------|-------|-----------|-----------|
+/// |  0  |  tag  | W^{lo} | spread(W^{lo}) | W | 1 | spread(C^{lo}) |
+/// |  1  |  tag  | W^{hi} | spread(W^{hi}) | R | 0 | spread(C^{hi}) |
+/// |  2  |  tag  | R_0^{even} | spread(R_0^{even}) | | | |
+/// |  3  |  tag  | R_0^{odd} | spread(R_0^{odd}) | | | |
+/// |  4  |  tag  | R_1^{even} | spread(R_1^{even}) | | | |
+/// |  5  |  tag  | R_1^{odd} | spread(R_1^{odd}) | | | |
+#[derive(Clone, Debug)]
+pub(super) struct XorConfig {
+    lookup: SpreadInputs,
+    a_3: Column<Advice>,
+
+    /// XOR gate for each word
+    s_xor: Selector,
+    /// Spread forms of the halves of the constant
+    c_xor: Column<Fixed>,
+}
+
+impl<F: FieldExt> Table16Assignment<F> for XorConfig {}
+
+impl XorConfig {
+    pub(super) fn configure<F: FieldExt>(
+        meta: &mut ConstraintSystem<F>,
+        lookup: SpreadInputs,
+        a_3: Column<Advice>,
+    ) -> Self {
+        let s_xor = meta.selector();
+        let c_xor = meta.fixed_column();
+
+        meta.create_gate("s_xor", |meta| {
+            let s_xor = meta.query_selector(s_xor);
+            let c_lo = meta.query_fixed(c_xor, Rotation::cur());
+            let c_hi = meta.query_fixed(c_xor, Rotation::next());
+            let word = meta.query_advice(a_3, Rotation::cur());
+            let r = meta.query_advice(a_3, Rotation::next());
+            let word_lo = meta.query_advice(lookup.dense, Rotation::cur());
+            let spread_word_lo = meta.query_advice(lookup.spread, Rotation::cur());
+            let word_hi = meta.query_advice(lookup.dense, Rotation::next());
+            let spread_word_hi = meta.query_advice(lookup.spread, Rotation::next());
+            let r_0_even = meta.query_advice(lookup.dense, Rotation(2));
+            let spread_r_0_even = meta.query_advice(lookup.spread, Rotation(2));
+            let spread_r_0_odd = meta.query_advice(lookup.spread, Rotation(3));
+            let r_1_even = meta.query_advice(lookup.dense, Rotation(4));
+            let spread_r_1_even = meta.query_advice(lookup.spread, Rotation(4));
+            let spread_r_1_odd = meta.query_advice(lookup.spread, Rotation(5));
+
+            let word_check = word_lo + word_hi * F::from_u64(1 << 16) - word;
+            let lo_check =
+                spread_word_lo + c_lo - spread_r_0_even - spread_r_0_odd * F::from_u64(2);
+            let hi_check =
+                spread_word_hi + c_hi - spread_r_1_even - spread_r_1_odd * F::from_u64(2);
+            let r_check = r_0_even + r_1_even * F::from_u64(1 << 16) - r;
+
+            vec![
+                ("word_check", word_check),
+                ("lo_check", lo_check),
+                ("hi_check", hi_check),
+                ("r_check", r_check),
+            ]
+            .into_iter()
+            .map(move |(name, poly)| (name, s_xor.clone() * poly))
+        });
+
+        XorConfig {
+            lookup,
+            a_3,
+            s_xor,
+            c_xor,
+        }
+    }
+
+    /// Returns the XOR of each of the given words with `constant`. The output words are
+    /// range-checked to 32 bits, as are any input words that are not constant.
+    pub(super) fn xor_constant<F: FieldExt>(
+        &self,
+        layouter: &mut impl Layouter<F>,
+        words: &[BlockWord],
+        constant: u32,
+    ) -> Result<Vec<BlockWord>, Error> {
+        layouter.assign_region(
+            || format!("xor {:#010x}", constant),
+            |mut region| {
+                let mut row = 0;
+                words
+                    .iter()
+                    .map(|word| match word {
+                        // Constant words need no constraints.
+                        BlockWord::Constant(word) => Ok(BlockWord::Constant(word ^ constant)),
+                        _ => {
+                            let xor = self.assign_xor(&mut region, row, *word, constant);
+                            row += XOR_ROWS;
+                            xor
+                        }
+                    })
+                    .collect()
+            },
+        )
+    }
+
+    fn assign_xor<F: FieldExt>(
+        &self,
+        region: &mut Region<'_, F>,
+        row: usize,
+        word: BlockWord,
+        constant: u32,
+    ) -> Result<BlockWord, Error> {
+        self.s_xor.enable(region, row)?;
+        let c_lo = interleave_u16_with_zeros(constant as u16);
+        let c_hi = interleave_u16_with_zeros((constant >> 16) as u16);
+        region.assign_fixed(|| "c_xor", self.c_xor, row, || Ok(F::from_u64(c_lo as u64)))?;
+        region.assign_fixed(
+            || "c_xor",
+            self.c_xor,
+            row + 1,
+            || Ok(F::from_u64(c_hi as u64)),
+        )?;
+
+        // Assign W and look up its halves
+        self.assign_block_word(region, || "W", self.a_3, row, word)?;
+        let value = word.value();
+        SpreadVar::with_lookup(
+            region,
+            &self.lookup,
+            row,
+            SpreadWord::opt_new(value.map(|value| value as u16)),
+        )?;
+        SpreadVar::with_lookup(
+            region,
+            &self.lookup,
+            row + 1,
+            SpreadWord::opt_new(value.map(|value| (value >> 16) as u16)),
+        )?;
+
+        // Look up the even and odd bits of the sums of spread halves
+        let r_0 = value
+            .map(|value| get_even_and_odd_bits_u32(interleave_u16_with_zeros(value as u16) + c_lo));
+        let r_1 = value.map(|value| {
+            get_even_and_odd_bits_u32(interleave_u16_with_zeros((value >> 16) as u16) + c_hi)
+        });
+        for (offset, half) in [
+            r_0.map(|(even, _)| even),
+            r_0.map(|(_, odd)| odd),
+            r_1.map(|(even, _)| even),
+            r_1.map(|(_, odd)| odd),
+        ]
+        .iter()
+        .enumerate()
+        {
+            SpreadVar::with_lookup(
+                region,
+                &self.lookup,
+                row + 2 + offset,
+                SpreadWord::opt_new(*half),
+            )?;
+        }
+
+        // Assign R, the XOR of W and the constant
+        let r = value.map(|value| value ^ constant);
+        let r_var = region.assign_advice(
+            || "R",
+            self.a_3,
+            row + 1,
+            || {
+                r.map(|r| F::from_u64(r as u64))
+                    .ok_or(Error::SynthesisError)
+            },
+        )?;
+
+        Ok(BlockWord::Assigned(CellValue32::new(r_var, r)))
+    }
+}